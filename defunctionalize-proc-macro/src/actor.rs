@@ -0,0 +1,80 @@
+use super::{Case, Signature};
+
+use proc_macro2::TokenStream;
+use proc_macro_error::abort;
+use quote::quote;
+use syn::{spanned::Spanned, Ident, ReturnType, Visibility};
+
+/// Generates a handle to an actor which handles the enum's commands, with an async method for each
+/// function that sends its command and waits for the output.
+pub fn actor_impl(
+    visibility: &Visibility,
+    enum_name: &Ident,
+    handle_name: &Ident,
+    cases: &[Case],
+    signature: &Signature,
+    krate: &TokenStream,
+) -> TokenStream {
+    if !signature.generics.params.is_empty() {
+        abort!(
+            signature.generics.span(),
+            "`actor` cannot be used with a generic signature"
+        );
+    }
+
+    let actor = quote!(#krate::actor);
+    let output_type = match &signature.output {
+        ReturnType::Default => quote!(()),
+        ReturnType::Type(.., ty) => quote!(#ty),
+    };
+
+    let methods = cases.iter().map(|case| {
+        let attrs = case
+            .attrs
+            .iter()
+            .filter(|attr| attr.path.is_ident("cfg") || attr.path.is_ident("doc"));
+        let method = case.ident;
+        let name = &case.name;
+        let params = case.captures.iter().map(|capture| {
+            let ident = &capture.ident;
+            let ty = &capture.ty;
+            quote!(#ident: #ty)
+        });
+        let command = if case.captures.is_empty() {
+            quote!(#enum_name::#name)
+        } else {
+            let idents = case.captures.iter().map(|capture| &capture.ident);
+            quote!(#enum_name::#name(#(#idents),*))
+        };
+        quote! {
+            #(#attrs)*
+            #visibility async fn #method(&self, #(#params),*) -> ::core::result::Result<#output_type, #actor::Closed> {
+                self.0.send(#command).await
+            }
+        }
+    });
+
+    quote! {
+        /// A handle to an actor, which sends it commands and waits for their outputs.
+        #visibility struct #handle_name(#actor::Sender<#enum_name, #output_type>);
+
+        impl ::core::clone::Clone for #handle_name {
+            fn clone(&self) -> Self {
+                #handle_name(::core::clone::Clone::clone(&self.0))
+            }
+        }
+
+        impl #handle_name {
+            #(#methods)*
+        }
+
+        impl #enum_name {
+            /// Creates a handle to an actor, and the mailbox from which the actor receives the
+            /// commands sent by the handle.
+            #visibility fn actor(buffer: usize) -> (#handle_name, #actor::Mailbox<#enum_name, #output_type>) {
+                let (sender, mailbox) = #actor::channel(buffer);
+                (#handle_name(sender), mailbox)
+            }
+        }
+    }
+}
@@ -0,0 +1,109 @@
+use super::Signature;
+
+use proc_macro2::{Span, TokenStream};
+use quote::quote;
+use syn::{GenericParam, Generics, Ident, Lifetime, LifetimeDef, Visibility};
+
+/// A struct with a public field for each argument of the signature, with the generics it is
+/// declared with, and the signature's output type, whose lifetimes are named to match.
+fn args_struct(
+    visibility: &Visibility,
+    args_name: &Ident,
+    signature: &Signature,
+) -> (TokenStream, Generics, TokenStream) {
+    let lifetime = Lifetime::new("'args", Span::call_site());
+    let (types, output_type, named) = signature.with_named_lifetimes(&lifetime);
+    let names = signature.inputs.iter().map(|arg| &arg.ident);
+
+    let mut generics = signature.generics.clone();
+    if named {
+        generics
+            .params
+            .insert(0, GenericParam::Lifetime(LifetimeDef::new(lifetime)));
+    }
+    let (impl_generics, .., where_clause) = generics.split_for_impl();
+
+    let item = quote! {
+        /// The arguments of the signature, by name.
+        #visibility struct #args_name #impl_generics #where_clause {
+            #(pub #names: #types,)*
+        }
+    };
+    (item, generics, output_type)
+}
+
+/// Generates a struct with a field for each argument of the signature, and the `call_with`
+/// method which takes the arguments as that struct, so they are named where it is called.
+pub fn args_impl(
+    visibility: &Visibility,
+    enum_name: &Ident,
+    args_name: &Ident,
+    signature: &Signature,
+) -> TokenStream {
+    let (args_struct, generics, output_type) = args_struct(visibility, args_name, signature);
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+    let names = signature
+        .inputs
+        .iter()
+        .map(|arg| &arg.ident)
+        .collect::<Vec<_>>();
+
+    quote! {
+        #args_struct
+
+        impl #enum_name {
+            /// Calls the command with its arguments given by name.
+            #visibility fn call_with #impl_generics (self, args: #args_name #ty_generics) -> #output_type #where_clause {
+                let #args_name { #(#names),* } = args;
+                #enum_name::call(self, #(#names),*)
+            }
+        }
+    }
+}
+
+/// Generates a struct with a field for each argument of the signature, and implements `DeFn`
+/// taking it in place of the tuple of arguments. When commands can be called by reference,
+/// `ref_call_body` is the body of `DeFn` for a reference to the enum.
+pub fn input_struct_impl(
+    visibility: &Visibility,
+    enum_name: &Ident,
+    input_name: &Ident,
+    signature: &Signature,
+    krate: &TokenStream,
+    constness: Option<&TokenStream>,
+    ref_call_body: Option<&TokenStream>,
+) -> TokenStream {
+    let (input_struct, generics, output_type) = args_struct(visibility, input_name, signature);
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+    let names = signature
+        .inputs
+        .iter()
+        .map(|arg| &arg.ident)
+        .collect::<Vec<_>>();
+
+    let ref_defn_impl = ref_call_body.map(|ref_call_body| {
+        quote! {
+            impl #impl_generics #constness #krate::DeFn<#input_name #ty_generics> for &#enum_name #where_clause {
+                type Output = #output_type;
+
+                fn call(self, #input_name { #(#names),* }: #input_name #ty_generics) -> #output_type {
+                    #ref_call_body
+                }
+            }
+        }
+    });
+
+    quote! {
+        #input_struct
+
+        impl #impl_generics #constness #krate::DeFn<#input_name #ty_generics> for #enum_name #where_clause {
+            type Output = #output_type;
+
+            fn call(self, #input_name { #(#names),* }: #input_name #ty_generics) -> #output_type {
+                #enum_name::call(self, #(#names),*)
+            }
+        }
+
+        #ref_defn_impl
+    }
+}
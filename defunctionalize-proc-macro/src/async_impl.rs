@@ -0,0 +1,195 @@
+use super::{Case, Signature};
+
+use proc_macro2::{Span, TokenStream, TokenTree};
+use proc_macro_error::{abort, abort_call_site};
+use quote::{format_ident, quote, quote_spanned};
+use syn::{spanned::Spanned, Ident, Lifetime, ReturnType, Visibility};
+
+/// The options which need `call` to return the output of the signature, rather than a future.
+pub fn check_options(unsupported: &[(&str, bool)]) {
+    if let Some((option, ..)) = unsupported.iter().find(|(.., used)| *used) {
+        abort_call_site!("`{}` cannot be used with an async signature", option);
+    }
+}
+
+/// The predicates of a case's `cfg` attributes, under which its function exists.
+fn cfg_predicates(case: &Case) -> Vec<TokenStream> {
+    case.cfgs()
+        .filter_map(|attr| match attr.tokens.clone().into_iter().next() {
+            Some(TokenTree::Group(group)) => Some(group.stream()),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Generates the `call` method for an async signature, which returns a future without
+/// allocating. The future is an enum with a variant for each function, holding the future
+/// returned by that function, so its type parameters are the `impl Future` types of the
+/// functions.
+pub fn async_impl(
+    visibility: &Visibility,
+    enum_name: &Ident,
+    future_name: &Ident,
+    cases: &[Case],
+    signature: &Signature,
+    send: Option<&TokenStream>,
+) -> TokenStream {
+    if !signature.generics.params.is_empty() {
+        abort!(
+            signature.generics.span(),
+            "an async signature cannot be generic"
+        );
+    }
+
+    let lifetime = Lifetime::new("'call", Span::call_site());
+    let (types, output_type, named) = signature.with_named_lifetimes(&lifetime);
+    let (lifetime_param, lifetime_bound) = if named {
+        (Some(quote!(<#lifetime>)), Some(quote!(+ #lifetime)))
+    } else {
+        (None, None)
+    };
+    let inputs = signature.inputs.iter().zip(&types).map(|(arg, ty)| {
+        let ident = &arg.ident;
+        quote!(#ident: #ty)
+    });
+    let input_names = signature
+        .inputs
+        .iter()
+        .map(|arg| &arg.ident)
+        .collect::<Vec<_>>();
+
+    let params = (0..cases.len())
+        .map(|index| format_ident!("F{}", index))
+        .collect::<Vec<_>>();
+    let names = cases.iter().map(|case| &case.name).collect::<Vec<_>>();
+    let futures = cases
+        .iter()
+        .map(|_| quote!(impl ::core::future::Future<Output = #output_type> #lifetime_bound));
+
+    let arms = cases.iter().map(|case| {
+        let cfgs = case.cfgs();
+        let pattern = case.pattern();
+        let name = &case.name;
+        let call = case.call(&input_names);
+        let future = quote_spanned!(case.output_span()=> expect_output(#call));
+        quote! {
+            #(#cfgs)*
+            #enum_name::#pattern => #future_name::#name(#future),
+        }
+    });
+
+    // The future of each function is passed through this function, so that a mismatched output is
+    // reported at the function's return type, before it is reported where the future is returned.
+    let expect_output = if cases.is_empty() {
+        None
+    } else {
+        let lifetime_arg = if named {
+            Some(quote!(#lifetime,))
+        } else {
+            None
+        };
+        Some(quote! {
+            #[allow(dead_code)]
+            fn expect_output<#lifetime_arg F>(future: F) -> F
+            where
+                F: ::core::future::Future<Output = #output_type>,
+            {
+                future
+            }
+        })
+    };
+
+    // The future of a function which is configured out is never created, but its type must still
+    // be inferred.
+    let configured_out = cases.iter().filter_map(|case| {
+        let predicates = cfg_predicates(case);
+        if predicates.is_empty() {
+            return None;
+        }
+        let name = &case.name;
+        Some(quote! {
+            #[cfg(not(all(#(#predicates),*)))]
+            if false {
+                return #future_name::#name(::core::future::pending());
+            }
+        })
+    });
+
+    // The output is a parameter of the implementation, so that it can name the lifetime of the
+    // arguments. Without any functions, there is nothing to constrain it.
+    let (output_param, output) = if cases.is_empty() {
+        (None, quote!(::core::convert::Infallible))
+    } else {
+        (Some(quote!(__Output,)), quote!(__Output))
+    };
+
+    // The futures cannot be named, so whether they can be sent is known only when they are
+    // required to be.
+    let defn_async_impl = send.map(|krate| {
+        let input_types = signature
+            .inputs
+            .iter()
+            .map(|arg| &arg.ty)
+            .collect::<Vec<_>>();
+        let output_type = match &signature.output {
+            ReturnType::Default => quote!(()),
+            ReturnType::Type(.., ty) => quote!(#ty),
+        };
+        // Without any functions, the future of `call` has no output to give.
+        let call = if cases.is_empty() {
+            quote!(async move { match self {} })
+        } else {
+            quote!(#enum_name::call(self, #(#input_names),*))
+        };
+        quote! {
+            impl #krate::DeFnAsync<(#(#input_types),*)> for #enum_name {
+                type Output = #output_type;
+
+                fn call_async(self, (#(#input_names),*): (#(#input_types),*)) -> impl ::core::future::Future<Output = Self::Output> + ::core::marker::Send {
+                    #call
+                }
+            }
+        }
+    });
+
+    quote! {
+        /// The future returned by calling a command, which is the future of its function.
+        #visibility enum #future_name<#(#params),*> {
+            #(#names(#params),)*
+        }
+
+        impl<#output_param #(#params),*> ::core::future::Future for #future_name<#(#params),*>
+        where
+            #(#params: ::core::future::Future<Output = __Output>,)*
+        {
+            type Output = #output;
+
+            fn poll(
+                self: ::core::pin::Pin<&mut Self>,
+                cx: &mut ::core::task::Context<'_>,
+            ) -> ::core::task::Poll<Self::Output> {
+                // Safety: the future of the function is never moved out of the enum, so it
+                // remains pinned.
+                unsafe {
+                    match *::core::pin::Pin::get_unchecked_mut(self) {
+                        #(#future_name::#names(ref mut future) => {
+                            ::core::future::Future::poll(::core::pin::Pin::new_unchecked(future), cx)
+                        })*
+                    }
+                }
+            }
+        }
+
+        impl #enum_name {
+            #visibility fn call #lifetime_param (self, #(#inputs),*) -> #future_name<#(#futures),*> {
+                #expect_output
+                #(#configured_out)*
+                match self {
+                    #(#arms)*
+                }
+            }
+        }
+
+        #defn_async_impl
+    }
+}
@@ -0,0 +1,568 @@
+use super::{
+    case_options::ArgOptions,
+    imports::Imports,
+    naming,
+    options::{Extra, IncludedFn},
+    CaseOptions, Signature,
+};
+
+use proc_macro2::{Group, Span, TokenStream, TokenTree};
+use proc_macro_error::{abort_if_dirty, diagnostic, Diagnostic, Level::Error};
+use quote::{format_ident, quote, quote_spanned, ToTokens};
+use std::borrow::Cow;
+use std::collections::HashMap;
+use syn::{
+    ext::IdentExt, parse_quote, spanned::Spanned, Attribute, FnArg, Generics, Ident, ItemFn,
+    ItemMod, Lit, Meta, MetaNameValue, Pat, PatType, ReturnType, Type,
+};
+
+pub struct Case<'a> {
+    pub ident: &'a Ident,
+    /// The path by which the function is called from beside the module.
+    pub path: TokenStream,
+    pub attrs: Vec<Attribute>,
+    pub options: CaseOptions,
+    pub name: Ident,
+    pub captures: Vec<Capture<'a>>,
+    /// Whether the function is known to be a `const fn`.
+    pub constness: bool,
+    /// The number of the signature's arguments which the function takes, when it leaves out the
+    /// rest.
+    pub shared: Option<usize>,
+    /// The spans of the types of the arguments which the function takes from the signature, where
+    /// the arguments are passed to it, so that mismatched types are reported there.
+    pub input_spans: Vec<Span>,
+    /// The span of the function's return type, where its output is returned, so that a mismatched
+    /// return type is reported there.
+    pub output_span: Option<Span>,
+}
+
+pub struct Capture<'a> {
+    pub ident: Cow<'a, Ident>,
+    pub ty: Cow<'a, Type>,
+    pub options: ArgOptions,
+}
+
+/// Removes the attributes of a function that should apply to its variant instead. The `cfg` and
+/// `doc` attributes remain on the function as well, while `cfg_attr` is moved to the variant. The
+/// `allow` and `expect` attributes remain on the function, and are copied to the variant as
+/// `allow`, since the variant need not trigger the expected lint. The `defunctionalize` helper
+/// attributes are parsed and removed.
+pub fn take_attrs(attrs: &mut Vec<Attribute>) -> syn::Result<(Vec<Attribute>, CaseOptions)> {
+    let options = CaseOptions::take(attrs)?;
+    let forwarded = attrs
+        .iter()
+        .filter_map(|attr| {
+            if attr.path.is_ident("expect") {
+                let tokens = &attr.tokens;
+                Some(parse_quote!(#[allow #tokens]))
+            } else if attr.path.is_ident("cfg")
+                || attr.path.is_ident("cfg_attr")
+                || attr.path.is_ident("doc")
+                || attr.path.is_ident("allow")
+            {
+                Some(attr.clone())
+            } else {
+                None
+            }
+        })
+        .collect();
+    attrs.retain(|attr| !attr.path.is_ident("cfg_attr"));
+    Ok((forwarded, options))
+}
+
+/// Removes the attributes of a function that should apply to its variant instead, as by
+/// `take_attrs`, and the `defunctionalize` helper attributes of its arguments.
+pub fn take_function_attrs(function: &mut ItemFn) -> syn::Result<(Vec<Attribute>, CaseOptions)> {
+    let (attrs, mut options) = take_attrs(&mut function.attrs)?;
+    options.args = function
+        .sig
+        .inputs
+        .iter_mut()
+        .map(|arg| match arg {
+            FnArg::Receiver(arg) => ArgOptions::take(&mut arg.attrs),
+            FnArg::Typed(arg) => ArgOptions::take(&mut arg.attrs),
+        })
+        .collect::<syn::Result<_>>()?;
+    Ok((attrs, options))
+}
+
+/// Reports an error for each case which has the same name as an earlier case, which can happen
+/// when functions are merged or included from elsewhere.
+pub fn check_duplicates(cases: &[Case]) {
+    let mut seen = HashMap::new();
+    for case in cases {
+        if let Some(previous) = seen.insert(&case.name, case.ident) {
+            diagnostic!(
+                case.ident.span(),
+                Error,
+                "`{}` would generate the case `{}`, which already exists",
+                case.ident,
+                case.name
+            )
+            .span_note(
+                previous.span(),
+                "case previously generated from here".to_owned(),
+            )
+            .emit();
+        }
+    }
+    abort_if_dirty();
+}
+
+fn receiver_error(arg: impl ToTokens) -> Diagnostic {
+    diagnostic!(
+        arg,
+        Error,
+        "defunctionalized functions cannot have receivers";
+        help = "functions in a module are not methods; take the value as a named argument instead, such as `this: Type`"
+    )
+}
+
+/// Sets the span of every token in `tokens`, so that errors in any of them point at `span`.
+pub fn respan(tokens: TokenStream, span: Span) -> TokenStream {
+    tokens
+        .into_iter()
+        .map(|mut token| {
+            if let TokenTree::Group(group) = &token {
+                let mut respanned = Group::new(group.delimiter(), respan(group.stream(), span));
+                respanned.set_span(span);
+                token = TokenTree::Group(respanned);
+            }
+            token.set_span(span);
+            token
+        })
+        .collect()
+}
+
+pub fn output_type(output: &ReturnType) -> Cow<'_, Type> {
+    match output {
+        ReturnType::Default => Cow::Owned(parse_quote!(())),
+        ReturnType::Type(.., ty) => Cow::Borrowed(ty),
+    }
+}
+
+fn case_name(function: &Ident) -> Ident {
+    format_ident!("{}", naming::camel_case(&function.unraw().to_string()))
+}
+
+impl<'a> Case<'a> {
+    pub fn new(
+        mod_path: TokenStream,
+        function: &'a ItemFn,
+        (attrs, options): (Vec<Attribute>, CaseOptions),
+        signature: &Signature,
+        shared_by_name: bool,
+    ) -> Result<Self, Diagnostic> {
+        if let Some(captures) = &options.captures {
+            return Err(diagnostic!(
+                captures,
+                Error,
+                "the captured arguments of a function in the module can be seen";
+                help = "`captures` is only for functions re-exported by `pub use`, which cannot be seen"
+            ));
+        }
+        if let Some(variadic) = &function.sig.variadic {
+            return Err(diagnostic!(
+                variadic,
+                Error,
+                "defunctionalized functions cannot be variadic";
+                help = "the arguments of each case are stored in the enum, so there must be a fixed number of them; take a slice instead"
+            ));
+        }
+        let mut captures = function
+            .sig
+            .inputs
+            .iter()
+            .map(|arg| match arg {
+                FnArg::Receiver(..) => Err(receiver_error(arg)),
+                FnArg::Typed(pat) => Ok(pat),
+            })
+            .enumerate()
+            .map(|(index, pat)| {
+                let pat = pat?;
+                match pat.pat.as_ref() {
+                    Pat::Ident(ident) if ident.ident == "self" => Err(receiver_error(pat)),
+                    Pat::Ident(ident) => Ok(Capture {
+                        ident: Cow::Borrowed(&ident.ident),
+                        ty: Cow::Borrowed(pat.ty.as_ref()),
+                        options: options.args.get(index).cloned().unwrap_or_default(),
+                    }),
+                    pat => Err(diagnostic!(
+                        pat.span(),
+                        Error,
+                        "arguments to defunctionalized functions must be named";
+                        help = "bind the argument to a name, and destructure it in the body of the function"
+                    )),
+                }
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        // With `shared_by_name`, the function takes those of the signature's arguments which its
+        // last arguments are named after, which must be the first of them. Leading underscores
+        // are ignored, as they only mark the argument as unused.
+        let name = |ident: &Ident| ident.unraw().to_string().trim_start_matches('_').to_owned();
+        let shared = if shared_by_name {
+            (0..=signature.inputs.len().min(captures.len()))
+                .rev()
+                .find(|&shared| {
+                    captures[captures.len() - shared..]
+                        .iter()
+                        .zip(&signature.inputs)
+                        .all(|(capture, arg)| name(&capture.ident) == name(&arg.ident))
+                })
+                .unwrap_or(0)
+        } else {
+            signature.inputs.len()
+        };
+        if captures.len() < shared {
+            return Err(diagnostic!(
+                function.sig.paren_token.span,
+                Error,
+                "expected at least {} arguments, from the defunctionalize signature, found {}",
+                shared,
+                captures.len()
+            ));
+        }
+        let input_spans = captures[captures.len() - shared..]
+            .iter()
+            .map(|capture| capture.ty.span())
+            .collect();
+        captures.truncate(captures.len() - shared);
+        let shared = if shared < signature.inputs.len() {
+            Some(shared)
+        } else {
+            None
+        };
+
+        let output_span = match &function.sig.output {
+            ReturnType::Default => None,
+            ReturnType::Type(.., ty) => Some(ty.span()),
+        };
+
+        let ident = &function.sig.ident;
+        Ok(Case {
+            ident,
+            path: quote!(#mod_path::#ident),
+            attrs,
+            options,
+            name: case_name(ident),
+            captures,
+            constness: function.sig.constness.is_some(),
+            shared,
+            input_spans,
+            output_span,
+        })
+    }
+
+    /// Rewrites the types of the captured arguments which name items imported into the module, so
+    /// that they can be written beside it.
+    pub fn qualify_imports(&mut self, imports: &Imports, generics: &Generics) {
+        for capture in &mut self.captures {
+            if let Some(ty) = imports.qualify(&capture.ty, generics) {
+                capture.ty = Cow::Owned(ty);
+            }
+        }
+    }
+
+    /// A case for a function re-exported by the module, whose captured arguments were listed by
+    /// the `captures` option.
+    pub fn reexported(
+        mod_path: TokenStream,
+        ident: &'a Ident,
+        (attrs, mut options): (Vec<Attribute>, CaseOptions),
+    ) -> Self {
+        let captures = options
+            .captures
+            .take()
+            .into_iter()
+            .flatten()
+            .map(|arg| Capture {
+                ident: Cow::Owned(arg.ident),
+                ty: Cow::Owned(*arg.ty),
+                options: ArgOptions::default(),
+            })
+            .collect();
+        Case {
+            ident,
+            path: quote!(#mod_path::#ident),
+            attrs,
+            options,
+            name: case_name(ident),
+            captures,
+            constness: false,
+            shared: None,
+            input_spans: vec![],
+            output_span: None,
+        }
+    }
+
+    /// A case for a function outside of the module, which was listed in the `include` option.
+    pub fn included(function: &'a IncludedFn) -> Self {
+        let ident = &function.path.segments.last().unwrap().ident;
+        let path = &function.path;
+        Case {
+            ident,
+            path: quote!(#path),
+            // The function's documentation cannot be seen.
+            attrs: vec![parse_quote!(#[allow(missing_docs)])],
+            options: CaseOptions::default(),
+            name: case_name(ident),
+            captures: function
+                .captures
+                .iter()
+                .map(|arg| Capture {
+                    ident: Cow::Borrowed(&arg.ident),
+                    ty: Cow::Borrowed(arg.ty.as_ref()),
+                    options: ArgOptions::default(),
+                })
+                .collect(),
+            constness: false,
+            shared: None,
+            input_spans: vec![],
+            output_span: None,
+        }
+    }
+
+    /// A case for an inner module which is itself defunctionalized, which wraps the enum generated
+    /// for that module and delegates to its `call`.
+    pub fn nested(mod_path: TokenStream, module: &'a ItemMod, enum_name: &Ident) -> Self {
+        let ident = &module.ident;
+        let attrs = module
+            .attrs
+            .iter()
+            .filter(|attr| attr.path.is_ident("cfg") || attr.path.is_ident("doc"))
+            .cloned()
+            .collect();
+        Case {
+            ident,
+            path: quote!(#mod_path::#enum_name::call),
+            attrs,
+            options: CaseOptions::default(),
+            name: case_name(ident),
+            captures: vec![Capture {
+                ident: Cow::Borrowed(ident),
+                ty: Cow::Owned(parse_quote!(#mod_path::#enum_name)),
+                options: ArgOptions::default(),
+            }],
+            constness: false,
+            shared: None,
+            input_spans: vec![],
+            output_span: None,
+        }
+    }
+
+    /// A hand-written case from the `extra` option, which is called by calling its closure. Its
+    /// fields are named after the closure's first arguments.
+    pub fn extra(extra: &'a Extra, signature: &Signature) -> Result<Self, Diagnostic> {
+        let closure = &extra.dispatch;
+        let expected = extra.fields.len() + signature.inputs.len();
+        if closure.inputs.len() != expected {
+            let (or1, inputs, or2) = (&closure.or1_token, &closure.inputs, &closure.or2_token);
+            return Err(diagnostic!(
+                quote!(#or1 #inputs #or2),
+                Error,
+                "expected a closure taking {} arguments, found {}",
+                expected,
+                closure.inputs.len();
+                note = "the closure takes the fields of `{}`, followed by the arguments of the signature", extra.name
+            ));
+        }
+        let captures = extra
+            .fields
+            .iter()
+            .zip(&closure.inputs)
+            .enumerate()
+            .map(|(index, (ty, pat))| Capture {
+                ident: match pat {
+                    Pat::Ident(pat) => Cow::Borrowed(&pat.ident),
+                    Pat::Type(pat) => match pat.pat.as_ref() {
+                        Pat::Ident(pat) => Cow::Borrowed(&pat.ident),
+                        _ => Cow::Owned(format_ident!("field{}", index)),
+                    },
+                    _ => Cow::Owned(format_ident!("field{}", index)),
+                },
+                ty: Cow::Borrowed(ty),
+                options: ArgOptions::default(),
+            })
+            .collect();
+        // The closure is called where it is written, so the types of its arguments cannot be
+        // inferred, and are written in for it.
+        let mut closure = closure.clone();
+        let types = extra
+            .fields
+            .iter()
+            .chain(signature.inputs.iter().map(|arg| arg.ty.as_ref()));
+        for (pat, ty) in closure.inputs.iter_mut().zip(types) {
+            if !matches!(pat, Pat::Type(..)) {
+                *pat = Pat::Type(PatType {
+                    attrs: vec![],
+                    pat: Box::new(pat.clone()),
+                    colon_token: Default::default(),
+                    ty: Box::new(ty.clone()),
+                });
+            }
+        }
+        Ok(Case {
+            ident: &extra.name,
+            path: quote!((#closure)),
+            attrs: extra.attrs.clone(),
+            options: CaseOptions::default(),
+            name: extra.name.clone(),
+            captures,
+            constness: false,
+            shared: None,
+            input_spans: vec![],
+            output_span: None,
+        })
+    }
+
+    /// The `cfg` attributes of the function, which must be applied to anything generated for
+    /// this case.
+    pub fn cfgs(&self) -> impl Iterator<Item = &Attribute> {
+        self.attrs.iter().filter(|attr| attr.path.is_ident("cfg"))
+    }
+
+    /// The text of the function's doc comment, if it has one.
+    pub fn doc(&self) -> Option<String> {
+        let lines = self
+            .attrs
+            .iter()
+            .filter(|attr| attr.path.is_ident("doc"))
+            .filter_map(|attr| match attr.parse_meta() {
+                Ok(Meta::NameValue(MetaNameValue {
+                    lit: Lit::Str(lit), ..
+                })) => Some(lit.value().trim().to_owned()),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+        let doc = lines.join("\n").trim().to_owned();
+        if doc.is_empty() {
+            None
+        } else {
+            Some(doc)
+        }
+    }
+
+    /// The name of the function which gives the default value of a captured argument.
+    pub fn default_fn(&self, capture: &Capture) -> Ident {
+        format_ident!("__default_{}_{}", self.ident.unraw(), capture.ident.unraw())
+    }
+
+    /// The variant of the enum for this case. When the enum derives `Deserialize`, `enum_name`
+    /// is given, so that arguments with defaults are filled in when they are missing.
+    pub fn variant(&self, krate: &TokenStream, enum_name: Option<&Ident>) -> TokenStream {
+        let attrs = &self.attrs;
+        let name = &self.name;
+        if self.captures.is_empty() {
+            quote!(#(#attrs)* #name)
+        } else {
+            let serialize_with = format!("{}::__private::serialize_redacted", krate);
+            let fields = self.captures.iter().map(|capture| {
+                let ty = &capture.ty;
+                let serialize_with = if capture.options.redact_serde {
+                    Some(quote!(#[serde(serialize_with = #serialize_with)]))
+                } else {
+                    None
+                };
+                let default = match enum_name {
+                    Some(enum_name) if capture.options.default.is_some() => {
+                        let default = format!("{}::{}", enum_name, self.default_fn(capture));
+                        Some(quote!(#[serde(default = #default)]))
+                    }
+                    _ => None,
+                };
+                quote!(#serialize_with #default #ty)
+            });
+            quote!(#(#attrs)* #name(#(#fields),*))
+        }
+    }
+
+    pub fn pattern(&self) -> TokenStream {
+        let name = &self.name;
+        if self.captures.is_empty() {
+            quote!(#name)
+        } else {
+            let idents = self.captures.iter().map(|capture| &capture.ident);
+            quote!(#name(#(#idents),*))
+        }
+    }
+
+    /// Like `pattern`, but binding the captured arguments by reference, for matching on `*self`,
+    /// which also matches an enum whose cases are all configured out.
+    pub fn ref_pattern(&self) -> TokenStream {
+        let name = &self.name;
+        if self.captures.is_empty() {
+            quote!(#name)
+        } else {
+            let idents = self.captures.iter().map(|capture| &capture.ident);
+            quote!(#name(#(ref #idents),*))
+        }
+    }
+
+    /// The path by which the function is called, spanned at its name, so that errors in using it
+    /// point there.
+    pub fn spanned_path(&self) -> TokenStream {
+        respan(
+            self.path.clone(),
+            self.ident.span().resolved_at(Span::call_site()),
+        )
+    }
+
+    /// The span at which a mismatched return type is reported: the function's return type, or
+    /// its name when it has none.
+    pub fn output_span(&self) -> Span {
+        self.output_span
+            .unwrap_or_else(|| self.ident.span())
+            .resolved_at(Span::call_site())
+    }
+
+    /// Calls the function with its captured arguments, followed by `inputs`.
+    pub fn call(&self, inputs: impl IntoIterator<Item = impl ToTokens>) -> TokenStream {
+        self.call_with(self.captures.iter().map(|capture| &capture.ident), inputs)
+    }
+
+    /// Calls the function with `captures` in place of its captured arguments, followed by as
+    /// many of `inputs` as it takes. The call is spanned at the name of the function, so that
+    /// errors in it, such as unsatisfied bounds, point there rather than at the attribute, each
+    /// input at the type of the argument it is passed as, and its output at the return type, so
+    /// that mismatched types point there.
+    pub fn call_with(
+        &self,
+        captures: impl IntoIterator<Item = impl ToTokens>,
+        inputs: impl IntoIterator<Item = impl ToTokens>,
+    ) -> TokenStream {
+        let span = self.ident.span().resolved_at(Span::call_site());
+        let path = self.spanned_path();
+        let captures = captures.into_iter();
+        let inputs = inputs
+            .into_iter()
+            .take(self.shared.unwrap_or(usize::MAX))
+            .enumerate()
+            .map(|(index, input)| match self.input_spans.get(index) {
+                Some(span) => respan(
+                    input.into_token_stream(),
+                    span.resolved_at(Span::call_site()),
+                ),
+                None => input.into_token_stream(),
+            });
+        let call = quote_spanned!(span=> #path(#(#captures,)* #(#inputs),*));
+        match self.output_span {
+            Some(..) => quote_spanned!(self.output_span()=> {
+                let output = #call;
+                output
+            }),
+            None => call,
+        }
+    }
+
+    /// A pattern matching this case without binding its captured arguments.
+    pub fn wildcard(&self) -> TokenStream {
+        let name = &self.name;
+        if self.captures.is_empty() {
+            quote!(#name)
+        } else {
+            quote!(#name(..))
+        }
+    }
+}
@@ -0,0 +1,244 @@
+use super::{options::unknown_option, SimpleArg};
+
+use syn::{
+    ext::IdentExt, parenthesized, parse::ParseStream, punctuated::Punctuated, Attribute, Expr,
+    Ident, LitInt, LitStr, Token,
+};
+
+const CASE_OPTIONS: &[&str] = &[
+    "alias", "captures", "cost", "default", "priority", "route", "tag", "undo",
+];
+
+const ARG_OPTIONS: &[&str] = &["default", "redact", "skip_eq"];
+
+/// Options given to a single function by a `#[defunctionalize(...)]` helper attribute.
+#[derive(Default)]
+pub struct CaseOptions {
+    /// The names by which the function was known before it was renamed, which are accepted when
+    /// deserializing.
+    pub aliases: Vec<LitStr>,
+    /// The captured arguments of a re-exported function, whose arguments cannot be seen.
+    pub captures: Option<Punctuated<SimpleArg, Token![,]>>,
+    /// The estimated cost of calling the function, returned by `weight`.
+    pub cost: Option<u32>,
+    /// Whether the enum's `Default` is this function, with default captured arguments.
+    pub default: bool,
+    pub priority: Option<i32>,
+    pub route: Option<Route>,
+    pub tag: Option<LitInt>,
+    /// The function which undoes this one, given the same captured arguments.
+    pub undo: Option<Ident>,
+    /// The options given to each of the function's arguments, in order.
+    pub args: Vec<ArgOptions>,
+}
+
+/// Options given to an argument of a function by a `#[defunctionalize(...)]` helper attribute.
+#[derive(Clone, Default)]
+pub struct ArgOptions {
+    /// The value of the argument when it is left out of a constructor or a serialized command.
+    pub default: Option<Expr>,
+    /// Whether the argument is shown as `<redacted>` by `Debug` and in audit records.
+    pub redact: bool,
+    /// Whether the argument is also serialized as `"<redacted>"`.
+    pub redact_serde: bool,
+    /// Whether the argument is left out when comparing and hashing commands.
+    pub skip_eq: bool,
+}
+
+impl ArgOptions {
+    /// Parses the options from all of the helper attributes among `attrs`, removing them.
+    pub fn take(attrs: &mut Vec<Attribute>) -> syn::Result<Self> {
+        let mut options = ArgOptions::default();
+        for attr in attrs.iter() {
+            if attr.path.is_ident("defunctionalize") {
+                attr.parse_args_with(|input: ParseStream| {
+                    while !input.is_empty() {
+                        options.parse_option(input)?;
+                        if input.is_empty() {
+                            break;
+                        }
+                        input.parse::<Token![,]>()?;
+                    }
+                    Ok(())
+                })?;
+            }
+        }
+        attrs.retain(|attr| !attr.path.is_ident("defunctionalize"));
+        Ok(options)
+    }
+
+    fn parse_option(&mut self, input: ParseStream) -> syn::Result<()> {
+        let name = input.call(Ident::parse_any)?;
+        match name.to_string().as_str() {
+            "default" if self.default.is_some() => Err(syn::Error::new(
+                name.span(),
+                format!("duplicate defunctionalize option `{}`", name),
+            )),
+            "default" => {
+                input.parse::<Token![=]>()?;
+                self.default = Some(input.parse()?);
+                Ok(())
+            }
+            "redact" if self.redact => Err(syn::Error::new(
+                name.span(),
+                format!("duplicate defunctionalize option `{}`", name),
+            )),
+            "redact" => {
+                self.redact = true;
+                if input.peek(syn::token::Paren) {
+                    let content;
+                    parenthesized!(content in input);
+                    let target: Ident = content.parse()?;
+                    if target != "serde" || !content.is_empty() {
+                        return Err(syn::Error::new(target.span(), "expected `redact(serde)`"));
+                    }
+                    self.redact_serde = true;
+                }
+                Ok(())
+            }
+            "skip_eq" if self.skip_eq => Err(syn::Error::new(
+                name.span(),
+                format!("duplicate defunctionalize option `{}`", name),
+            )),
+            "skip_eq" => {
+                self.skip_eq = true;
+                Ok(())
+            }
+            _ => Err(unknown_option(&name, ARG_OPTIONS)),
+        }
+    }
+}
+
+/// An HTTP route to a function, written as `"METHOD /path"`.
+pub struct Route {
+    pub method: String,
+    pub path: String,
+}
+
+impl Route {
+    fn parse(lit: &LitStr) -> syn::Result<Self> {
+        let value = lit.value();
+        let mut parts = value.split_whitespace();
+        match (parts.next(), parts.next(), parts.next()) {
+            (Some(method), Some(path), None)
+                if method.chars().all(|ch| ch.is_ascii_uppercase()) && path.starts_with('/') =>
+            {
+                Ok(Route {
+                    method: method.to_owned(),
+                    path: path.to_owned(),
+                })
+            }
+            _ => Err(syn::Error::new(
+                lit.span(),
+                "expected a route like \"POST /items\"",
+            )),
+        }
+    }
+}
+
+impl CaseOptions {
+    pub fn tag(&self) -> Option<u32> {
+        self.tag
+            .as_ref()
+            .map(|tag| tag.base10_parse().expect("tag was validated when parsed"))
+    }
+
+    /// Parses the options from all of the helper attributes among `attrs`, removing them.
+    pub fn take(attrs: &mut Vec<Attribute>) -> syn::Result<Self> {
+        let mut options = CaseOptions::default();
+        for attr in attrs.iter() {
+            // A bare attribute marks a re-exported function as a case, without any options.
+            if attr.path.is_ident("defunctionalize") && !attr.tokens.is_empty() {
+                attr.parse_args_with(|input: ParseStream| options.parse_options(input))?;
+            }
+        }
+        attrs.retain(|attr| !attr.path.is_ident("defunctionalize"));
+        Ok(options)
+    }
+
+    /// Rejects the helper attributes of a function which does not become a case, which would
+    /// otherwise be mistaken for the `defunctionalize` attribute itself.
+    pub fn reject(attrs: &[Attribute]) -> syn::Result<()> {
+        match attrs
+            .iter()
+            .find(|attr| attr.path.is_ident("defunctionalize"))
+        {
+            Some(attr) => Err(syn::Error::new_spanned(
+                attr,
+                "defunctionalize options can only be given to `pub` functions, which become cases",
+            )),
+            None => Ok(()),
+        }
+    }
+
+    fn parse_options(&mut self, input: ParseStream) -> syn::Result<()> {
+        while !input.is_empty() {
+            self.parse_option(input)?;
+            if input.is_empty() {
+                break;
+            }
+            input.parse::<Token![,]>()?;
+        }
+        Ok(())
+    }
+
+    fn parse_option(&mut self, input: ParseStream) -> syn::Result<()> {
+        let name = input.call(Ident::parse_any)?;
+        let given = match name.to_string().as_str() {
+            "captures" => self.captures.is_some(),
+            "cost" => self.cost.is_some(),
+            "default" => self.default,
+            "priority" => self.priority.is_some(),
+            "route" => self.route.is_some(),
+            "tag" => self.tag.is_some(),
+            "undo" => self.undo.is_some(),
+            _ => false,
+        };
+        if given {
+            return Err(syn::Error::new(
+                name.span(),
+                format!("duplicate defunctionalize option `{}`", name),
+            ));
+        }
+        match name.to_string().as_str() {
+            "alias" => {
+                input.parse::<Token![=]>()?;
+                self.aliases.push(input.parse()?);
+            }
+            "captures" => {
+                let content;
+                parenthesized!(content in input);
+                self.captures = Some(Punctuated::parse_terminated(&content)?);
+            }
+            "cost" => {
+                input.parse::<Token![=]>()?;
+                let cost: LitInt = input.parse()?;
+                self.cost = Some(cost.base10_parse()?);
+            }
+            "default" => self.default = true,
+            "priority" => {
+                input.parse::<Token![=]>()?;
+                let negative = input.parse::<Option<Token![-]>>()?.is_some();
+                let priority: LitInt = input.parse()?;
+                let priority = priority.base10_parse::<i32>()?;
+                self.priority = Some(if negative { -priority } else { priority });
+            }
+            "route" => {
+                input.parse::<Token![=]>()?;
+                self.route = Some(Route::parse(&input.parse()?)?);
+            }
+            "tag" => {
+                input.parse::<Token![=]>()?;
+                let tag: LitInt = input.parse()?;
+                tag.base10_parse::<u32>()?;
+                self.tag = Some(tag);
+            }
+            "undo" => {
+                input.parse::<Token![=]>()?;
+                self.undo = Some(input.parse()?);
+            }
+            _ => return Err(unknown_option(&name, CASE_OPTIONS)),
+        }
+        Ok(())
+    }
+}
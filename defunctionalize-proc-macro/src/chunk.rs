@@ -0,0 +1,84 @@
+use super::{Case, Signature};
+
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+use syn::Ident;
+
+/// The number of arms in each helper function, unless the `chunked` option specifies another.
+pub const DEFAULT_CHUNK_SIZE: usize = 64;
+
+/// Generates the hidden method which finds the position of a command's function, by which calls
+/// are routed to the helper function containing its arm.
+pub fn index_impl(enum_name: &Ident, cases: &[Case]) -> TokenStream {
+    let index_arms = cases.iter().enumerate().map(|(index, case)| {
+        let cfgs = case.cfgs();
+        let wildcard = case.wildcard();
+        quote!(#(#cfgs)* #enum_name::#wildcard => #index,)
+    });
+
+    quote! {
+        fn __defunctionalize_index(&self) -> usize {
+            match *self {
+                #(#index_arms)*
+            }
+        }
+    }
+}
+
+/// Splits the arms of a match into helper functions of at most `size` arms each, so that no single
+/// function is too large for the compiler and other tools. Returns the body of the dispatching
+/// function, which checks which range the command's index falls in, along with the helpers. When
+/// `by_ref`, the helpers match on a reference to the command, as in the `DeFn` implementation for
+/// references.
+pub fn chunked_dispatch(
+    enum_name: &Ident,
+    arms: &[TokenStream],
+    signature: &Signature,
+    size: usize,
+    by_ref: bool,
+) -> (TokenStream, TokenStream) {
+    let generics = &signature.generics;
+    let where_clause = &signature.generics.where_clause;
+    let inputs = &signature.inputs;
+    let input_names = signature
+        .inputs
+        .iter()
+        .map(|arg| &arg.ident)
+        .collect::<Vec<_>>();
+    let output = &signature.output;
+    let (receiver, scrutinee, reference) = if by_ref {
+        (quote!(&self), quote!(*self), quote!(self))
+    } else {
+        (quote!(self), quote!(self), quote!(&self))
+    };
+
+    let chunks = arms.chunks(size).enumerate().map(|(chunk, arms)| {
+        let helper = if by_ref {
+            format_ident!("__defunctionalize_call_ref_{}", chunk)
+        } else {
+            format_ident!("__defunctionalize_call_{}", chunk)
+        };
+        let start = chunk * size;
+        let end = start + arms.len() - 1;
+        let route = quote!(#start..=#end => #enum_name::#helper(self, #(#input_names),*),);
+        let helper = quote! {
+            fn #helper #generics (#receiver, #inputs) #output #where_clause {
+                #[allow(unreachable_patterns)]
+                match #scrutinee {
+                    #(#arms)*
+                    _ => ::core::unreachable!(),
+                }
+            }
+        };
+        (route, helper)
+    });
+    let (routes, helpers): (Vec<_>, Vec<_>) = chunks.unzip();
+
+    let body = quote! {
+        match #enum_name::__defunctionalize_index(#reference) {
+            #(#routes)*
+            _ => ::core::unreachable!(),
+        }
+    };
+    (body, quote!(#(#helpers)*))
+}
@@ -0,0 +1,119 @@
+use super::{naming, Case};
+
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{ext::IdentExt, Ident};
+
+/// Generates `clap::Subcommand` and `clap::FromArgMatches` implementations, where each function is
+/// a subcommand and each of its captured arguments is a required `--flag` of that subcommand.
+pub fn subcommand_impl(enum_name: &Ident, cases: &[Case], krate: &TokenStream) -> TokenStream {
+    let clap = quote!(#krate::__private::clap);
+    let commands = cases
+        .iter()
+        .map(|case| naming::kebab_case(&case.ident.unraw().to_string()))
+        .collect::<Vec<_>>();
+    let cfgs = cases
+        .iter()
+        .map(|case| case.cfgs().collect::<Vec<_>>())
+        .collect::<Vec<_>>();
+
+    let augments = cases.iter().zip(&commands).map(|(case, command)| {
+        let cfgs = case.cfgs();
+        let about = case.doc().map(|doc| quote!(.about(#doc)));
+        let args = case.captures.iter().map(|capture| {
+            let id = capture.ident.unraw().to_string();
+            let long = naming::kebab_case(&id);
+            let ty = &capture.ty;
+            quote! {
+                .arg(
+                    #clap::Arg::new(#id)
+                        .long(#long)
+                        .required(true)
+                        .value_parser(#clap::value_parser!(#ty))
+                )
+            }
+        });
+        quote! {
+            #(#cfgs)*
+            let command = command.subcommand(
+                #clap::Command::new(#command)
+                    #about
+                    #(#args)*
+            );
+        }
+    });
+
+    let arms = cases.iter().zip(&commands).map(|(case, command)| {
+        let cfgs = case.cfgs();
+        let name = &case.name;
+        if case.captures.is_empty() {
+            return quote!(#(#cfgs)* #command => ::core::result::Result::Ok(#enum_name::#name),);
+        }
+        let args = case.captures.iter().map(|capture| {
+            let id = capture.ident.unraw().to_string();
+            let ty = &capture.ty;
+            quote! {
+                match #clap::ArgMatches::remove_one::<#ty>(&mut matches, #id) {
+                    ::core::option::Option::Some(value) => value,
+                    ::core::option::Option::None => {
+                        return ::core::result::Result::Err(#clap::Error::raw(
+                            #clap::error::ErrorKind::MissingRequiredArgument,
+                            ::std::format!("missing argument `{}` for `{}`", #id, #command),
+                        ));
+                    }
+                }
+            }
+        });
+        quote!(#(#cfgs)* #command => ::core::result::Result::Ok(#enum_name::#name(#(#args),*)),)
+    });
+
+    quote! {
+        impl #clap::FromArgMatches for #enum_name {
+            fn from_arg_matches(matches: &#clap::ArgMatches) -> ::core::result::Result<Self, #clap::Error> {
+                Self::from_arg_matches_mut(&mut ::core::clone::Clone::clone(matches))
+            }
+
+            fn from_arg_matches_mut(matches: &mut #clap::ArgMatches) -> ::core::result::Result<Self, #clap::Error> {
+                let (name, mut matches) = match #clap::ArgMatches::remove_subcommand(matches) {
+                    ::core::option::Option::Some(subcommand) => subcommand,
+                    ::core::option::Option::None => {
+                        return ::core::result::Result::Err(#clap::Error::raw(
+                            #clap::error::ErrorKind::MissingSubcommand,
+                            "a subcommand is required",
+                        ));
+                    }
+                };
+                match name.as_str() {
+                    #(#arms)*
+                    name => ::core::result::Result::Err(#clap::Error::raw(
+                        #clap::error::ErrorKind::InvalidSubcommand,
+                        ::std::format!("unrecognized subcommand `{}`", name),
+                    )),
+                }
+            }
+
+            fn update_from_arg_matches(&mut self, matches: &#clap::ArgMatches) -> ::core::result::Result<(), #clap::Error> {
+                *self = Self::from_arg_matches(matches)?;
+                ::core::result::Result::Ok(())
+            }
+        }
+
+        impl #clap::Subcommand for #enum_name {
+            fn augment_subcommands(command: #clap::Command) -> #clap::Command {
+                #(#augments)*
+                command
+            }
+
+            fn augment_subcommands_for_update(command: #clap::Command) -> #clap::Command {
+                Self::augment_subcommands(command)
+            }
+
+            fn has_subcommand(name: &str) -> bool {
+                match name {
+                    #(#(#cfgs)* #commands => true,)*
+                    _ => false,
+                }
+            }
+        }
+    }
+}
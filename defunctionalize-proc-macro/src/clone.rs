@@ -0,0 +1,76 @@
+use super::Case;
+
+use proc_macro2::TokenStream;
+use quote::{quote, quote_spanned};
+use syn::{spanned::Spanned, Ident, Type};
+
+const COPY_PRIMITIVES: &[&str] = &[
+    "bool", "char", "f32", "f64", "i8", "i16", "i32", "i64", "i128", "isize", "u8", "u16", "u32",
+    "u64", "u128", "usize",
+];
+
+/// Whether a type is known to be `Copy` from its syntax alone. Named types other than the
+/// primitives can't be checked this way, so they are assumed not to be.
+fn is_copy(ty: &Type) -> bool {
+    match ty {
+        Type::Array(array) => is_copy(&array.elem),
+        Type::BareFn(..) | Type::Never(..) | Type::Ptr(..) => true,
+        Type::Group(group) => is_copy(&group.elem),
+        Type::Paren(paren) => is_copy(&paren.elem),
+        Type::Path(path) if path.qself.is_none() => path
+            .path
+            .get_ident()
+            .map(|ident| COPY_PRIMITIVES.iter().any(|primitive| ident == primitive))
+            .unwrap_or(false),
+        Type::Reference(reference) => reference.mutability.is_none(),
+        Type::Tuple(tuple) => tuple.elems.iter().all(is_copy),
+        _ => false,
+    }
+}
+
+/// Whether every captured argument is known to be `Copy`.
+pub fn is_all_copy(cases: &[Case]) -> bool {
+    cases
+        .iter()
+        .all(|case| case.captures.iter().all(|capture| is_copy(&capture.ty)))
+}
+
+pub fn clone_impl(enum_name: &Ident, cases: &[Case]) -> TokenStream {
+    if is_all_copy(cases) {
+        return quote! {
+            impl ::core::clone::Clone for #enum_name {
+                fn clone(&self) -> Self {
+                    *self
+                }
+            }
+
+            impl ::core::marker::Copy for #enum_name {}
+        };
+    }
+
+    let arms = cases.iter().map(|case| {
+        let cfgs = case.cfgs();
+        let pattern = case.ref_pattern();
+        let name = &case.name;
+        if case.captures.is_empty() {
+            return quote!(#(#cfgs)* #enum_name::#pattern => #enum_name::#name);
+        }
+        // Each clone is spanned to the captured argument's type, so a payload that is not `Clone`
+        // is reported on the offending parameter of the original function.
+        let clones = case.captures.iter().map(|capture| {
+            let ident = &capture.ident;
+            quote_spanned!(capture.ty.span() => ::core::clone::Clone::clone(#ident))
+        });
+        quote!(#(#cfgs)* #enum_name::#pattern => #enum_name::#name(#(#clones),*))
+    });
+
+    quote! {
+        impl ::core::clone::Clone for #enum_name {
+            fn clone(&self) -> Self {
+                match *self {
+                    #(#arms,)*
+                }
+            }
+        }
+    }
+}
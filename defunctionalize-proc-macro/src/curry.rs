@@ -0,0 +1,70 @@
+use super::Signature;
+
+use proc_macro2::{Span, TokenStream};
+use proc_macro_error::abort_call_site;
+use quote::quote;
+use syn::{GenericParam, Ident, Lifetime, Visibility};
+
+/// Generates the `call_curried` method, which takes the arguments of the signature one at a time
+/// through nested closures. All but the outermost closure are boxed.
+pub fn curry_impl(
+    visibility: &Visibility,
+    enum_name: &Ident,
+    signature: &Signature,
+) -> TokenStream {
+    if signature.inputs.len() < 2 {
+        abort_call_site!("`curry` requires the signature to have at least two arguments");
+    }
+
+    let lifetime = Lifetime::new("'__curry", Span::call_site());
+    let (types, output_type, _) = signature.with_named_lifetimes(&lifetime);
+    let names = signature
+        .inputs
+        .iter()
+        .map(|arg| &arg.ident)
+        .collect::<Vec<_>>();
+
+    // Built from the innermost closure outwards.
+    let mut closure_type = output_type;
+    let mut body = quote!(#enum_name::call(self, #(#names),*));
+    for (index, (name, ty)) in names.iter().zip(&types).enumerate().rev() {
+        if index == 0 {
+            closure_type = quote!(impl ::core::ops::FnOnce(#ty) -> #closure_type + #lifetime);
+            body = quote!(move |#name: #ty| #body);
+        } else {
+            closure_type = quote! {
+                ::std::boxed::Box<dyn ::core::ops::FnOnce(#ty) -> #closure_type + #lifetime>
+            };
+            body = quote!(::std::boxed::Box::new(move |#name: #ty| #body));
+        }
+    }
+
+    let params = signature.generics.params.iter();
+    let type_bounds = signature
+        .generics
+        .params
+        .iter()
+        .filter_map(|param| match param {
+            GenericParam::Type(param) => Some(&param.ident),
+            _ => None,
+        });
+    let predicates = signature
+        .generics
+        .where_clause
+        .iter()
+        .flat_map(|where_clause| where_clause.predicates.iter());
+
+    quote! {
+        impl #enum_name {
+            /// Takes the arguments of the signature one at a time, returning a closure for each
+            /// until the last, which calls the command.
+            #visibility fn call_curried<#lifetime, #(#params),*>(self) -> #closure_type
+            where
+                #(#type_bounds: #lifetime,)*
+                #(#predicates,)*
+            {
+                #body
+            }
+        }
+    }
+}
@@ -0,0 +1,43 @@
+use proc_macro2::{Span, TokenStream};
+use proc_macro_error::abort;
+use std::env;
+use std::fs;
+use std::path::PathBuf;
+use std::process::Command;
+use syn::{Ident, LitStr};
+
+/// The environment variable which sets the directory for every module, without the option.
+const ENV_VAR: &str = "DEFUNCTIONALIZE_DEBUG_EXPAND";
+
+/// Writes the expanded code of a module to `{directory}/{enum_name}.rs`, for debugging, when a
+/// directory is given by the `debug_expand` option or the environment variable. A relative
+/// directory is relative to the crate being compiled. The code is formatted by `rustfmt` if it is
+/// installed.
+pub fn write(directory: Option<&LitStr>, enum_name: &Ident, expanded: &TokenStream) {
+    let (directory, span) = match directory {
+        Some(directory) => (directory.value(), directory.span()),
+        None => match env::var(ENV_VAR) {
+            Ok(directory) if !directory.is_empty() => (directory, Span::call_site()),
+            _ => return,
+        },
+    };
+    let mut path = PathBuf::from(directory);
+    if path.is_relative() {
+        if let Ok(manifest_dir) = env::var("CARGO_MANIFEST_DIR") {
+            path = PathBuf::from(manifest_dir).join(path);
+        }
+    }
+    let result = fs::create_dir_all(&path).and_then(|()| {
+        path.push(format!("{}.rs", enum_name));
+        fs::write(&path, expanded.to_string())
+    });
+    if let Err(error) = result {
+        abort!(
+            span,
+            "failed to write the expanded code to `{}`: {}",
+            path.display(),
+            error
+        );
+    }
+    let _ = Command::new("rustfmt").arg(&path).output();
+}
@@ -0,0 +1,164 @@
+use super::Case;
+
+use proc_macro2::TokenStream;
+use proc_macro_error::{abort_if_dirty, emit_error};
+use quote::quote;
+use syn::{ext::IdentExt, Ident, Visibility};
+
+/// Implements `Default` for the enum, as the case whose function is marked
+/// `#[defunctionalize(default)]`, with each of its captured arguments given its own default, or
+/// else defaulted.
+pub fn default_impl(enum_name: &Ident, cases: &[Case]) -> Option<TokenStream> {
+    let mut defaults = cases.iter().filter(|case| case.options.default);
+    let default = defaults.next()?;
+    for case in defaults {
+        emit_error!(
+            case.ident,
+            "`{}` is marked as the default, but so is `{}`",
+            case.ident,
+            default.ident;
+            help = "mark only one function with `#[defunctionalize(default)]`"
+        );
+    }
+    abort_if_dirty();
+
+    let cfgs = default.cfgs();
+    let name = &default.name;
+    let fields = default
+        .captures
+        .iter()
+        .map(|capture| match &capture.options.default {
+            Some(..) => {
+                let default_fn = default.default_fn(capture);
+                quote!(#enum_name::#default_fn())
+            }
+            None => quote!(::core::default::Default::default()),
+        });
+    let value = if default.captures.is_empty() {
+        quote!(#enum_name::#name)
+    } else {
+        quote!(#enum_name::#name(#(#fields),*))
+    };
+    Some(quote! {
+        #(#cfgs)*
+        impl ::core::default::Default for #enum_name {
+            fn default() -> Self {
+                #value
+            }
+        }
+    })
+}
+
+/// Generates a function giving the default value of each captured argument which has one, and
+/// for each function with such arguments, a constructor named after the function which leaves
+/// them out. Only trailing arguments may have defaults, so that the constructor takes the others
+/// in order, and no constructor may be named the same as one of the other `methods` which are
+/// generated for the enum.
+pub fn arg_defaults_impl(
+    visibility: &Visibility,
+    enum_name: &Ident,
+    cases: &[Case],
+    methods: &[(&str, bool)],
+) -> Option<TokenStream> {
+    for case in cases {
+        let has_defaults = case
+            .captures
+            .iter()
+            .any(|capture| capture.options.default.is_some());
+        let name = case.ident.unraw();
+        if has_defaults && methods.contains(&(name.to_string().as_str(), true)) {
+            emit_error!(
+                case.ident,
+                "the constructor of `{}`, which leaves out its arguments with defaults, would have the same name as the `{}` method of `{}`",
+                case.ident,
+                name,
+                enum_name;
+                help = "rename the function, or remove the defaults of its arguments"
+            );
+        }
+        let mut defaulted = None;
+        for capture in &case.captures {
+            match &capture.options.default {
+                Some(..) => defaulted = Some(capture),
+                None => {
+                    if let Some(defaulted) = defaulted {
+                        emit_error!(
+                            capture.ident,
+                            "`{}` has no default, but follows `{}`, which does",
+                            capture.ident,
+                            defaulted.ident;
+                            help = "only trailing arguments may have defaults"
+                        );
+                    }
+                }
+            }
+        }
+    }
+    abort_if_dirty();
+
+    let items = cases
+        .iter()
+        .filter(|case| {
+            case.captures
+                .iter()
+                .any(|capture| capture.options.default.is_some())
+        })
+        .map(|case| {
+            let cfgs = case.cfgs().collect::<Vec<_>>();
+            let ident = case.ident;
+            let name = &case.name;
+            let default_fns = case.captures.iter().filter_map(|capture| {
+                let default = capture.options.default.as_ref()?;
+                let default_fn = case.default_fn(capture);
+                let ty = &capture.ty;
+                Some(quote! {
+                    #(#cfgs)*
+                    fn #default_fn() -> #ty {
+                        #default
+                    }
+                })
+            });
+            let required = case
+                .captures
+                .iter()
+                .filter(|capture| capture.options.default.is_none())
+                .map(|capture| {
+                    let ident = &capture.ident;
+                    let ty = &capture.ty;
+                    quote!(#ident: #ty)
+                });
+            let values = case.captures.iter().map(|capture| {
+                let ident = &capture.ident;
+                match &capture.options.default {
+                    Some(..) => {
+                        let default_fn = case.default_fn(capture);
+                        quote!(#enum_name::#default_fn())
+                    }
+                    None => quote!(#ident),
+                }
+            });
+            let doc = format!(
+                "Constructs a `{}` command, with the default value of each argument which has one.",
+                ident
+            );
+            quote! {
+                #(#default_fns)*
+
+                #(#cfgs)*
+                #[doc = #doc]
+                #visibility fn #ident(#(#required),*) -> Self {
+                    #enum_name::#name(#(#values),*)
+                }
+            }
+        })
+        .collect::<Vec<_>>();
+    if items.is_empty() {
+        return None;
+    }
+
+    Some(quote! {
+        impl #enum_name {
+            #(#items)*
+        }
+    })
+}
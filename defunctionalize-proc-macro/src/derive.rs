@@ -0,0 +1,150 @@
+use super::Signature;
+
+use proc_macro2::TokenStream;
+use proc_macro_error::{abort, abort_call_site, abort_if_dirty, emit_error};
+use quote::{format_ident, quote};
+use syn::{
+    parse::{Parse, ParseStream},
+    Data, DeriveInput, Fields, Ident, Path, ReturnType, Token,
+};
+
+/// The `#[defn(call = path::to::function)]` attribute of a variant.
+struct VariantCall {
+    path: Path,
+}
+
+impl Parse for VariantCall {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let name: Ident = input.parse()?;
+        if name != "call" {
+            return Err(syn::Error::new(name.span(), "expected `call = path`"));
+        }
+        input.parse::<Token![=]>()?;
+        Ok(VariantCall {
+            path: input.parse()?,
+        })
+    }
+}
+
+/// Implements `DeFn` for a hand-written enum, with the signature given by `#[defn(fn(...))]` on
+/// the enum, by calling the function of each variant given by `#[defn(call = ...)]` with the
+/// variant's fields followed by the inputs.
+pub fn derive_defn(input: DeriveInput) -> TokenStream {
+    let signature = match input.attrs.iter().find(|attr| attr.path.is_ident("defn")) {
+        Some(attr) => match attr.parse_args::<Signature>() {
+            Ok(Signature {
+                asyncness: Some(asyncness),
+                ..
+            }) => abort!(asyncness, "`DeFn` cannot be derived for an async signature"),
+            Ok(signature) => signature,
+            Err(error) => return error.to_compile_error(),
+        },
+        None => abort_call_site!(
+            "`DeFn` requires the signature of the functions";
+            help = "add `#[defn(fn(...) -> ...)]` to the enum"
+        ),
+    };
+    let data = match &input.data {
+        Data::Enum(data) => data,
+        _ => abort_call_site!("`DeFn` can only be derived for enums"),
+    };
+
+    let enum_name = &input.ident;
+    let input_names = signature
+        .inputs
+        .iter()
+        .map(|arg| &arg.ident)
+        .collect::<Vec<_>>();
+    let arms = data
+        .variants
+        .iter()
+        .map(|variant| {
+            let call = match variant.attrs.iter().find(|attr| attr.path.is_ident("defn")) {
+                Some(attr) => match attr.parse_args::<VariantCall>() {
+                    Ok(call) => call,
+                    Err(error) => {
+                        emit_error!(error.span(), "{}", error);
+                        return quote!();
+                    }
+                },
+                None => {
+                    emit_error!(
+                        variant.ident.span(),
+                        "`{}` has no function to call", variant.ident;
+                        help = "add `#[defn(call = path::to::function)]` to the variant"
+                    );
+                    return quote!();
+                }
+            };
+            let name = &variant.ident;
+            let path = &call.path;
+            let (pattern, fields) = match &variant.fields {
+                Fields::Unit => (quote!(#name), vec![]),
+                Fields::Unnamed(fields) => {
+                    let idents = (0..fields.unnamed.len())
+                        .map(|index| format_ident!("__field{}", index))
+                        .collect::<Vec<_>>();
+                    (quote!(#name(#(#idents),*)), idents)
+                }
+                Fields::Named(fields) => {
+                    let idents = fields
+                        .named
+                        .iter()
+                        .map(|field| field.ident.clone().unwrap())
+                        .collect::<Vec<_>>();
+                    (quote!(#name { #(#idents),* }), idents)
+                }
+            };
+            quote! {
+                #enum_name::#pattern => #path(#(#fields,)* #(#input_names),*),
+            }
+        })
+        .collect::<Vec<_>>();
+    abort_if_dirty();
+
+    let visibility = &input.vis;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    let generics = &signature.generics;
+    let signature_where = &signature.generics.where_clause;
+    let inputs = &signature.inputs;
+    let input_types = signature
+        .inputs
+        .iter()
+        .map(|arg| &arg.ty)
+        .collect::<Vec<_>>();
+    let output = &signature.output;
+    let output_type = match output {
+        ReturnType::Default => quote!(()),
+        ReturnType::Type(.., ty) => quote!(#ty),
+    };
+
+    let mut defn_generics = input.generics.clone();
+    defn_generics
+        .params
+        .extend(signature.generics.params.iter().cloned());
+    if let Some(signature_where) = signature_where {
+        defn_generics
+            .make_where_clause()
+            .predicates
+            .extend(signature_where.predicates.iter().cloned());
+    }
+    let (defn_impl_generics, _, defn_where_clause) = defn_generics.split_for_impl();
+
+    quote! {
+        impl #impl_generics #enum_name #ty_generics #where_clause {
+            #visibility fn call #generics (self, #inputs) #output #signature_where {
+                match self {
+                    #(#arms)*
+                }
+            }
+        }
+
+        impl #defn_impl_generics ::defunctionalize::DeFn<(#(#input_types),*)> for #enum_name #ty_generics #defn_where_clause {
+            type Output = #output_type;
+
+            fn call(self, (#(#input_names),*): (#(#input_types),*)) #output {
+                #enum_name::call(self, #(#input_names),*)
+            }
+        }
+    }
+}
@@ -0,0 +1,111 @@
+use super::{Case, Signature};
+
+use proc_macro2::TokenStream;
+use quote::{quote, quote_spanned};
+use syn::{
+    parenthesized,
+    parse::{Parse, ParseStream},
+    spanned::Spanned,
+    token::Paren,
+    Ident, Path, Receiver,
+};
+
+/// A trait method to implement on the enum by forwarding to the function of each variant, written
+/// as `path::to::Trait::method`, optionally followed by the receiver of the method, such as
+/// `(&self)`. The receiver defaults to `self`.
+pub struct Dispatch {
+    pub trait_path: Path,
+    pub method: Ident,
+    pub receiver: Receiver,
+}
+
+impl Parse for Dispatch {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let mut trait_path: Path = input.parse()?;
+        let method = match trait_path.segments.pop() {
+            Some(method)
+                if !trait_path.segments.is_empty() && method.value().arguments.is_empty() =>
+            {
+                method.into_value().ident
+            }
+            _ => {
+                return Err(syn::Error::new(
+                    trait_path.span(),
+                    "expected the path to a trait method, like `Trait::method`",
+                ))
+            }
+        };
+        // The separator before the method is left behind by `pop`.
+        let last = trait_path.segments.pop().unwrap().into_value();
+        trait_path.segments.push(last);
+
+        let receiver = if input.peek(Paren) {
+            let content;
+            parenthesized!(content in input);
+            content.parse()?
+        } else {
+            syn::parse_quote!(self)
+        };
+        Ok(Dispatch {
+            trait_path,
+            method,
+            receiver,
+        })
+    }
+}
+
+/// Implements the trait of a `dispatch` option on the enum, calling the function of each variant
+/// directly. When the receiver is a reference, the captured arguments are cloned.
+pub fn dispatch_impl(
+    enum_name: &Ident,
+    cases: &[Case],
+    signature: &Signature,
+    dispatch: &Dispatch,
+) -> TokenStream {
+    let Dispatch {
+        trait_path,
+        method,
+        receiver,
+    } = dispatch;
+    let by_ref = receiver.reference.is_some();
+    let generics = &signature.generics;
+    let where_clause = &signature.generics.where_clause;
+    let inputs = &signature.inputs;
+    let input_names = inputs.iter().map(|arg| &arg.ident).collect::<Vec<_>>();
+    let output = &signature.output;
+
+    let arms = cases.iter().map(|case| {
+        let cfgs = case.cfgs();
+        let pattern = if by_ref {
+            case.ref_pattern()
+        } else {
+            case.pattern()
+        };
+        let captures = case.captures.iter().map(|capture| {
+            let ident = &capture.ident;
+            if by_ref {
+                quote_spanned!(capture.ty.span() => ::core::clone::Clone::clone(#ident))
+            } else {
+                quote!(#ident)
+            }
+        });
+        let call = case.call_with(captures, &input_names);
+        quote! {
+            #(#cfgs)*
+            #enum_name::#pattern => {
+                #call
+            }
+        }
+    });
+
+    let matched = if by_ref { quote!(*self) } else { quote!(self) };
+    quote! {
+        impl #trait_path for #enum_name {
+            fn #method #generics (#receiver, #inputs) #output #where_clause {
+                match #matched {
+                    #(#arms)*
+                }
+            }
+        }
+    }
+}
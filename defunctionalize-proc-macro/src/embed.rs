@@ -0,0 +1,39 @@
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{Ident, Path};
+
+/// Converts between the enum and a wider enum which has a variant holding it, given by `target`
+/// as the path to the variant, or only the wider enum's name when the variant is named after the
+/// enum.
+pub fn embed_impl(enum_name: &Ident, target: &Path) -> TokenStream {
+    let (wider, variant) = if target.leading_colon.is_none() && target.segments.len() == 1 {
+        (target.clone(), enum_name.clone())
+    } else {
+        let mut wider = target.clone();
+        let variant = wider.segments.pop().unwrap().into_value().ident;
+        // Leave no trailing `::` behind.
+        let last = wider.segments.pop().unwrap().into_value();
+        wider.segments.push(last);
+        (wider, variant)
+    };
+
+    quote! {
+        impl ::core::convert::From<#enum_name> for #wider {
+            fn from(command: #enum_name) -> Self {
+                #wider::#variant(command)
+            }
+        }
+
+        impl ::core::convert::TryFrom<#wider> for #enum_name {
+            type Error = #wider;
+
+            #[allow(unreachable_patterns)]
+            fn try_from(command: #wider) -> ::core::result::Result<Self, #wider> {
+                match command {
+                    #wider::#variant(command) => ::core::result::Result::Ok(command),
+                    command => ::core::result::Result::Err(command),
+                }
+            }
+        }
+    }
+}
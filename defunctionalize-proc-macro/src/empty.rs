@@ -0,0 +1,55 @@
+use super::warning::warning;
+
+use proc_macro2::TokenStream;
+use proc_macro_error::abort;
+use syn::{
+    ext::IdentExt,
+    parse::{Parse, ParseStream},
+    Ident,
+};
+
+/// How to report a module without any functions to defunctionalize.
+#[derive(Clone, Copy, PartialEq, Eq, Default)]
+pub enum EmptyLevel {
+    Allow,
+    #[default]
+    Warn,
+    Deny,
+}
+
+impl Parse for EmptyLevel {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let level: Ident = input.parse()?;
+        match level.to_string().as_str() {
+            "allow" => Ok(EmptyLevel::Allow),
+            "warn" => Ok(EmptyLevel::Warn),
+            "deny" => Ok(EmptyLevel::Deny),
+            _ => Err(syn::Error::new(
+                level.span(),
+                format!(
+                    "unknown level `{}`, expected `allow`, `warn`, or `deny`",
+                    level
+                ),
+            )),
+        }
+    }
+}
+
+/// Reports a module without any functions, which is most likely because its functions are not
+/// `pub`.
+pub fn check_empty(mod_name: &Ident, level: EmptyLevel) -> Option<TokenStream> {
+    let message = format!(
+        "module `{}` has no public functions to defunctionalize, so the enum has no cases",
+        mod_name
+    );
+    let help = "only `pub` functions become cases; use `empty = allow` if this is intended";
+    match level {
+        EmptyLevel::Allow => None,
+        EmptyLevel::Deny => abort!(mod_name, message; help = help),
+        EmptyLevel::Warn => Some(warning(
+            mod_name.span(),
+            &format!("no_public_functions_in_{}", mod_name.unraw()),
+            &format!("{}; {}", message, help),
+        )),
+    }
+}
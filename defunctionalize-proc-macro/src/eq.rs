@@ -0,0 +1,169 @@
+use super::{serde_impl, Case};
+
+use proc_macro2::{Literal, TokenStream};
+use proc_macro_error::abort_call_site;
+use quote::{format_ident, quote};
+use syn::{Ident, Visibility};
+
+/// The captured arguments of a case which are compared, with `None` for those which are not.
+fn compared<'a>(case: &'a Case) -> Vec<Option<&'a Ident>> {
+    case.captures
+        .iter()
+        .map(|capture| {
+            if capture.options.skip_eq {
+                None
+            } else {
+                Some(capture.ident.as_ref())
+            }
+        })
+        .collect()
+}
+
+/// Implements `PartialEq` and `Hash` for the enum in place of the derived implementations, which
+/// are given by `eq` and `hash`, leaving out the arguments marked `skip_eq`, so that commands
+/// which differ only by those are equal.
+pub fn eq_impl(enum_name: &Ident, cases: &[Case], eq: bool, hash: bool) -> TokenStream {
+    let eq_impl = if eq {
+        let arms = cases.iter().map(|case| {
+            let cfgs = case.cfgs();
+            let name = &case.name;
+            let fields = compared(case);
+            if fields.is_empty() {
+                return quote!(#(#cfgs)* (#enum_name::#name, #enum_name::#name) => true,);
+            }
+            let bind = |prefix: &str| {
+                fields
+                    .iter()
+                    .map(|field| match field {
+                        Some(ident) => {
+                            let ident = format_ident!("{}_{}", prefix, ident);
+                            quote!(#ident)
+                        }
+                        None => quote!(_),
+                    })
+                    .collect::<Vec<_>>()
+            };
+            let (lhs, rhs) = (bind("lhs"), bind("rhs"));
+            let comparisons = fields.iter().flatten().map(|ident| {
+                let lhs = format_ident!("lhs_{}", ident);
+                let rhs = format_ident!("rhs_{}", ident);
+                quote!(&& #lhs == #rhs)
+            });
+            quote! {
+                #(#cfgs)*
+                (#enum_name::#name(#(#lhs),*), #enum_name::#name(#(#rhs),*)) => true #(#comparisons)*,
+            }
+        });
+        Some(quote! {
+            impl ::core::cmp::PartialEq for #enum_name {
+                #[allow(unreachable_patterns)]
+                fn eq(&self, other: &Self) -> bool {
+                    match (self, other) {
+                        #(#arms)*
+                        _ => false,
+                    }
+                }
+            }
+        })
+    } else {
+        None
+    };
+
+    let hash_impl = if hash {
+        let arms = cases.iter().map(|case| {
+            let cfgs = case.cfgs();
+            let name = &case.name;
+            let fields = compared(case);
+            if fields.is_empty() {
+                return quote!(#(#cfgs)* #enum_name::#name => {});
+            }
+            let bindings = fields.iter().map(|field| match field {
+                Some(ident) => quote!(ref #ident),
+                None => quote!(_),
+            });
+            let hashes = fields.iter().flatten();
+            quote! {
+                #(#cfgs)*
+                #enum_name::#name(#(#bindings),*) => {
+                    #(::core::hash::Hash::hash(#hashes, state);)*
+                }
+            }
+        });
+        Some(quote! {
+            impl ::core::hash::Hash for #enum_name {
+                fn hash<H: ::core::hash::Hasher>(&self, state: &mut H) {
+                    ::core::hash::Hash::hash(&::core::mem::discriminant(self), state);
+                    match *self {
+                        #(#arms)*
+                    }
+                }
+            }
+        })
+    } else {
+        None
+    };
+
+    quote! {
+        #eq_impl
+        #hash_impl
+    }
+}
+
+/// Implements `Hash` for the enum by hashing the tag of each command as a `u32`, followed by each
+/// of its compared arguments in order, so that hashes do not change when functions are added,
+/// removed, or reordered. With a `StableHasher`, which the generated `stable_hash` method uses,
+/// they do not depend on the platform either.
+pub fn stable_hash_impl(
+    vis: &Visibility,
+    enum_name: &Ident,
+    cases: &[Case],
+    krate: &TokenStream,
+) -> TokenStream {
+    if !serde_impl::validate_tags(cases) {
+        abort_call_site!(
+            "`stable_hash` requires a tag on every function";
+            help = "add `#[defunctionalize(tag = ...)]` to each function"
+        );
+    }
+    let arms = cases.iter().map(|case| {
+        let cfgs = case.cfgs();
+        let name = &case.name;
+        let tag = Literal::u32_suffixed(case.options.tag().unwrap());
+        let fields = compared(case);
+        let pattern = if fields.is_empty() {
+            quote!(#enum_name::#name)
+        } else {
+            let bindings = fields.iter().map(|field| match field {
+                Some(ident) => quote!(ref #ident),
+                None => quote!(_),
+            });
+            quote!(#enum_name::#name(#(#bindings),*))
+        };
+        let hashes = fields.iter().flatten();
+        quote! {
+            #(#cfgs)*
+            #pattern => {
+                ::core::hash::Hash::hash(&#tag, state);
+                #(::core::hash::Hash::hash(#hashes, state);)*
+            }
+        }
+    });
+
+    quote! {
+        impl ::core::hash::Hash for #enum_name {
+            fn hash<H: ::core::hash::Hasher>(&self, state: &mut H) {
+                match *self {
+                    #(#arms)*
+                }
+            }
+        }
+
+        impl #enum_name {
+            /// A hash of the command which stays the same across platforms and versions of the
+            /// program, as long as its tag and the types of its arguments do not change.
+            #vis fn stable_hash(&self) -> u64 {
+                #krate::stable_hash(self)
+            }
+        }
+    }
+}
@@ -0,0 +1,35 @@
+use super::Signature;
+
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{Ident, ReturnType, Type, Visibility};
+
+/// Generates the `apply` method of the `events` option, which calls the command, if the signature
+/// takes only a mutable reference to some state and returns nothing, as when the enum is the type
+/// of events which change that state.
+pub fn apply_impl(
+    visibility: &Visibility,
+    enum_name: &Ident,
+    signature: &Signature,
+) -> Option<TokenStream> {
+    if signature.inputs.len() != 1 || !matches!(signature.output, ReturnType::Default) {
+        return None;
+    }
+    let state = &signature.inputs[0];
+    if !matches!(&*state.ty, Type::Reference(ty) if ty.mutability.is_some()) {
+        return None;
+    }
+
+    let generics = &signature.generics;
+    let where_clause = &signature.generics.where_clause;
+    let name = &state.ident;
+    let ty = &state.ty;
+    Some(quote! {
+        impl #enum_name {
+            /// Applies this event to the state, by calling it.
+            #visibility fn apply #generics (self, #name: #ty) #where_clause {
+                #enum_name::call(self, #name)
+            }
+        }
+    })
+}
@@ -0,0 +1,101 @@
+use super::{serde_impl::validate_tags, Case, Signature};
+
+use proc_macro2::{Literal, TokenStream};
+use proc_macro_error::{abort, abort_call_site};
+use quote::quote;
+use syn::{spanned::Spanned, Ident, ReturnType, Visibility};
+
+/// Generates an `extern "C"` function through which a foreign host calls a command, identified by
+/// its stable tag, with its captured arguments encoded as a postcard payload.
+pub fn ffi_impl(
+    visibility: &Visibility,
+    enum_name: &Ident,
+    function_name: &Ident,
+    cases: &[Case],
+    signature: &Signature,
+    krate: &TokenStream,
+) -> TokenStream {
+    if !signature.generics.params.is_empty() {
+        abort!(
+            signature.generics.span(),
+            "`ffi` cannot be used with a generic signature"
+        );
+    }
+    if !validate_tags(cases) {
+        abort_call_site!(
+            "`ffi` requires every function to have a stable tag";
+            help = "add `#[defunctionalize(tag = ...)]` to each function"
+        );
+    }
+
+    let postcard = quote!(#krate::__private::postcard);
+    let inputs = signature.inputs.iter();
+    let input_names = signature.inputs.iter().map(|arg| &arg.ident);
+    let (output_param, write_output) = match &signature.output {
+        ReturnType::Default => (None, quote!(let () = result;)),
+        ReturnType::Type(.., ty) => (
+            Some(quote!(output: *mut #ty,)),
+            quote! {
+                if !output.is_null() {
+                    ::core::ptr::write(output, result);
+                }
+            },
+        ),
+    };
+
+    let arms = cases.iter().map(|case| {
+        let cfgs = case.cfgs();
+        let tag = Literal::u32_suffixed(case.options.tag().unwrap());
+        let name = &case.name;
+        if case.captures.is_empty() {
+            return quote!(#(#cfgs)* #tag => #enum_name::#name,);
+        }
+        let idents = case
+            .captures
+            .iter()
+            .map(|capture| &capture.ident)
+            .collect::<Vec<_>>();
+        let types = case.captures.iter().map(|capture| &capture.ty);
+        quote! {
+            #(#cfgs)*
+            #tag => match #postcard::from_bytes::<(#(#types,)*)>(payload) {
+                ::core::result::Result::Ok((#(#idents,)*)) => #enum_name::#name(#(#idents),*),
+                ::core::result::Result::Err(..) => return 2,
+            },
+        }
+    });
+
+    quote! {
+        /// Calls the command identified by `tag`, decoding its captured arguments from the
+        /// postcard-encoded `payload` of `len` bytes. Returns `0` on success, `1` if no command
+        /// has the tag, or `2` if the payload could not be decoded.
+        ///
+        /// # Safety
+        ///
+        /// `payload` must point to `len` readable bytes, unless `len` is `0`. If there is an
+        /// `output` pointer, it must be null or valid for writes.
+        #[no_mangle]
+        // Without any functions, every tag is unknown.
+        #[allow(unreachable_code)]
+        #visibility unsafe extern "C" fn #function_name(
+            tag: u32,
+            payload: *const u8,
+            len: usize,
+            #(#inputs,)*
+            #output_param
+        ) -> i32 {
+            let payload: &[u8] = if len == 0 {
+                &[]
+            } else {
+                ::core::slice::from_raw_parts(payload, len)
+            };
+            let command = match tag {
+                #(#arms)*
+                _ => return 1,
+            };
+            let result = #enum_name::call(command, #(#input_names),*);
+            #write_output
+            0
+        }
+    }
+}
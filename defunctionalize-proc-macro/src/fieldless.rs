@@ -0,0 +1,276 @@
+use super::{serde_impl, Case, Signature};
+
+use proc_macro2::{TokenStream, TokenTree};
+use quote::{quote, ToTokens};
+use syn::{Attribute, Ident, Path, Visibility};
+
+/// Whether none of the cases capture any arguments, in which case the enum is fieldless.
+pub fn is_fieldless(cases: &[Case]) -> bool {
+    cases.iter().all(|case| case.captures.is_empty())
+}
+
+const DERIVES: &[&str] = &["Clone", "Copy", "PartialEq", "Eq", "Hash"];
+
+/// The derives of a fieldless enum, which is cheap to copy and compare, other than those which are
+/// already derived, either in the options or on the module, or implemented by other options.
+pub fn derive_attr(derives: &[Path], attrs: &[Attribute], skip: &[&str]) -> Option<TokenStream> {
+    let derives = DERIVES
+        .iter()
+        .filter(|name| !skip.contains(name))
+        .filter(|name| !serde_impl::derives(derives, attrs, name))
+        .map(|name| match *name {
+            "Clone" => quote!(::core::clone::Clone),
+            "Copy" => quote!(::core::marker::Copy),
+            "PartialEq" => quote!(::core::cmp::PartialEq),
+            "Eq" => quote!(::core::cmp::Eq),
+            _ => quote!(::core::hash::Hash),
+        })
+        .collect::<Vec<_>>();
+    if derives.is_empty() {
+        None
+    } else {
+        Some(quote!(#[derive(#(#derives),*)]))
+    }
+}
+
+fn mentions_impl(tokens: TokenStream) -> bool {
+    tokens.into_iter().any(|token| match token {
+        TokenTree::Ident(ident) => ident == "impl",
+        TokenTree::Group(group) => mentions_impl(group.stream()),
+        _ => false,
+    })
+}
+
+/// Whether the functions can be stored in a table of function pointers, which requires that the
+/// signature is neither generic nor async, and that every function takes all of its arguments.
+/// Arguments of `impl Trait` types make the functions generic as well.
+pub fn is_tabulable(cases: &[Case], signature: &Signature) -> bool {
+    !cases.is_empty()
+        && cases.iter().all(|case| case.shared.is_none())
+        && signature.asyncness.is_none()
+        && signature.generics.params.is_empty()
+        && !signature
+            .inputs
+            .iter()
+            .any(|arg| mentions_impl(arg.ty.to_token_stream()))
+}
+
+/// Calls a fieldless command by looking up its function in the `LOOKUP` table, rather than by
+/// matching on it.
+pub fn table_dispatch(
+    enum_name: &Ident,
+    signature: &Signature,
+    command: TokenStream,
+) -> TokenStream {
+    let input_names = signature.inputs.iter().map(|arg| &arg.ident);
+    quote!(#enum_name::LOOKUP[#enum_name::variant_index(#command)](#(#input_names),*))
+}
+
+/// Generates the number of cases and the index of each, and when the functions can be stored in
+/// a table, a constant table of them in the same order, for dispatching without matching in
+/// constant and allocation-free contexts. When the discriminants are the stable tags, as for
+/// `repr_c`, the index is found by searching for the tag.
+pub fn lookup_impl(
+    visibility: &Visibility,
+    enum_name: &Ident,
+    cases: &[Case],
+    signature: &Signature,
+    tagged: bool,
+) -> TokenStream {
+    let units = cases.iter().map(|case| {
+        let cfgs = case.cfgs();
+        quote!(#(#cfgs)* ())
+    });
+    let index = if cases.is_empty() {
+        quote!(match self {})
+    } else if tagged {
+        let tags = cases.iter().map(|case| {
+            let cfgs = case.cfgs();
+            let tag = case.options.tag().unwrap();
+            quote!(#(#cfgs)* #tag)
+        });
+        quote! {
+            const TAGS: &[u32] = &[#(#tags),*];
+            let tag = self as u32;
+            let mut index = 0;
+            while TAGS[index] != tag {
+                index += 1;
+            }
+            index
+        }
+    } else {
+        quote!(self as usize)
+    };
+    let lookup = if is_tabulable(cases, signature) {
+        let input_types = signature.inputs.iter().map(|arg| &arg.ty);
+        let output = &signature.output;
+        // Each function is called from a closure, rather than cast to a function pointer, so
+        // that its arguments are coerced as they are by any call, such as from `&mut T` to `&T`.
+        let input_names = signature
+            .inputs
+            .iter()
+            .map(|arg| &arg.ident)
+            .collect::<Vec<_>>();
+        let functions = cases.iter().map(|case| {
+            let cfgs = case.cfgs();
+            let call = case.call(&input_names);
+            quote!(#(#cfgs)* |#(#input_names),*| #call)
+        });
+        Some(quote! {
+            /// The function of each command, indexed by `variant_index`.
+            #visibility const LOOKUP: [fn(#(#input_types),*) #output; #enum_name::COUNT] = [#(#functions),*];
+        })
+    } else {
+        None
+    };
+
+    quote! {
+        impl #enum_name {
+            /// The number of commands.
+            #visibility const COUNT: usize = {
+                let units: &[()] = &[#(#units),*];
+                units.len()
+            };
+
+            #lookup
+
+            /// The position of this command among all of the commands, from `0` to `COUNT`.
+            #visibility const fn variant_index(self) -> usize {
+                #index
+            }
+        }
+    }
+}
+
+pub fn variants_impl(visibility: &Visibility, enum_name: &Ident, cases: &[Case]) -> TokenStream {
+    let variants = cases.iter().map(|case| {
+        let cfgs = case.cfgs();
+        let name = &case.name;
+        quote!(#(#cfgs)* #enum_name::#name)
+    });
+
+    quote! {
+        impl #enum_name {
+            #visibility fn variants() -> &'static [#enum_name] {
+                &[#(#variants),*]
+            }
+        }
+    }
+}
+
+/// Generates a set type for a fieldless enum, backed by a bitmask with one bit per case.
+pub fn set_impl(
+    visibility: &Visibility,
+    enum_name: &Ident,
+    set_name: &Ident,
+    cases: &[Case],
+) -> TokenStream {
+    let repr = match cases.len() {
+        0..=8 => quote!(u8),
+        9..=16 => quote!(u16),
+        17..=32 => quote!(u32),
+        33..=64 => quote!(u64),
+        _ => quote!(u128),
+    };
+    let cfgs = cases
+        .iter()
+        .map(|case| case.cfgs().collect::<Vec<_>>())
+        .collect::<Vec<_>>();
+    let names = cases.iter().map(|case| &case.name).collect::<Vec<_>>();
+    let indices = (0..cases.len() as u32).collect::<Vec<_>>();
+
+    quote! {
+        #[derive(
+            ::core::clone::Clone,
+            ::core::marker::Copy,
+            ::core::cmp::PartialEq,
+            ::core::cmp::Eq,
+            ::core::hash::Hash,
+            ::core::default::Default,
+            ::core::fmt::Debug,
+        )]
+        #visibility struct #set_name(#repr);
+
+        impl #set_name {
+            #visibility const fn new() -> Self {
+                #set_name(0)
+            }
+
+            fn bit(variant: &#enum_name) -> #repr {
+                match *variant {
+                    #(#(#cfgs)* #enum_name::#names => 1 << #indices,)*
+                }
+            }
+
+            fn variant(index: u32) -> ::core::option::Option<#enum_name> {
+                match index {
+                    #(#(#cfgs)* #indices => ::core::option::Option::Some(#enum_name::#names),)*
+                    _ => ::core::option::Option::None,
+                }
+            }
+
+            /// Adds a command to the set, returning whether it was not already present.
+            #visibility fn insert(&mut self, variant: #enum_name) -> bool {
+                let inserted = !self.contains(&variant);
+                self.0 |= Self::bit(&variant);
+                inserted
+            }
+
+            /// Removes a command from the set, returning whether it was present.
+            #visibility fn remove(&mut self, variant: &#enum_name) -> bool {
+                let removed = self.contains(variant);
+                self.0 &= !Self::bit(variant);
+                removed
+            }
+
+            #visibility fn contains(&self, variant: &#enum_name) -> bool {
+                self.0 & Self::bit(variant) != 0
+            }
+
+            #visibility fn len(&self) -> usize {
+                self.0.count_ones() as usize
+            }
+
+            #visibility fn is_empty(&self) -> bool {
+                self.0 == 0
+            }
+
+            #visibility fn clear(&mut self) {
+                self.0 = 0;
+            }
+
+            #visibility fn iter(&self) -> impl ::core::iter::Iterator<Item = #enum_name> {
+                let bits = self.0;
+                ::core::iter::Iterator::filter_map(0..#repr::BITS, move |index| {
+                    if bits & (1 << index) == 0 {
+                        ::core::option::Option::None
+                    } else {
+                        Self::variant(index)
+                    }
+                })
+            }
+        }
+
+        impl ::core::iter::Extend<#enum_name> for #set_name {
+            fn extend<I>(&mut self, iter: I)
+            where
+                I: ::core::iter::IntoIterator<Item = #enum_name>,
+            {
+                for variant in iter {
+                    self.insert(variant);
+                }
+            }
+        }
+
+        impl ::core::iter::FromIterator<#enum_name> for #set_name {
+            fn from_iter<I>(iter: I) -> Self
+            where
+                I: ::core::iter::IntoIterator<Item = #enum_name>,
+            {
+                let mut set = Self::new();
+                ::core::iter::Extend::extend(&mut set, iter);
+                set
+            }
+        }
+    }
+}
@@ -0,0 +1,258 @@
+use proc_macro2::Span;
+use syn::{
+    punctuated::Punctuated,
+    visit_mut::{self, VisitMut},
+    Generics, Ident, Item, Path, PathSegment, TraitBound, Type, TypePath, UseTree,
+};
+
+/// The items which the `use` declarations of a module bring into scope, by the name they are
+/// brought in as, with their paths from beside the module. The enum is generated beside the
+/// module, so the types of captured arguments which name these items are rewritten to these
+/// paths.
+#[derive(Clone, Default)]
+pub struct Imports {
+    paths: Vec<(Ident, Path)>,
+}
+
+/// Whether `item` declares something named `name`, which a path in the module may start from.
+fn declares(item: &Item, name: &Ident) -> bool {
+    match item {
+        Item::Const(item) => item.ident == *name,
+        Item::Enum(item) => item.ident == *name,
+        Item::Fn(item) => item.sig.ident == *name,
+        Item::Mod(item) => item.ident == *name,
+        Item::Static(item) => item.ident == *name,
+        Item::Struct(item) => item.ident == *name,
+        Item::Trait(item) => item.ident == *name,
+        Item::Type(item) => item.ident == *name,
+        Item::Union(item) => item.ident == *name,
+        _ => false,
+    }
+}
+
+/// Collects the names brought into scope by a `use` tree, with the path each one is imported
+/// from, as it is written.
+fn collect(tree: &UseTree, prefix: &mut Vec<Ident>, imported: &mut Vec<(Ident, Vec<Ident>)>) {
+    let mut import = |ident: &Ident, name: &Ident| {
+        let mut path = prefix.clone();
+        if ident != "self" {
+            path.push(ident.clone());
+        }
+        if !path.is_empty() {
+            imported.push((name.clone(), path));
+        }
+    };
+    match tree {
+        UseTree::Path(tree) => {
+            prefix.push(tree.ident.clone());
+            collect(&tree.tree, prefix, imported);
+            prefix.pop();
+        }
+        UseTree::Name(tree) if tree.ident == "self" => {
+            if let Some(name) = prefix.last() {
+                import(&tree.ident, &name.clone());
+            }
+        }
+        UseTree::Name(tree) => import(&tree.ident, &tree.ident),
+        UseTree::Rename(tree) if tree.rename == "_" => {}
+        UseTree::Rename(tree) => import(&tree.ident, &tree.rename),
+        // The names brought in by a glob cannot be known.
+        UseTree::Glob(..) => {}
+        UseTree::Group(group) => {
+            for tree in &group.items {
+                collect(tree, prefix, imported);
+            }
+        }
+    }
+}
+
+impl Imports {
+    /// The imports of a module, from its `items`, where `mod_path` is the path to the module from
+    /// beside it.
+    pub fn new(mod_path: &[&Ident], items: &[Item]) -> Self {
+        let mut imported = vec![];
+        for item in items {
+            let item = match item {
+                Item::Use(item) => item,
+                _ => continue,
+            };
+            let mut names = vec![];
+            collect(&item.tree, &mut vec![], &mut names);
+            let leading_colon = item.leading_colon.is_some();
+            imported.extend(
+                names
+                    .into_iter()
+                    .map(|(name, segments)| (name, leading_colon, segments)),
+            );
+        }
+        let paths = imported
+            .iter()
+            .filter_map(|(name, leading_colon, segments)| {
+                let (leading_colon, segments) =
+                    unchain(items, &imported, *leading_colon, segments.clone())?;
+                let path = resolve(mod_path, items, leading_colon, segments)?;
+                Some((name.clone(), path))
+            })
+            .collect();
+        Imports { paths }
+    }
+
+    /// Rewrites the paths in `ty` which start from an imported name to the path of the import,
+    /// returning the type if it changed. Type parameters of `generics` are not rewritten.
+    pub fn qualify(&self, ty: &Type, generics: &Generics) -> Option<Type> {
+        let mut qualified = ty.clone();
+        let mut visitor = Qualify {
+            imports: self,
+            generics: generics.type_params().map(|param| &param.ident).collect(),
+            changed: false,
+        };
+        visitor.visit_type_mut(&mut qualified);
+        if visitor.changed {
+            Some(qualified)
+        } else {
+            None
+        }
+    }
+}
+
+/// The path of an import as written from the module, with a first segment which names another
+/// import, as in `use model::Item;` after `use super::model;`, replaced by the path of that
+/// import, or `None` if the imports refer to each other in a cycle.
+fn unchain(
+    items: &[Item],
+    imported: &[(Ident, bool, Vec<Ident>)],
+    mut leading_colon: bool,
+    mut segments: Vec<Ident>,
+) -> Option<(bool, Vec<Ident>)> {
+    for _ in 0..=imported.len() {
+        let first = &segments[0];
+        if leading_colon
+            || first == "crate"
+            || first == "self"
+            || first == "super"
+            || items.iter().any(|item| declares(item, first))
+        {
+            return Some((leading_colon, segments));
+        }
+        // An import which starts from its own name, as `use serde;` does, names a crate.
+        let (.., import_colon, import) = match imported
+            .iter()
+            .find(|(name, .., import)| name == first && import[0] != *name)
+        {
+            Some(import) => import,
+            None => return Some((leading_colon, segments)),
+        };
+        leading_colon = *import_colon;
+        segments = import
+            .iter()
+            .cloned()
+            .chain(segments.into_iter().skip(1))
+            .collect();
+    }
+    None
+}
+
+/// The path, from beside the module at `mod_path`, of an item imported into the module from
+/// `segments`.
+fn resolve(
+    mod_path: &[&Ident],
+    items: &[Item],
+    leading_colon: bool,
+    segments: Vec<Ident>,
+) -> Option<Path> {
+    let ident = |name: &str| Ident::new(name, Span::call_site());
+    let first = &segments[0];
+    let (leading_colon, segments) = if leading_colon || first == "crate" {
+        (leading_colon, segments)
+    } else if first == "self" {
+        let path = mod_path.iter().map(|&ident| ident.clone());
+        (false, path.chain(segments.into_iter().skip(1)).collect())
+    } else if first == "super" {
+        let supers = segments
+            .iter()
+            .take_while(|&ident| ident == "super")
+            .count();
+        let rest = segments.into_iter().skip(supers);
+        let path = match mod_path.len().checked_sub(supers) {
+            Some(0) => std::iter::once(ident("self")).chain(rest).collect(),
+            Some(kept) => mod_path[..kept]
+                .iter()
+                .map(|&ident| ident.clone())
+                .chain(rest)
+                .collect(),
+            None => std::iter::repeat_n(ident("super"), supers - mod_path.len())
+                .chain(rest)
+                .collect(),
+        };
+        (false, path)
+    } else if first == "Self" {
+        return None;
+    } else if items.iter().any(|item| declares(item, first)) {
+        let path = mod_path.iter().map(|&ident| ident.clone());
+        (false, path.chain(segments).collect())
+    } else {
+        // Otherwise the path starts from a crate, or from a name brought in by a glob import,
+        // either of which may be named beside the module in the same way.
+        (false, segments)
+    };
+    Some(Path {
+        leading_colon: if leading_colon {
+            Some(Default::default())
+        } else {
+            None
+        },
+        segments: segments
+            .into_iter()
+            .map(PathSegment::from)
+            .collect::<Punctuated<_, _>>(),
+    })
+}
+
+struct Qualify<'a> {
+    imports: &'a Imports,
+    generics: Vec<&'a Ident>,
+    changed: bool,
+}
+
+impl Qualify<'_> {
+    fn qualify_path(&mut self, path: &mut Path) {
+        if path.leading_colon.is_some() {
+            return;
+        }
+        let first = &path.segments[0];
+        if self.generics.contains(&&first.ident) {
+            return;
+        }
+        let imported = match self
+            .imports
+            .paths
+            .iter()
+            .find(|(name, ..)| *name == first.ident)
+        {
+            Some((.., imported)) => imported,
+            None => return,
+        };
+        let mut qualified = imported.clone();
+        let mut segments = std::mem::take(&mut path.segments).into_iter();
+        let first = segments.next().unwrap();
+        qualified.segments.last_mut().unwrap().arguments = first.arguments;
+        qualified.segments.extend(segments);
+        *path = qualified;
+        self.changed = true;
+    }
+}
+
+impl VisitMut for Qualify<'_> {
+    fn visit_type_path_mut(&mut self, ty: &mut TypePath) {
+        // The positions of qualified paths are counted in segments, so they are left as they are.
+        if ty.qself.is_none() {
+            self.qualify_path(&mut ty.path);
+        }
+        visit_mut::visit_type_path_mut(self, ty);
+    }
+
+    fn visit_trait_bound_mut(&mut self, bound: &mut TraitBound) {
+        self.qualify_path(&mut bound.path);
+        visit_mut::visit_trait_bound_mut(self, bound);
+    }
+}
@@ -0,0 +1,44 @@
+use super::Signature;
+
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{Ident, ReturnType, Visibility};
+
+/// Generates methods to read a command from JSON, such as the payload of a webhook or an entry of
+/// a configuration file, and to call it. The enum must implement `Deserialize`.
+pub fn json_impl(
+    visibility: &Visibility,
+    enum_name: &Ident,
+    signature: &Signature,
+    krate: &TokenStream,
+) -> TokenStream {
+    let serde_json = quote!(#krate::__private::serde_json);
+    let generics = &signature.generics;
+    let where_clause = &signature.generics.where_clause;
+    let inputs = signature.inputs.iter();
+    let input_names = signature.inputs.iter().map(|arg| &arg.ident);
+    let output_type = match &signature.output {
+        ReturnType::Default => quote!(()),
+        ReturnType::Type(.., ty) => quote!(#ty),
+    };
+
+    quote! {
+        impl #enum_name {
+            /// Reads a command from a string of JSON.
+            #visibility fn from_json_str(
+                json: &str,
+            ) -> ::core::result::Result<Self, #krate::JsonError> {
+                #serde_json::from_str(json)
+            }
+
+            /// Reads a command from a string of JSON, and calls it.
+            #visibility fn dispatch_json #generics (
+                json: &str,
+                #(#inputs,)*
+            ) -> ::core::result::Result<#output_type, #krate::JsonError> #where_clause {
+                let command = Self::from_json_str(json)?;
+                ::core::result::Result::Ok(#enum_name::call(command, #(#input_names),*))
+            }
+        }
+    }
+}
@@ -0,0 +1,43 @@
+use proc_macro2::TokenStream;
+use proc_macro_error::abort;
+use quote::{format_ident, quote};
+use syn::{ExprClosure, Pat, ReturnType};
+
+/// Lifts a closure into a value which implements `DeFn`, taking the closure's parameters as its
+/// input. The closure's captured variables are the captured arguments of the value.
+pub fn defn(closure: ExprClosure) -> TokenStream {
+    let types = closure
+        .inputs
+        .iter()
+        .map(|input| match input {
+            Pat::Type(pat) => &pat.ty,
+            pat => abort!(pat, "the parameters of `defn!` closures must have types"),
+        })
+        .collect::<Vec<_>>();
+    let args = (0..types.len())
+        .map(|index| format_ident!("__arg{}", index))
+        .collect::<Vec<_>>();
+    let output_type = match &closure.output {
+        ReturnType::Default => quote!(()),
+        ReturnType::Type(.., ty) => quote!(#ty),
+    };
+
+    quote! {
+        {
+            struct Lifted<F>(F);
+
+            impl<F> ::defunctionalize::DeFn<(#(#types),*)> for Lifted<F>
+            where
+                F: ::core::ops::FnOnce(#(#types),*) -> #output_type,
+            {
+                type Output = #output_type;
+
+                fn call(self, (#(#args),*): (#(#types),*)) -> Self::Output {
+                    (self.0)(#(#args),*)
+                }
+            }
+
+            Lifted(#closure)
+        }
+    }
+}
@@ -1,12 +1,60 @@
-use heck::CamelCase;
 use proc_macro::TokenStream;
-use proc_macro_error::{diagnostic, Diagnostic, Level::Error};
+use proc_macro2::{Delimiter, Literal, TokenStream as TokenStream2, TokenTree};
+use proc_macro_error::{abort, abort_call_site, Diagnostic};
 use quote::{format_ident, quote};
-use syn::{spanned::Spanned, FnArg, Ident, Item, ItemMod, Pat, ReturnType, Visibility};
+use syn::{
+    parse_quote, Attribute, DeriveInput, ExprClosure, Generics, Ident, ItemFn, ItemMod, Path,
+    ReturnType,
+};
 
+mod actor;
+mod args_struct;
+mod async_impl;
+mod case;
+mod case_options;
+mod chunk;
+mod clap_impl;
+mod clone;
+mod curry;
+mod debug_expand;
+mod default;
+mod derive;
+mod dispatch;
+mod embed;
+mod empty;
+mod eq;
+mod event;
+mod ffi;
+mod fieldless;
+mod imports;
+mod json;
+mod lambda;
+mod markers;
+mod module;
+mod naming;
+mod options;
+mod partial;
+mod partition;
+mod priority;
+mod proto;
+mod redact;
+mod reflect;
+mod register;
+mod repr_c;
+mod route;
+mod serde_impl;
 mod signature;
 mod simple_arg;
+mod single;
+mod size;
+mod type_name;
+mod undo;
+mod warning;
 
+use case::Case;
+use case_options::CaseOptions;
+use imports::Imports;
+use options::Args;
 use signature::Signature;
 use simple_arg::SimpleArg;
 
@@ -14,100 +62,209 @@ use simple_arg::SimpleArg;
 #[proc_macro_error::proc_macro_error]
 pub fn defunctionalize(attr: TokenStream, item: TokenStream) -> TokenStream {
     let mut mod_item = syn::parse_macro_input!(item as ItemMod);
-    let signature = syn::parse_macro_input!(attr as Signature);
+    let attr_tokens = TokenStream2::from(attr.clone());
+    let Args {
+        signature,
+        mut options,
+    } = syn::parse_macro_input!(attr as Args);
 
-    let items = match &mod_item.content {
+    if options.partition {
+        if !options.includes.is_empty() {
+            abort_call_site!("`include` cannot be used with `partition`");
+        }
+        if !options.expects.is_empty() {
+            abort_call_site!("`expect` cannot be used with `partition`");
+        }
+        return partition::partition(
+            attr_tokens,
+            &signature,
+            &options.krate(),
+            &mod_item,
+            &options.merge,
+        )
+        .into();
+    }
+
+    let mut attrs = std::mem::take(&mut mod_item.attrs);
+    // When the module has another `defunctionalize` attribute, another enum is generated from it,
+    // so the module is passed on to that attribute as it was given, with all of its attributes,
+    // rather than emitted as it is here. Its other attributes apply to both enums.
+    let mut passed_on = if attrs.iter().any(module::is_defunctionalize) {
+        let module = ItemMod {
+            attrs: attrs.clone(),
+            ..mod_item.clone()
+        };
+        attrs.retain(|attr| !module::is_defunctionalize(attr));
+        Some(module)
+    } else {
+        None
+    };
+    let cfgs = attrs
+        .iter()
+        .filter(|attr| attr.path.is_ident("cfg"))
+        .cloned()
+        .collect::<Vec<_>>();
+    mod_item.attrs = attrs
+        .iter()
+        .filter(|attr| attr.path.is_ident("cfg") || attr.path.is_ident("doc"))
+        .cloned()
+        .collect();
+
+    let items = match &mut mod_item.content {
         Some((.., items)) => items,
         None => panic!(),
     };
 
-    let attrs = std::mem::take(&mut mod_item.attrs);
+    let case_attrs = match module::take_case_attrs(items, &options.merge) {
+        Ok(case_attrs) => case_attrs,
+        Err(error) => return error.to_compile_error().into(),
+    };
+    let (reexports, reexport_attrs): (Vec<_>, Vec<_>) = match module::take_reexports(items) {
+        Ok(reexports) => reexports
+            .into_iter()
+            .map(|(name, attrs, options)| (name, (attrs, options)))
+            .unzip(),
+        Err(error) => return error.to_compile_error().into(),
+    };
+    let redacted = case_attrs
+        .iter()
+        .any(|(.., options)| options.args.iter().any(|arg| arg.redact));
+    let derives_debug = (redacted || options.debug_call)
+        && redact::take_derive(&mut options.derives, &mut attrs, "Debug");
+    let skips_eq = case_attrs
+        .iter()
+        .any(|(.., options)| options.args.iter().any(|arg| arg.skip_eq));
+    let derives_eq = skips_eq && redact::take_derive(&mut options.derives, &mut attrs, "PartialEq");
+    let derives_hash = (skips_eq || options.stable_hash)
+        && redact::take_derive(&mut options.derives, &mut attrs, "Hash");
 
     let mod_name = &mod_item.ident;
-    let enum_name = signature
-        .ident
-        .clone()
-        .unwrap_or_else(|| format_ident!("{}", mod_name.to_string().to_camel_case()));
+    let enum_name = options.enum_name(&signature, mod_name);
+    // Names in scripts without case, such as Japanese, are not changed by CamelCase.
+    if enum_name == *mod_name {
+        abort!(
+            mod_name,
+            "the enum would have the same name as the module `{}`",
+            mod_name;
+            help = "name the enum in the signature, as in `fn Name(...)`"
+        );
+    }
 
-    let functions = items
-        .iter()
-        .filter_map(|item| match item {
-            Item::Fn(item) => Some(item),
-            _ => None,
-        })
-        .filter(|item| matches!(item.vis, Visibility::Public(..)))
-        .collect::<Vec<_>>();
+    let reexport = if options.reexport {
+        Some(mod_item.vis.clone())
+    } else {
+        None
+    };
+    if let Some((.., items)) = &mut mod_item.content {
+        module::import_enum(items, &enum_name, reexport.as_ref());
+    }
+    if let Some((.., items)) = passed_on
+        .as_mut()
+        .and_then(|module| module.content.as_mut())
+    {
+        module::import_enum(items, &enum_name, reexport.as_ref());
+    }
 
-    let case_names = functions
-        .iter()
-        .map(|item| item.sig.ident.to_string().to_camel_case())
-        .map(|name| format_ident!("{}", name))
-        .collect::<Vec<_>>();
+    let items = match &mod_item.content {
+        Some((.., items)) => items,
+        None => unreachable!(),
+    };
+    let functions = match module::case_functions(mod_name, items, &options.merge) {
+        Ok(functions) => functions,
+        Err(error) => return error.to_compile_error().into(),
+    };
 
-    let function_names = functions
-        .iter()
-        .map(|item| &item.sig.ident)
-        .collect::<Vec<_>>();
+    let imports = Imports::new(&[mod_name], items);
 
-    let case_arg_names = functions
-        .iter()
-        .map(|item| {
-            item.sig
-                .inputs
-                .iter()
-                .map(|arg| match arg {
-                    FnArg::Receiver(..) => Err(diagnostic!(
-                        arg.span(),
-                        Error,
-                        "defunctionalized functions cannot have receivers"
-                    )),
-                    FnArg::Typed(pat) => Ok(pat.pat.as_ref()),
-                })
-                .map(|pat| match pat? {
-                    Pat::Ident(ident) => Ok(&ident.ident),
-                    pat => Err(diagnostic!(
-                        pat.span(),
-                        Error,
-                        "arguments to defunctionalized functions must be named"
-                    )),
-                })
-                .collect::<Result<Vec<_>, _>>()
-        })
-        .map(|mut args| {
-            match &mut args {
-                Ok(args) => args.truncate(args.len() - signature.inputs.len()),
-                Err(..) => {}
-            }
-            args
-        })
-        .map(|args| {
-            let args = args?;
-            Ok(if args.is_empty() { vec![] } else { vec![args] })
+    // The enum for a group of functions generated by `partition` has only those functions as cases.
+    let selected = |ident: &Ident| {
+        options
+            .cases
+            .as_ref()
+            .is_none_or(|cases| cases.contains(ident))
+    };
+    let cases = functions
+        .into_iter()
+        .zip(case_attrs)
+        .filter(|((.., function), ..)| selected(&function.sig.ident))
+        .map(|((mod_path, imports, item), attrs)| {
+            let mut case = Case::new(mod_path, item, attrs, &signature, options.shared_by_name)?;
+            case.qualify_imports(&imports, &item.sig.generics);
+            Ok(case)
         })
+        .chain(options.expects.iter().map(|function| {
+            let attrs = (function.attrs.clone(), CaseOptions::default());
+            Case::new(
+                quote!(#mod_name),
+                function,
+                attrs,
+                &signature,
+                options.shared_by_name,
+            )
+        }))
+        .chain(
+            module::nested_modules(items, &options.merge)
+                .into_iter()
+                .filter(|(module, ..)| selected(&module.ident))
+                .map(|(module, name)| Ok(Case::nested(quote!(#mod_name), module, &name))),
+        )
+        .chain(reexports.iter().zip(reexport_attrs).map(|(name, attrs)| {
+            let mut case = Case::reexported(quote!(#mod_name), name, attrs);
+            case.qualify_imports(&imports, &Generics::default());
+            Ok(case)
+        }))
+        .chain(
+            options
+                .extras
+                .iter()
+                .map(|extra| Case::extra(extra, &signature)),
+        )
+        .chain(
+            options
+                .includes
+                .iter()
+                .map(|function| Ok(Case::included(function))),
+        )
         .collect::<Result<Vec<_>, Diagnostic>>();
-    let case_arg_names = match case_arg_names {
-        Ok(case_arg_names) => case_arg_names,
+    let cases = match cases {
+        Ok(cases) => cases,
         Err(diagnostic) => diagnostic.abort(),
     };
-
-    let case_arg_types = functions
-        .iter()
-        .map(|item| {
-            item.sig
-                .inputs
-                .iter()
-                .map(|arg| match arg {
-                    FnArg::Receiver(..) => unreachable!(),
-                    FnArg::Typed(pat) => pat.ty.as_ref(),
-                })
-                .collect::<Vec<_>>()
-        })
-        .map(|mut args| {
-            args.truncate(args.len() - signature.inputs.len());
-            args
-        })
-        .map(|args| if args.is_empty() { vec![] } else { vec![args] })
-        .collect::<Vec<_>>();
+    case::check_duplicates(&cases);
+    let empty_warning = if cases.is_empty() {
+        empty::check_empty(mod_name, options.empty)
+    } else {
+        None
+    };
+    let private_warnings = if options.warn_private {
+        Some(module::warn_private(&enum_name, items, &options.merge))
+    } else {
+        None
+    };
+    if options.send && signature.asyncness.is_none() {
+        abort_call_site!("`send` can only be used with an async signature");
+    }
+    if signature.asyncness.is_some() {
+        async_impl::check_options(&[
+            ("actor", options.actor.is_some()),
+            ("apply", options.apply.is_some()),
+            ("args", options.args.is_some()),
+            ("chunked", options.chunked.is_some()),
+            ("curry", options.curry),
+            ("dispatch", !options.dispatches.is_empty()),
+            ("events", options.events),
+            ("ffi", options.ffi.is_some()),
+            ("impl_trait", !options.impl_traits.is_empty()),
+            ("input_struct", options.input_struct.is_some()),
+            ("json", options.json),
+            ("like_trait", options.like_trait.is_some()),
+            ("registry", options.registry),
+            (
+                "route",
+                cases.iter().any(|case| case.options.route.is_some()),
+            ),
+        ]);
+    }
 
     let visibility = &mod_item.vis;
     let generics = &signature.generics;
@@ -119,42 +276,725 @@ pub fn defunctionalize(attr: TokenStream, item: TokenStream) -> TokenStream {
         .iter()
         .map(|arg| &arg.ident)
         .collect::<Vec<&Ident>>();
-    let arg_idents = std::iter::repeat(&input_names);
     let output = &signature.output;
     let output_type = match output {
         ReturnType::Default => quote!(()),
         ReturnType::Type(.., ty) => quote!(#ty),
     };
 
-    let output = quote! {
-        #mod_item
+    let like_trait = options.like_trait.as_ref().map(|name| {
+        name.clone()
+            .unwrap_or_else(|| format_ident!("{}Like", enum_name))
+    });
+    let like_trait_def = like_trait.as_ref().map(|name| {
+        quote! {
+            #visibility trait #name {
+                fn call #generics (self, #inputs) #output #where_clause;
+            }
+        }
+    });
 
-        #(#attrs)*
-        #visibility enum #enum_name {
-            #(#case_names#((#(#case_arg_types),*))*),*
+    let impl_traits = options
+        .impl_traits
+        .iter()
+        .cloned()
+        .chain(like_trait.map(Path::from))
+        .collect::<Vec<_>>();
+    let trait_impls = impl_traits.iter().map(|trait_path| {
+        quote! {
+            impl #trait_path for #enum_name {
+                fn call #generics (self, #inputs) #output #where_clause {
+                    #enum_name::call(self, #(#input_names),*)
+                }
+            }
+        }
+    });
+
+    let krate = options.krate();
+
+    // A `#[repr(C, u32)]` enum identifies its functions by their stable tags, if they have them.
+    let tagged = serde_impl::validate_tags(&cases);
+    let tagged_repr = options.repr_c && tagged;
+    let derives_deserialize = serde_impl::derives(&options.derives, &attrs, "Deserialize");
+    let redacts_serde = cases
+        .iter()
+        .flat_map(|case| &case.captures)
+        .any(|capture| capture.options.redact_serde);
+    if redacts_serde && (tagged || !serde_impl::derives(&options.derives, &attrs, "Serialize")) {
+        abort_call_site!(
+            "`redact(serde)` requires the enum to derive `Serialize`, and its functions not to have tags"
+        );
+    }
+    let serde_defaults = if derives_deserialize && !tagged {
+        Some(&enum_name)
+    } else {
+        None
+    };
+    let variants = cases.iter().map(|case| {
+        let alias_attr = serde_impl::alias_attr(case, derives_deserialize, tagged);
+        let variant = case.variant(&krate, serde_defaults);
+        if tagged_repr {
+            let tag = Literal::u32_unsuffixed(case.options.tag().unwrap());
+            quote!(#alias_attr #variant = #tag)
+        } else {
+            quote!(#alias_attr #variant)
+        }
+    });
+    let arms = cases
+        .iter()
+        .map(|case| {
+            let pattern = case.pattern();
+            let call = case.call(input_names);
+            let cfgs = case.cfgs();
+            quote! {
+                #(#cfgs)*
+                #enum_name::#pattern => {
+                    #call
+                }
+            }
+        })
+        .collect::<Vec<_>>();
+
+    let by_ref = clone::is_all_copy(&cases);
+    let fieldless = fieldless::is_fieldless(&cases);
+
+    // With the `const-trait` feature, `call` is `const` when every function is, so that commands
+    // can be called in constant contexts. Function pointers cannot be called there, nor can the
+    // helper functions of `chunked` be known to be `const`, so the cases are matched directly. An
+    // enum without cases is not `const`, as there are no functions to make it so.
+    let constness = if cfg!(feature = "const-trait")
+        && signature.asyncness.is_none()
+        && !cases.is_empty()
+        && cases.iter().all(|case| case.constness)
+    {
+        Some(quote!(const))
+    } else {
+        None
+    };
+
+    let (call_body, ref_call_body, chunk_helpers) = match options.chunked {
+        _ if constness.is_some() => (
+            quote!(match self { #(#arms)* }),
+            quote!(match *self { #(#arms)* }),
+            None,
+        ),
+        _ if fieldless && fieldless::is_tabulable(&cases, &signature) => (
+            fieldless::table_dispatch(&enum_name, &signature, quote!(self)),
+            fieldless::table_dispatch(&enum_name, &signature, quote!(*self)),
+            None,
+        ),
+        Some(size) => {
+            let (call_body, helpers) =
+                chunk::chunked_dispatch(&enum_name, &arms, &signature, size, false);
+            let (ref_call_body, ref_helpers) = if by_ref {
+                chunk::chunked_dispatch(&enum_name, &arms, &signature, size, true)
+            } else {
+                Default::default()
+            };
+            let index_impl = chunk::index_impl(&enum_name, &cases);
+            (
+                call_body,
+                ref_call_body,
+                Some(quote! {
+                    impl #enum_name {
+                        #index_impl
+                        #helpers
+                        #ref_helpers
+                    }
+                }),
+            )
+        }
+        None => (
+            quote!(match self { #(#arms)* }),
+            quote!(match *self { #(#arms)* }),
+            None,
+        ),
+    };
+
+    // With `input_struct`, `DeFn` takes the arguments as a struct instead of a tuple.
+    let input_struct_impl = options.input_struct.as_ref().map(|name| {
+        let input_name = name
+            .clone()
+            .unwrap_or_else(|| format_ident!("{}Input", enum_name));
+        args_struct::input_struct_impl(
+            visibility,
+            &enum_name,
+            &input_name,
+            &signature,
+            &krate,
+            constness.as_ref(),
+            if by_ref { Some(&ref_call_body) } else { None },
+        )
+    });
+
+    // Commands in read-only tables can be called by reference when their arguments can be
+    // copied out.
+    let ref_defn_impl = if by_ref && options.input_struct.is_none() {
+        Some(quote! {
+            impl #generics #constness #krate::DeFn<(#(#input_types),*)> for &#enum_name #where_clause {
+                type Output = #output_type;
+
+                fn call (self, (#(#input_names),*): (#(#input_types),*)) #output {
+                    #ref_call_body
+                }
+            }
+        })
+    } else {
+        None
+    };
+
+    // With a single input, `DeFn` takes it directly, but also as a 1-tuple, so that generic code
+    // can pass inputs as tuples of any length. Both can only be implemented when the input cannot
+    // itself be a 1-tuple.
+    let tuple_defn_impl = match &input_types[..] {
+        [ty] if !signature.is_type_param(ty) && options.input_struct.is_none() => {
+            let name = &input_names[0];
+            let ref_impl = if by_ref {
+                Some(quote! {
+                    impl #generics #constness #krate::DeFn<(#ty,)> for &#enum_name #where_clause {
+                        type Output = #output_type;
+
+                        fn call (self, (#name,): (#ty,)) #output {
+                            #krate::DeFn::call(self, #name)
+                        }
+                    }
+                })
+            } else {
+                None
+            };
+            Some(quote! {
+                impl #generics #constness #krate::DeFn<(#ty,)> for #enum_name #where_clause {
+                    type Output = #output_type;
+
+                    fn call (self, (#name,): (#ty,)) #output {
+                        #enum_name::call(self, #name)
+                    }
+                }
+
+                #ref_impl
+            })
+        }
+        _ => None,
+    };
+
+    let dispatch_impls = options
+        .dispatches
+        .iter()
+        .map(|dispatch| dispatch::dispatch_impl(&enum_name, &cases, &signature, dispatch));
+
+    let embed_impls = options
+        .embed_into
+        .iter()
+        .map(|target| embed::embed_impl(&enum_name, target));
+
+    let clone_impl = if options.auto_clone {
+        Some(clone::clone_impl(&enum_name, &cases))
+    } else {
+        None
+    };
+
+    let derives = &options.derives;
+    let derive_attr = if derives.is_empty() {
+        None
+    } else {
+        Some(quote!(#[derive(#(#derives),*)]))
+    };
+    let fieldless_derive_attr = if fieldless {
+        let mut implemented = vec![];
+        if options.auto_clone {
+            implemented.extend(["Clone", "Copy"]);
+        }
+        if options.stable_hash {
+            implemented.push("Hash");
+        }
+        fieldless::derive_attr(derives, &attrs, &implemented)
+    } else {
+        None
+    };
+
+    let serde_impls = serde_impl::serde_impls(&enum_name, &cases, &krate);
+    let postcard_impl = if options.postcard {
+        Some(serde_impl::postcard_impl(
+            visibility, &enum_name, &cases, &krate,
+        ))
+    } else {
+        None
+    };
+    let markers_impl = options.markers.as_ref().map(|name| {
+        let markers_name = name.clone().unwrap_or_else(|| {
+            format_ident!("{}_markers", naming::snake_case(&enum_name.to_string()))
+        });
+        markers::markers_impl(visibility, &enum_name, &markers_name, &cases)
+    });
+    let roundtrip_tests_impl = options.roundtrip_tests.as_ref().map(|name| {
+        let serializable = derives_deserialize
+            && serde_impl::derives(&options.derives, &attrs, "Serialize");
+        if !tagged && !serializable {
+            abort_call_site!(
+                "`roundtrip_tests` requires the enum to derive `Serialize` and `Deserialize`, or its functions to have tags"
+            );
+        }
+        let tests_name = name.clone().unwrap_or_else(|| {
+            format_ident!("{}_roundtrip_tests", naming::snake_case(&enum_name.to_string()))
+        });
+        serde_impl::roundtrip_tests_impl(
+            &enum_name,
+            &tests_name,
+            &cases,
+            options.serde.is_none(),
+            &krate,
+        )
+    });
+    let proto_impl = options.protobuf.as_ref().map(|name| {
+        let proto_name = name.clone().unwrap_or_else(|| {
+            format_ident!("{}_proto", naming::snake_case(&enum_name.to_string()))
+        });
+        proto::proto_impl(visibility, &enum_name, &proto_name, &cases, &krate)
+    });
+    let version_impl = options
+        .version
+        .as_ref()
+        .map(|version| serde_impl::version_impl(visibility, &enum_name, version, &krate));
+    let serde_attr = options
+        .serde
+        .as_ref()
+        .map(|serde| serde_impl::serde_attr(serde, &cases));
+
+    let size_assertions = options
+        .max_size
+        .map(|max_size| size::size_assertions(&cases, max_size));
+
+    let reflect_impl = if options.reflect {
+        Some(reflect::reflect_impl(
+            visibility, &enum_name, &cases, &krate,
+        ))
+    } else {
+        None
+    };
+    // `stable_hash` implements `Hash` itself.
+    let derives_hash = derives_hash && !options.stable_hash;
+    let eq_impl = if derives_eq || derives_hash {
+        Some(eq::eq_impl(&enum_name, &cases, derives_eq, derives_hash))
+    } else {
+        None
+    };
+
+    let stable_hash_impl = if options.stable_hash {
+        Some(eq::stable_hash_impl(visibility, &enum_name, &cases, &krate))
+    } else {
+        None
+    };
+
+    let debug_impl = if options.debug_call {
+        Some(redact::debug_call_impl(&enum_name, &cases))
+    } else if derives_debug {
+        Some(redact::debug_impl(&enum_name, &cases))
+    } else {
+        None
+    };
+    let audit_impl = if options.audit {
+        Some(reflect::audit_impl(visibility, &enum_name, &cases, &krate))
+    } else {
+        None
+    };
+
+    let variants_impl = if fieldless {
+        Some(fieldless::variants_impl(visibility, &enum_name, &cases))
+    } else {
+        None
+    };
+    let lookup_impl = if fieldless {
+        Some(fieldless::lookup_impl(
+            visibility,
+            &enum_name,
+            &cases,
+            &signature,
+            tagged_repr,
+        ))
+    } else {
+        None
+    };
+
+    let invert_impl = undo::invert_impl(visibility, &enum_name, &cases, &krate);
+
+    let ordering_impl = priority::ordering_impl(visibility, &enum_name, &cases, &krate);
+    let weight_impl = priority::weight_impl(visibility, &enum_name, &cases);
+
+    let default_impl = default::default_impl(&enum_name, &cases);
+    // The inherent methods generated for the enum, each with whether it is generated, which the
+    // constructors of functions with defaults must not be named the same as.
+    let any_case =
+        |option: fn(&CaseOptions) -> bool| cases.iter().any(|case| option(&case.options));
+    let routed = any_case(|options| options.route.is_some());
+    let methods = [
+        ("call", true),
+        ("into_closure", signature.asyncness.is_none()),
+        ("variants", fieldless),
+        ("variant_index", fieldless),
+        ("priority", any_case(|options| options.priority.is_some())),
+        ("weight", any_case(|options| options.cost.is_some())),
+        ("invert", any_case(|options| options.undo.is_some())),
+        ("name", options.reflect),
+        ("arity", options.reflect),
+        ("arg_names", options.reflect),
+        ("description", options.reflect),
+        ("audit_record", options.audit),
+        ("tag", options.repr_c),
+        ("arg_offsets", options.repr_c),
+        ("encode", options.postcard),
+        ("decode", options.postcard),
+        ("schema_version", options.version.is_some()),
+        ("encode_proto", options.protobuf.is_some()),
+        ("decode_proto", options.protobuf.is_some()),
+        ("from_json_str", options.json),
+        ("dispatch_json", options.json),
+        ("call_registered", options.registry),
+        ("registrations", options.registry),
+        ("routes", routed),
+        ("from_request", routed),
+        ("dispatch_request", routed),
+        ("call_curried", options.curry),
+        ("call_with", options.args.is_some()),
+        ("actor", options.actor.is_some()),
+        ("apply", options.apply.is_some() || options.events),
+    ];
+    let arg_defaults_impl = default::arg_defaults_impl(visibility, &enum_name, &cases, &methods);
+
+    let set_impl = options.set.as_ref().map(|name| {
+        if !fieldless {
+            abort_call_site!("`set` can only be used when no function captures arguments");
+        }
+        if cases.len() > 128 {
+            abort_call_site!("`set` supports at most 128 functions");
+        }
+        let set_name = name
+            .clone()
+            .unwrap_or_else(|| format_ident!("{}Set", enum_name));
+        fieldless::set_impl(visibility, &enum_name, &set_name, &cases)
+    });
+
+    let registry_impl = if options.registry {
+        Some(register::registry_impl(
+            visibility, &enum_name, &signature, &krate,
+        ))
+    } else {
+        None
+    };
+
+    let actor_impl = options.actor.as_ref().map(|name| {
+        let handle_name = name
+            .clone()
+            .unwrap_or_else(|| format_ident!("{}Handle", enum_name));
+        actor::actor_impl(
+            visibility,
+            &enum_name,
+            &handle_name,
+            &cases,
+            &signature,
+            &krate,
+        )
+    });
+
+    let ffi_impl = options.ffi.as_ref().map(|name| {
+        let function_name = name.clone().unwrap_or_else(|| {
+            format_ident!("{}_dispatch", naming::snake_case(&enum_name.to_string()))
+        });
+        ffi::ffi_impl(
+            visibility,
+            &enum_name,
+            &function_name,
+            &cases,
+            &signature,
+            &krate,
+        )
+    });
+
+    let routes_impl = route::routes_impl(visibility, &enum_name, &cases, &signature, &krate);
+
+    let event_impl = if options.events {
+        if options.apply.is_some() {
+            abort_call_site!(
+                "`events` cannot be used with `apply`, which generates a method of the same name"
+            );
+        }
+        match event::apply_impl(visibility, &enum_name, &signature) {
+            Some(event_impl) => Some(event_impl),
+            None => abort_call_site!(
+                "`events` can only be used with a signature like `fn(state: &mut State)`"
+            ),
+        }
+    } else {
+        None
+    };
+
+    let json_impl = if options.json {
+        Some(json::json_impl(visibility, &enum_name, &signature, &krate))
+    } else {
+        None
+    };
+
+    let apply_impl = options.apply.as_ref().map(|name| {
+        let applied_name = name
+            .clone()
+            .unwrap_or_else(|| format_ident!("{}Applied", enum_name));
+        partial::apply_impl(visibility, &enum_name, &applied_name, &signature, &krate)
+    });
+
+    let args_impl = options.args.as_ref().map(|name| {
+        let args_name = name
+            .clone()
+            .unwrap_or_else(|| format_ident!("{}Args", enum_name));
+        args_struct::args_impl(visibility, &enum_name, &args_name, &signature)
+    });
+
+    let curry_impl = if options.curry {
+        Some(curry::curry_impl(visibility, &enum_name, &signature))
+    } else {
+        None
+    };
+
+    let subcommand_impl = if options.subcommand {
+        Some(clap_impl::subcommand_impl(&enum_name, &cases, &krate))
+    } else {
+        None
+    };
+
+    let (repr_attr, repr_c_impl) = if options.repr_c {
+        if cases.is_empty() {
+            abort_call_site!("`repr_c` requires at least one function");
         }
+        // Without any captured arguments, the enum is only its tag.
+        let repr = if fieldless {
+            quote!(#[repr(u32)])
+        } else {
+            quote!(#[repr(C, u32)])
+        };
+        (
+            Some(repr),
+            Some(repr_c::repr_c_impl(visibility, &enum_name, &cases)),
+        )
+    } else {
+        (None, None)
+    };
+
+    let json_schema_attr = if options.json_schema {
+        let schemars = quote!(#krate::__private::schemars);
+        let schemars_str = schemars.to_string();
+        Some(quote! {
+            #[derive(#schemars::JsonSchema)]
+            #[schemars(crate = #schemars_str)]
+        })
+    } else {
+        None
+    };
 
-        impl #generics defunctionalize::DeFn<(#(#input_types),*)> for #enum_name #where_clause {
-            type Output = #output_type;
+    let defn_impl = match input_struct_impl {
+        Some(input_struct_impl) => input_struct_impl,
+        None => quote! {
+            impl #generics #constness #krate::DeFn<(#(#input_types),*)> for #enum_name #where_clause {
+                type Output = #output_type;
 
-            fn call (self, (#(#input_names),*): (#(#input_types),*)) #output {
-                self.call(#(#input_names),*)
+                fn call (self, (#(#input_names),*): (#(#input_types),*)) #output {
+                    #enum_name::call(self, #(#input_names),*)
+                }
             }
+        },
+    };
+
+    let call_impl = match signature.asyncness {
+        Some(..) => {
+            let future_name = format_ident!("{}Future", enum_name);
+            let send = if options.send { Some(&krate) } else { None };
+            async_impl::async_impl(
+                visibility,
+                &enum_name,
+                &future_name,
+                &cases,
+                &signature,
+                send,
+            )
         }
+        None => quote! {
+            #defn_impl
+
+            impl #enum_name {
+                #visibility #constness fn call #generics (self, #inputs) #output #where_clause {
+                    #call_body
+                }
 
-        impl #enum_name {
-            #visibility fn call #generics (self, #inputs) #output #where_clause {
-                match self {
-                    #(Self::#case_names#((#(#case_arg_names),*))* => {
-                        #mod_name::#function_names(
-                            #(#(#case_arg_names,)*)*
-                            #(#arg_idents),*
-                        )
-                    })*
+                /// Converts this command into a closure which calls it, without allocating.
+                #visibility fn into_closure #generics (self) -> impl ::core::ops::FnOnce(#(#input_types),*) -> #output_type #where_clause {
+                    move |#inputs| #enum_name::call(self, #(#input_names),*)
                 }
             }
+
+            #chunk_helpers
+
+            #ref_defn_impl
+
+            #tuple_defn_impl
+        },
+    };
+
+    let enum_item = quote! {
+        #derive_attr
+        #fieldless_derive_attr
+        #json_schema_attr
+        #(#attrs)*
+        #serde_attr
+        #repr_attr
+        #visibility enum #enum_name {
+            #(#variants),*
         }
     };
 
+    // The generated items are not written by the user, so lints which the user could only satisfy
+    // by editing them are allowed, such as for arguments which no function takes.
+    let items = quote! {
+        #call_impl
+
+        #empty_warning
+
+        #private_warnings
+
+        #size_assertions
+
+        #variants_impl
+
+        #lookup_impl
+
+        #reflect_impl
+        #debug_impl
+        #eq_impl
+        #stable_hash_impl
+        #audit_impl
+
+        #repr_c_impl
+
+        #set_impl
+
+        #invert_impl
+
+        #ordering_impl
+        #weight_impl
+
+        #default_impl
+        #arg_defaults_impl
+
+        #clone_impl
+
+        #serde_impls
+
+        #postcard_impl
+        #roundtrip_tests_impl
+        #version_impl
+        #markers_impl
+        #proto_impl
+
+        #subcommand_impl
+
+        #registry_impl
+
+        #apply_impl
+
+        #curry_impl
+
+        #args_impl
+
+        #actor_impl
+
+        #ffi_impl
+
+        #routes_impl
+
+        #json_impl
+
+        #event_impl
+
+        #like_trait_def
+
+        #(#trait_impls)*
+
+        #(#dispatch_impls)*
+        #(#embed_impls)*
+    };
+
+    let allow: Attribute = parse_quote!(#[allow(missing_docs, unused_variables, clippy::pedantic)]);
+    let items = with_attrs(items, &[&[allow][..], &cfgs].concat());
+    let generated = quote! {
+        #enum_item
+
+        #items
+    };
+    let module = passed_on.as_ref().unwrap_or(&mod_item);
+    let output = quote! {
+        #module
+
+        #generated
+    };
+    debug_expand::write(options.debug_expand.as_ref(), &enum_name, &output);
+
     output.into()
 }
+
+#[proc_macro_attribute]
+#[proc_macro_error::proc_macro_error]
+pub fn register(attr: TokenStream, item: TokenStream) -> TokenStream {
+    let function = syn::parse_macro_input!(item as ItemFn);
+    let enum_path = syn::parse_macro_input!(attr as Path);
+    register::register(enum_path, function).into()
+}
+
+#[proc_macro_attribute]
+#[proc_macro_error::proc_macro_error]
+pub fn defunctionalize_fn(attr: TokenStream, item: TokenStream) -> TokenStream {
+    let function = syn::parse_macro_input!(item as ItemFn);
+    let signature = syn::parse_macro_input!(attr as Signature);
+    single::defunctionalize_fn(signature, function).into()
+}
+
+#[proc_macro_derive(DeFn, attributes(defn))]
+#[proc_macro_error::proc_macro_error]
+pub fn derive_defn(input: TokenStream) -> TokenStream {
+    let input = syn::parse_macro_input!(input as DeriveInput);
+    derive::derive_defn(input).into()
+}
+
+#[proc_macro]
+#[proc_macro_error::proc_macro_error]
+pub fn defn(input: TokenStream) -> TokenStream {
+    let closure = syn::parse_macro_input!(input as ExprClosure);
+    lambda::defn(closure).into()
+}
+
+/// Applies attributes to each of the generated items, such as the module's `cfg` attributes, so
+/// they are compiled only when the module is.
+///
+/// The items are separated by their tokens rather than parsed again, as they may be large. An item
+/// ends with a `;`, or with a block which is not followed by a `;` or by the rest of a type, as in
+/// `Type<{ N }>`.
+fn with_attrs(generated: TokenStream2, attrs: &[Attribute]) -> TokenStream2 {
+    if attrs.is_empty() {
+        return generated;
+    }
+    let mut output = TokenStream2::new();
+    let mut item = vec![];
+    let mut tokens = generated.into_iter().peekable();
+    while let Some(token) = tokens.next() {
+        let ends = match &token {
+            TokenTree::Punct(punct) => punct.as_char() == ';',
+            TokenTree::Group(group) => {
+                group.delimiter() == Delimiter::Brace
+                    && !matches!(tokens.peek(), Some(TokenTree::Punct(punct)) if punct.as_char() != '#')
+            }
+            _ => false,
+        };
+        item.push(token);
+        if ends {
+            output.extend(quote!(#(#attrs)*));
+            output.extend(item.drain(..));
+        }
+    }
+    output.extend(item);
+    output
+}
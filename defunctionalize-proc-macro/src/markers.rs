@@ -0,0 +1,77 @@
+use super::Case;
+
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{ext::IdentExt, Ident, Index, Visibility};
+
+/// Generates a module of zero-sized types, one for each command, named after its variant, which
+/// implement a sealed `Variant` trait describing the command, so that downstream code can refer
+/// to commands by type.
+pub fn markers_impl(
+    visibility: &Visibility,
+    enum_name: &Ident,
+    markers_name: &Ident,
+    cases: &[Case],
+) -> TokenStream {
+    let markers = cases.iter().enumerate().map(|(index, case)| {
+        let cfgs = case.cfgs().collect::<Vec<_>>();
+        let name = &case.name;
+        let name_str = case.ident.unraw().to_string();
+        let doc = format!("The `{}` command, as a type.", name_str);
+        let types = case.captures.iter().map(|capture| &capture.ty);
+        let command = if case.captures.is_empty() {
+            quote!(super::#enum_name::#name)
+        } else {
+            let fields = (0..case.captures.len()).map(Index::from);
+            quote!(super::#enum_name::#name(#(payload.#fields),*))
+        };
+        quote! {
+            #(#cfgs)*
+            #[doc = #doc]
+            #[derive(::core::clone::Clone, ::core::marker::Copy, ::core::fmt::Debug, ::core::default::Default, ::core::cmp::PartialEq, ::core::cmp::Eq, ::core::hash::Hash)]
+            pub struct #name;
+
+            #(#cfgs)*
+            impl sealed::Sealed for #name {}
+
+            #(#cfgs)*
+            impl Variant for #name {
+                const NAME: &'static str = #name_str;
+                const INDEX: usize = #index;
+                type Payload = (#(#types,)*);
+
+                fn command(payload: Self::Payload) -> super::#enum_name {
+                    #command
+                }
+            }
+        }
+    });
+    let doc = format!("Types standing for each command of `{}`.", enum_name);
+
+    quote! {
+        #[doc = #doc]
+        #visibility mod #markers_name {
+            #[allow(unused_imports)]
+            use super::*;
+
+            mod sealed {
+                pub trait Sealed {}
+            }
+
+            /// A command, as a type. Implemented only by the types in this module.
+            pub trait Variant: sealed::Sealed {
+                /// The name of the function the command calls.
+                const NAME: &'static str;
+                /// The position of the function among all of the enum's functions.
+                const INDEX: usize;
+                /// The captured arguments of the command, as a tuple.
+                type Payload;
+
+                /// The command with the captured arguments `payload`.
+                fn command(payload: Self::Payload) -> super::#enum_name;
+            }
+
+            #(#markers)*
+        }
+    }
+}
@@ -0,0 +1,297 @@
+use super::{case, imports::Imports, warning::warning, Args, CaseOptions};
+
+use proc_macro2::{TokenStream, TokenTree};
+use quote::quote;
+use syn::{
+    ext::IdentExt, parse_quote, Attribute, FnArg, Ident, Item, ItemFn, ItemMod, UseTree, Visibility,
+};
+
+/// The public functions among `items`, which become cases.
+fn functions(items: &[Item]) -> impl Iterator<Item = &ItemFn> {
+    items
+        .iter()
+        .filter_map(|item| match item {
+            Item::Fn(item) => Some(item),
+            _ => None,
+        })
+        .filter(|item| matches!(item.vis, Visibility::Public(..)))
+}
+
+/// The functions among `items` which are not public, and so do not become cases.
+fn private_functions(items: &[Item]) -> impl Iterator<Item = &ItemFn> {
+    items
+        .iter()
+        .filter_map(|item| match item {
+            Item::Fn(item) => Some(item),
+            _ => None,
+        })
+        .filter(|item| !matches!(item.vis, Visibility::Public(..)))
+}
+
+fn functions_mut(items: &mut [Item]) -> impl Iterator<Item = &mut ItemFn> {
+    items
+        .iter_mut()
+        .filter_map(|item| match item {
+            Item::Fn(item) => Some(item),
+            _ => None,
+        })
+        .filter(|item| matches!(item.vis, Visibility::Public(..)))
+}
+
+fn no_module(name: &Ident) -> syn::Error {
+    syn::Error::new(
+        name.span(),
+        format!("cannot merge `{}`: no inline module with that name", name),
+    )
+}
+
+fn merged_items<'a>(items: &'a [Item], name: &Ident) -> syn::Result<&'a [Item]> {
+    items
+        .iter()
+        .find_map(|item| match item {
+            Item::Mod(item) if item.ident == *name => item.content.as_ref(),
+            _ => None,
+        })
+        .map(|(.., items)| items.as_slice())
+        .ok_or_else(|| no_module(name))
+}
+
+fn merged_items_mut<'a>(items: &'a mut [Item], name: &Ident) -> syn::Result<&'a mut [Item]> {
+    items
+        .iter_mut()
+        .find_map(|item| match item {
+            Item::Mod(item) if item.ident == *name => item.content.as_mut(),
+            _ => None,
+        })
+        .map(|(.., items)| items.as_mut_slice())
+        .ok_or_else(|| no_module(name))
+}
+
+/// Rejects the helper attributes of a function which does not become a case, and of its arguments.
+fn reject_options(function: &ItemFn) -> syn::Result<()> {
+    CaseOptions::reject(&function.attrs)?;
+    for arg in &function.sig.inputs {
+        match arg {
+            FnArg::Receiver(arg) => CaseOptions::reject(&arg.attrs)?,
+            FnArg::Typed(arg) => CaseOptions::reject(&arg.attrs)?,
+        }
+    }
+    Ok(())
+}
+
+/// Removes the attributes of each function which becomes a case, in the same order as
+/// `case_functions`.
+pub fn take_case_attrs(
+    items: &mut [Item],
+    merge: &[Ident],
+) -> syn::Result<Vec<(Vec<Attribute>, CaseOptions)>> {
+    for function in private_functions(items) {
+        reject_options(function)?;
+    }
+    let mut case_attrs = functions_mut(items)
+        .map(case::take_function_attrs)
+        .collect::<syn::Result<Vec<_>>>()?;
+    for name in merge {
+        for function in private_functions(merged_items(items, name)?) {
+            reject_options(function)?;
+        }
+        for function in functions_mut(merged_items_mut(items, name)?) {
+            case_attrs.push(case::take_function_attrs(function)?);
+        }
+    }
+    Ok(case_attrs)
+}
+
+/// The name by which a `use` declaration of a single item brings it into scope.
+fn reexported_name(tree: &UseTree) -> Option<&Ident> {
+    match tree {
+        UseTree::Path(tree) => reexported_name(&tree.tree),
+        UseTree::Name(tree) if tree.ident != "self" => Some(&tree.ident),
+        UseTree::Rename(tree) if tree.rename != "_" => Some(&tree.rename),
+        _ => None,
+    }
+}
+
+/// Removes the attributes of each function re-exported by the module with `pub use` which
+/// becomes a case, returning each with the name it is re-exported as. The macro cannot tell a
+/// re-exported function from any other item, so only those marked with a `defunctionalize`
+/// helper attribute become cases.
+pub fn take_reexports(
+    items: &mut [Item],
+) -> syn::Result<Vec<(Ident, Vec<Attribute>, CaseOptions)>> {
+    let mut reexports = vec![];
+    for item in items {
+        let item = match item {
+            Item::Use(item) => item,
+            _ => continue,
+        };
+        if !matches!(item.vis, Visibility::Public(..)) {
+            CaseOptions::reject(&item.attrs)?;
+            continue;
+        }
+        if !item
+            .attrs
+            .iter()
+            .any(|attr| attr.path.is_ident("defunctionalize"))
+        {
+            continue;
+        }
+        let name = reexported_name(&item.tree)
+            .ok_or_else(|| {
+                syn::Error::new_spanned(
+                    &item.tree,
+                    "only a `pub use` of a single function can become a case",
+                )
+            })?
+            .clone();
+        let (attrs, options) = case::take_attrs(&mut item.attrs)?;
+        reexports.push((name, attrs, options));
+    }
+    Ok(reexports)
+}
+
+/// The functions which become cases, each with the path to the module that contains it and the
+/// imports of that module. These are the public functions of the module, followed by those of
+/// each of the merged inner modules.
+pub fn case_functions<'a>(
+    mod_name: &Ident,
+    items: &'a [Item],
+    merge: &[Ident],
+) -> syn::Result<Vec<(TokenStream, Imports, &'a ItemFn)>> {
+    let imports = Imports::new(&[mod_name], items);
+    let mut case_functions = functions(items)
+        .map(|function| (quote!(#mod_name), imports.clone(), function))
+        .collect::<Vec<_>>();
+    for name in merge {
+        let merged = merged_items(items, name)?;
+        let imports = Imports::new(&[mod_name, name], merged);
+        case_functions.extend(
+            functions(merged).map(|function| (quote!(#mod_name::#name), imports.clone(), function)),
+        );
+    }
+    Ok(case_functions)
+}
+
+/// Generates a warning for each function of the module, or of the merged inner modules, which does
+/// not become a case because it is not public.
+pub fn warn_private(enum_name: &Ident, items: &[Item], merge: &[Ident]) -> TokenStream {
+    let merged = merge
+        .iter()
+        .filter_map(|name| merged_items(items, name).ok())
+        .flat_map(private_functions);
+    let warnings = private_functions(items).chain(merged).map(|function| {
+        let ident = &function.sig.ident;
+        let message = format!(
+            "`{}` is not a case of `{}`, as only `pub` functions are defunctionalized",
+            ident, enum_name
+        );
+        warning(
+            ident.span(),
+            &format!("private_function_{}", ident.unraw()),
+            &message,
+        )
+    });
+    quote!(#(#warnings)*)
+}
+
+/// Whether an attribute applies the `defunctionalize` macro, by any path.
+pub fn is_defunctionalize(attr: &Attribute) -> bool {
+    attr.path
+        .segments
+        .last()
+        .is_some_and(|segment| segment.ident == "defunctionalize")
+}
+
+/// The name of the enum that will be generated for an inner module, if it is itself
+/// defunctionalized. If its arguments are invalid, it reports that error itself.
+fn nested_enum_name(module: &ItemMod) -> Option<Ident> {
+    let attr = module.attrs.iter().find(|attr| is_defunctionalize(attr))?;
+    let args = if attr.tokens.is_empty() {
+        syn::parse2(TokenStream::new())
+    } else {
+        attr.parse_args()
+    };
+    let Args { signature, options } = args.ok()?;
+    Some(options.enum_name(&signature, &module.ident))
+}
+
+/// The public inner modules which are themselves defunctionalized and not merged, each with the
+/// name of the enum that is generated for it.
+pub fn nested_modules<'a>(items: &'a [Item], merge: &[Ident]) -> Vec<(&'a ItemMod, Ident)> {
+    items
+        .iter()
+        .filter_map(|item| match item {
+            Item::Mod(item) => Some(item),
+            _ => None,
+        })
+        .filter(|item| matches!(item.vis, Visibility::Public(..)))
+        .filter(|item| !merge.contains(&item.ident))
+        .filter_map(|item| Some((item, nested_enum_name(item)?)))
+        .collect()
+}
+
+/// Whether a `use` declaration brings an item named `name` into scope.
+fn imports(tree: &UseTree, name: &Ident) -> bool {
+    match tree {
+        UseTree::Path(path) => imports(&path.tree, name),
+        UseTree::Name(item) => item.ident == *name,
+        UseTree::Rename(item) => item.rename == *name,
+        UseTree::Glob(..) => false,
+        UseTree::Group(group) => group.items.iter().any(|tree| imports(tree, name)),
+    }
+}
+
+/// The visibility with which the enum is re-exported from inside a module of visibility `vis`,
+/// which is the same as that of the enum, as far as it can be written from inside the module.
+fn reexport_visibility(vis: &Visibility) -> TokenStream {
+    match vis {
+        Visibility::Public(..) | Visibility::Crate(..) => quote!(#vis),
+        Visibility::Restricted(restricted) if restricted.path.segments[0].ident == "crate" => {
+            quote!(#vis)
+        }
+        // A path relative to the module's parent means something else inside the module, so the
+        // enum is re-exported no further than the parent.
+        Visibility::Restricted(..) | Visibility::Inherited => quote!(pub(super)),
+    }
+}
+
+/// Whether `name` appears anywhere in `tokens`.
+fn mentions(tokens: TokenStream, name: &Ident) -> bool {
+    tokens.into_iter().any(|token| match token {
+        TokenTree::Ident(ident) => ident == *name,
+        TokenTree::Group(group) => mentions(group.stream(), name),
+        _ => false,
+    })
+}
+
+/// Imports the generated enum into the module, so that its functions can refer to it by name, as
+/// when they return the next state of a state machine. With `reexport`, the enum is imported
+/// with the module's visibility, so that it can also be named by a path through the module.
+/// Nothing is imported if the module already has a type by that name. Without `reexport`, the
+/// enum is only imported if the module mentions it, as `super` cannot name the enum when the
+/// module is declared inside a function body.
+pub fn import_enum(items: &mut Vec<Item>, enum_name: &Ident, reexport: Option<&Visibility>) {
+    if reexport.is_none() && !items.iter().any(|item| mentions(quote!(#item), enum_name)) {
+        return;
+    }
+    let declared = items.iter().any(|item| match item {
+        Item::Enum(item) => item.ident == *enum_name,
+        Item::Mod(item) => item.ident == *enum_name,
+        Item::Struct(item) => item.ident == *enum_name,
+        Item::Trait(item) => item.ident == *enum_name,
+        Item::Type(item) => item.ident == *enum_name,
+        Item::Union(item) => item.ident == *enum_name,
+        Item::Use(item) => imports(&item.tree, enum_name),
+        _ => false,
+    });
+    if !declared {
+        let vis = reexport.map(reexport_visibility);
+        items.insert(
+            0,
+            parse_quote! {
+                #[allow(unused_imports)]
+                #vis use super::#enum_name;
+            },
+        );
+    }
+}
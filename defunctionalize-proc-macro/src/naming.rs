@@ -0,0 +1,51 @@
+use heck::{CamelCase, SnakeCase};
+
+/// Converts a name to CamelCase. Names which are not ASCII are converted without heck, which
+/// splits words within scripts that have no case, and changes letters whose uppercase is several
+/// letters, such as `ß`: each word separated by `_` is kept as it is written, except that its
+/// first letter is uppercased when that gives a single letter.
+pub fn camel_case(name: &str) -> String {
+    if name.is_ascii() {
+        return name.to_camel_case();
+    }
+    name.split('_')
+        .filter(|word| !word.is_empty())
+        .map(|word| {
+            let mut chars = word.chars();
+            let first = chars.next().unwrap();
+            let first = single(first.to_uppercase()).unwrap_or(first);
+            std::iter::once(first).chain(chars).collect::<String>()
+        })
+        .collect()
+}
+
+/// Converts a name to snake_case. Names which are not ASCII are split only before uppercase
+/// letters, and letters whose lowercase is several letters are kept as they are.
+pub fn snake_case(name: &str) -> String {
+    if name.is_ascii() {
+        return name.to_snake_case();
+    }
+    let mut snake = String::new();
+    let mut previous = None::<char>;
+    for c in name.chars() {
+        if c.is_uppercase() && previous.is_some_and(|p| p != '_' && !p.is_uppercase()) {
+            snake.push('_');
+        }
+        snake.push(single(c.to_lowercase()).unwrap_or(c));
+        previous = Some(c);
+    }
+    snake
+}
+
+/// Converts a name to kebab-case, the same as `snake_case` but separated by `-`.
+pub fn kebab_case(name: &str) -> String {
+    snake_case(name).replace('_', "-")
+}
+
+/// The only letter of a change of case, if it gives only one.
+fn single(mut chars: impl Iterator<Item = char>) -> Option<char> {
+    match (chars.next(), chars.next()) {
+        (Some(c), None) => Some(c),
+        _ => None,
+    }
+}
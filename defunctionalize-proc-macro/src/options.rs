@@ -0,0 +1,461 @@
+use super::{
+    chunk::DEFAULT_CHUNK_SIZE, dispatch::Dispatch, empty::EmptyLevel, naming, Signature, SimpleArg,
+};
+
+use proc_macro2::{Span, TokenStream};
+use quote::{format_ident, quote, quote_spanned};
+use syn::{
+    ext::IdentExt,
+    parenthesized,
+    parse::{Parse, ParseStream},
+    parse_quote,
+    punctuated::Punctuated,
+    token::Paren,
+    Attribute, ExprClosure, Generics, Ident, ItemFn, LitInt, LitStr, Path, ReturnType, Token, Type,
+};
+
+const OPTIONS: &[&str] = &[
+    "actor",
+    "apply",
+    "args",
+    "audit",
+    "auto_clone",
+    "chunked",
+    "crate",
+    "curry",
+    "debug_call",
+    "debug_expand",
+    "derive",
+    "dispatch",
+    "embed_into",
+    "empty",
+    "events",
+    "expect",
+    "extra",
+    "ffi",
+    "impl_trait",
+    "include",
+    "input_struct",
+    "json",
+    "json_schema",
+    "like_trait",
+    "markers",
+    "max_size",
+    "merge",
+    "partition",
+    "postcard",
+    "prefix",
+    "protobuf",
+    "reexport",
+    "reflect",
+    "registry",
+    "repr_c",
+    "roundtrip_tests",
+    "send",
+    "serde",
+    "set",
+    "shared_by_name",
+    "stable_hash",
+    "subcommand",
+    "suffix",
+    "version",
+    "warn_private",
+];
+
+const SERDE_OPTIONS: &[&str] = &["content", "tag", "untagged"];
+
+pub fn unknown_option(name: &Ident, expected: &[&str]) -> syn::Error {
+    let expected = expected
+        .iter()
+        .map(|option| format!("`{}`", option))
+        .collect::<Vec<_>>()
+        .join(", ");
+    syn::Error::new(
+        name.span(),
+        format!(
+            "unknown defunctionalize option `{}`, expected one of {}",
+            name, expected
+        ),
+    )
+}
+
+/// Parses the `= Name` of an option which generates an item with a default name.
+fn parse_optional_name(input: ParseStream) -> syn::Result<Option<Ident>> {
+    if input.parse::<Option<Token![=]>>()?.is_some() {
+        Ok(Some(input.parse()?))
+    } else {
+        Ok(None)
+    }
+}
+
+pub struct Args {
+    pub signature: Signature,
+    pub options: Options,
+}
+
+#[derive(Default)]
+pub struct Options {
+    pub actor: Option<Option<Ident>>,
+    pub apply: Option<Option<Ident>>,
+    pub args: Option<Option<Ident>>,
+    pub chunked: Option<usize>,
+    pub krate: Option<Path>,
+    pub debug_call: bool,
+    pub debug_expand: Option<LitStr>,
+    pub derives: Vec<Path>,
+    pub dispatches: Vec<Dispatch>,
+    /// The variants of wider enums which hold this one, given by `embed_into`.
+    pub embed_into: Vec<Path>,
+    pub empty: EmptyLevel,
+    pub expects: Vec<ItemFn>,
+    pub extras: Vec<Extra>,
+    pub ffi: Option<Option<Ident>>,
+    pub impl_traits: Vec<Path>,
+    pub includes: Vec<IncludedFn>,
+    pub input_struct: Option<Option<Ident>>,
+    pub like_trait: Option<Option<Ident>>,
+    pub markers: Option<Option<Ident>>,
+    pub audit: bool,
+    pub auto_clone: bool,
+    pub curry: bool,
+    pub events: bool,
+    pub json: bool,
+    pub json_schema: bool,
+    pub max_size: Option<usize>,
+    pub merge: Vec<Ident>,
+    pub partition: bool,
+    /// The functions which become cases, when the enum is generated for a group of them by
+    /// `partition`.
+    pub cases: Option<Vec<Ident>>,
+    pub postcard: bool,
+    /// Added to the start of the enum's name, and so to the names of the types named after it.
+    pub prefix: Option<Ident>,
+    pub protobuf: Option<Option<Ident>>,
+    pub reexport: bool,
+    pub reflect: bool,
+    pub registry: bool,
+    pub repr_c: bool,
+    pub roundtrip_tests: Option<Option<Ident>>,
+    pub send: bool,
+    pub serde: Option<SerdeOptions>,
+    pub set: Option<Option<Ident>>,
+    pub shared_by_name: bool,
+    pub stable_hash: bool,
+    pub subcommand: bool,
+    /// Added to the end of the enum's name, and so to the names of the types named after it.
+    pub suffix: Option<Ident>,
+    pub version: Option<LitInt>,
+    pub warn_private: bool,
+}
+
+/// A function outside of the module to include as a case. Its captured arguments must be listed,
+/// as `path::to::function(name: Type, ...)`, as the function itself cannot be seen.
+pub struct IncludedFn {
+    pub path: Path,
+    pub captures: Punctuated<SimpleArg, Token![,]>,
+}
+
+impl Parse for IncludedFn {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let path = input.call(Path::parse_mod_style)?;
+        let captures = if input.peek(Paren) {
+            let content;
+            parenthesized!(content in input);
+            Punctuated::parse_terminated(&content)?
+        } else {
+            Punctuated::new()
+        };
+        Ok(IncludedFn { path, captures })
+    }
+}
+
+/// A function of the module which the macro cannot see, such as one generated by a macro invoked
+/// in the module, declared as `name: (name: Type, ...) -> Output`. It is parsed as a function with
+/// an empty body, so that it becomes a case like any other function.
+struct ExpectedFn(ItemFn);
+
+impl Parse for ExpectedFn {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let name: Ident = input.parse()?;
+        input.parse::<Token![:]>()?;
+        let content;
+        let paren = parenthesized!(content in input);
+        let inputs = Punctuated::<SimpleArg, Token![,]>::parse_terminated(&content)?;
+        let output: ReturnType = input.parse()?;
+        let inputs = quote_spanned!(paren.span=> (#inputs));
+        Ok(ExpectedFn(parse_quote! {
+            #[allow(missing_docs)]
+            pub fn #name #inputs #output {}
+        }))
+    }
+}
+
+/// A hand-written case, as `Name(Type, ...) => |field, ..., arg, ...| ...`, whose fields are
+/// given to the closure followed by the arguments of the signature when it is called.
+pub struct Extra {
+    pub attrs: Vec<Attribute>,
+    pub name: Ident,
+    pub fields: Punctuated<Type, Token![,]>,
+    pub dispatch: ExprClosure,
+}
+
+impl Parse for Extra {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let attrs = input.call(Attribute::parse_outer)?;
+        let name = input.parse()?;
+        let fields = if input.peek(Paren) {
+            let content;
+            parenthesized!(content in input);
+            Punctuated::parse_terminated(&content)?
+        } else {
+            Punctuated::new()
+        };
+        input.parse::<Token![=>]>()?;
+        let dispatch = input.parse()?;
+        Ok(Extra {
+            attrs,
+            name,
+            fields,
+            dispatch,
+        })
+    }
+}
+
+/// The serde enum representation, which is forwarded to the serde derives.
+#[derive(Default)]
+pub struct SerdeOptions {
+    pub span: Option<Span>,
+    pub tag: Option<LitStr>,
+    pub content: Option<LitStr>,
+    pub untagged: bool,
+}
+
+impl Parse for SerdeOptions {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let mut options = SerdeOptions::default();
+        while !input.is_empty() {
+            let name = input.call(Ident::parse_any)?;
+            match name.to_string().as_str() {
+                "content" => {
+                    input.parse::<Token![=]>()?;
+                    options.content = Some(input.parse()?);
+                }
+                "tag" => {
+                    input.parse::<Token![=]>()?;
+                    options.tag = Some(input.parse()?);
+                }
+                "untagged" => options.untagged = true,
+                _ => return Err(unknown_option(&name, SERDE_OPTIONS)),
+            }
+            if input.is_empty() {
+                break;
+            }
+            input.parse::<Token![,]>()?;
+        }
+        Ok(options)
+    }
+}
+
+impl Options {
+    /// The path to the `defunctionalize` crate, for use in generated code.
+    pub fn krate(&self) -> TokenStream {
+        match &self.krate {
+            Some(path) => quote!(#path),
+            None => quote!(::defunctionalize),
+        }
+    }
+
+    /// The name of the enum generated for the module `mod_name`: the name in the signature, or
+    /// else the module's name in CamelCase, between the `prefix` and the `suffix`.
+    pub fn enum_name(&self, signature: &Signature, mod_name: &Ident) -> Ident {
+        let name = signature.ident.clone().unwrap_or_else(|| {
+            format_ident!("{}", naming::camel_case(&mod_name.unraw().to_string()))
+        });
+        if self.prefix.is_none() && self.suffix.is_none() {
+            return name;
+        }
+        let affix = |affix: &Option<Ident>| {
+            affix
+                .as_ref()
+                .map(|affix| affix.unraw().to_string())
+                .unwrap_or_default()
+        };
+        format_ident!(
+            "{}{}{}",
+            affix(&self.prefix),
+            name,
+            affix(&self.suffix),
+            span = name.span()
+        )
+    }
+
+    fn parse_option(&mut self, input: ParseStream) -> syn::Result<()> {
+        let name = input.call(Ident::parse_any)?;
+        match name.to_string().as_str() {
+            "actor" => self.actor = Some(parse_optional_name(input)?),
+            "apply" => self.apply = Some(parse_optional_name(input)?),
+            "args" => self.args = Some(parse_optional_name(input)?),
+            "audit" => self.audit = true,
+            "auto_clone" => self.auto_clone = true,
+            "chunked" => {
+                let size = if input.parse::<Option<Token![=]>>()?.is_some() {
+                    let size = input.parse::<LitInt>()?;
+                    match size.base10_parse()? {
+                        0 => return Err(syn::Error::new(size.span(), "chunks cannot be empty")),
+                        size => size,
+                    }
+                } else {
+                    DEFAULT_CHUNK_SIZE
+                };
+                self.chunked = Some(size);
+            }
+            "crate" => {
+                input.parse::<Token![=]>()?;
+                self.krate = Some(input.parse()?);
+            }
+            "curry" => self.curry = true,
+            "debug_call" => self.debug_call = true,
+            "debug_expand" => {
+                input.parse::<Token![=]>()?;
+                self.debug_expand = Some(input.parse()?);
+            }
+            "derive" => {
+                let content;
+                parenthesized!(content in input);
+                self.derives
+                    .extend(Punctuated::<Path, Token![,]>::parse_terminated(&content)?);
+            }
+            "dispatch" => {
+                input.parse::<Token![=]>()?;
+                self.dispatches.push(input.parse()?);
+            }
+            "embed_into" => {
+                input.parse::<Token![=]>()?;
+                self.embed_into.push(input.parse()?);
+            }
+            "empty" => {
+                input.parse::<Token![=]>()?;
+                self.empty = input.parse()?;
+            }
+            "events" => self.events = true,
+            "expect" => {
+                let content;
+                parenthesized!(content in input);
+                self.expects.extend(
+                    Punctuated::<ExpectedFn, Token![,]>::parse_terminated(&content)?
+                        .into_iter()
+                        .map(|expected| expected.0),
+                );
+            }
+            "extra" => {
+                let content;
+                parenthesized!(content in input);
+                self.extras
+                    .extend(Punctuated::<Extra, Token![,]>::parse_terminated(&content)?);
+            }
+            "ffi" => self.ffi = Some(parse_optional_name(input)?),
+            "impl_trait" => {
+                input.parse::<Token![=]>()?;
+                self.impl_traits.push(input.parse()?);
+            }
+            "include" => {
+                let content;
+                parenthesized!(content in input);
+                self.includes
+                    .extend(Punctuated::<IncludedFn, Token![,]>::parse_terminated(
+                        &content,
+                    )?);
+            }
+            "input_struct" => self.input_struct = Some(parse_optional_name(input)?),
+            "json" => self.json = true,
+            "json_schema" => self.json_schema = true,
+            "like_trait" => self.like_trait = Some(parse_optional_name(input)?),
+            "markers" => self.markers = Some(parse_optional_name(input)?),
+            "max_size" => {
+                input.parse::<Token![=]>()?;
+                self.max_size = Some(input.parse::<LitInt>()?.base10_parse()?);
+            }
+            "merge" => {
+                let content;
+                parenthesized!(content in input);
+                self.merge
+                    .extend(Punctuated::<Ident, Token![,]>::parse_terminated(&content)?);
+            }
+            "partition" => self.partition = true,
+            "postcard" => self.postcard = true,
+            "prefix" => {
+                input.parse::<Token![=]>()?;
+                self.prefix = Some(input.parse()?);
+            }
+            "protobuf" => self.protobuf = Some(parse_optional_name(input)?),
+            "reexport" => self.reexport = true,
+            "reflect" => self.reflect = true,
+            "registry" => self.registry = true,
+            "repr_c" => self.repr_c = true,
+            "roundtrip_tests" => self.roundtrip_tests = Some(parse_optional_name(input)?),
+            "send" => self.send = true,
+            "serde" => {
+                let content;
+                parenthesized!(content in input);
+                self.serde = Some(SerdeOptions {
+                    span: Some(name.span()),
+                    ..content.parse()?
+                });
+            }
+            "set" => self.set = Some(parse_optional_name(input)?),
+            "shared_by_name" => self.shared_by_name = true,
+            "stable_hash" => self.stable_hash = true,
+            "subcommand" => self.subcommand = true,
+            "suffix" => {
+                input.parse::<Token![=]>()?;
+                self.suffix = Some(input.parse()?);
+            }
+            "version" => {
+                input.parse::<Token![=]>()?;
+                let version: LitInt = input.parse()?;
+                version.base10_parse::<u32>()?;
+                self.version = Some(version);
+            }
+            "warn_private" => self.warn_private = true,
+            "__cases" => {
+                let content;
+                parenthesized!(content in input);
+                self.cases = Some(
+                    Punctuated::<Ident, Token![,]>::parse_terminated(&content)?
+                        .into_iter()
+                        .collect(),
+                );
+            }
+            _ => return Err(unknown_option(&name, OPTIONS)),
+        }
+        Ok(())
+    }
+}
+
+impl Parse for Args {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        if input.is_empty() {
+            // A bare attribute captures every argument of every function, which return `()`.
+            return Ok(Args {
+                signature: Signature {
+                    asyncness: None,
+                    ident: None,
+                    generics: Generics::default(),
+                    inputs: Punctuated::new(),
+                    output: ReturnType::Default,
+                },
+                options: Options::default(),
+            });
+        }
+        let signature: Signature = input.parse()?;
+        let mut options = Options::default();
+        while !input.is_empty() {
+            input.parse::<Token![,]>()?;
+            if input.is_empty() {
+                break;
+            }
+            options.parse_option(input)?;
+        }
+        Ok(Args { signature, options })
+    }
+}
@@ -0,0 +1,68 @@
+use super::Signature;
+
+use proc_macro2::TokenStream;
+use proc_macro_error::abort_call_site;
+use quote::quote;
+use syn::{Ident, ReturnType, Visibility};
+
+/// Generates the `apply` method, which binds the first argument of the signature to a command,
+/// and the type of the result, which implements `DeFn` over the remaining arguments.
+pub fn apply_impl(
+    visibility: &Visibility,
+    enum_name: &Ident,
+    applied_name: &Ident,
+    signature: &Signature,
+    krate: &TokenStream,
+) -> TokenStream {
+    let mut inputs = signature.inputs.iter();
+    let first = match inputs.next() {
+        Some(first) => first,
+        None => abort_call_site!("`apply` requires the signature to have an argument"),
+    };
+    let rest = inputs.collect::<Vec<_>>();
+    let first_name = &first.ident;
+    let first_type = &first.ty;
+    let rest_types = rest.iter().map(|arg| &arg.ty).collect::<Vec<_>>();
+    let rest_names = rest.iter().map(|arg| &arg.ident).collect::<Vec<_>>();
+
+    let generics = &signature.generics;
+    let where_clause = &signature.generics.where_clause;
+    let output = &signature.output;
+    let output_type = match output {
+        ReturnType::Default => quote!(()),
+        ReturnType::Type(.., ty) => quote!(#ty),
+    };
+
+    quote! {
+        /// A command with the first argument of its signature already bound, created by `apply`.
+        #visibility struct #applied_name<A> {
+            command: #enum_name,
+            arg: A,
+        }
+
+        impl #enum_name {
+            /// Binds the first argument of the signature, so that the command can be called with
+            /// only the remaining arguments.
+            #visibility fn apply #generics (self, #first_name: #first_type) -> #applied_name<#first_type> #where_clause {
+                #applied_name {
+                    command: self,
+                    arg: #first_name,
+                }
+            }
+        }
+
+        impl #generics #applied_name<#first_type> #where_clause {
+            #visibility fn call(self, #(#rest_names: #rest_types),*) #output {
+                #enum_name::call(self.command, self.arg, #(#rest_names),*)
+            }
+        }
+
+        impl #generics #krate::DeFn<(#(#rest_types),*)> for #applied_name<#first_type> #where_clause {
+            type Output = #output_type;
+
+            fn call(self, (#(#rest_names),*): (#(#rest_types),*)) #output {
+                #applied_name::call(self, #(#rest_names),*)
+            }
+        }
+    }
+}
@@ -0,0 +1,190 @@
+use super::{case, module, naming, type_name, Signature};
+
+use proc_macro2::{TokenStream, TokenTree};
+use proc_macro_error::{abort, abort_call_site, abort_if_dirty, emit_error};
+use quote::{format_ident, quote};
+use syn::{ext::IdentExt, parse::ParseStream, FnArg, Ident, ItemMod, ReturnType, Type};
+
+/// A group of functions which share the types of their trailing arguments and their return type,
+/// for which one enum is generated.
+struct Group<'a> {
+    types: Vec<&'a Type>,
+    output: Type,
+    functions: Vec<&'a Ident>,
+}
+
+/// The options given after the signature, without `partition` itself.
+fn other_options(attr: TokenStream) -> TokenStream {
+    let options = syn::parse::Parser::parse2(
+        |input: ParseStream| {
+            input.parse::<Signature>()?;
+            input.parse::<TokenStream>()
+        },
+        attr,
+    )
+    .expect("the arguments were already parsed");
+    let mut chunks = vec![];
+    let mut chunk = vec![];
+    for token in options {
+        match &token {
+            TokenTree::Punct(punct) if punct.as_char() == ',' => {
+                chunks.push(std::mem::take(&mut chunk));
+            }
+            _ => chunk.push(token),
+        }
+    }
+    chunks.push(chunk);
+    let options = chunks
+        .into_iter()
+        .filter(|chunk| !chunk.is_empty())
+        .filter(|chunk| !matches!(&chunk[..], [TokenTree::Ident(ident)] if ident == "partition"));
+    quote!(#(, #(#options)*)*)
+}
+
+/// A name for the types of a group, from the identifiers in them, such as `MutWorld` for
+/// `&mut World`.
+fn types_name(types: &[&Type]) -> String {
+    fn idents(tokens: TokenStream, name: &mut String) {
+        let mut lifetime = false;
+        for token in tokens {
+            match &token {
+                TokenTree::Ident(ident) if !lifetime => {
+                    name.push_str(&naming::camel_case(&ident.unraw().to_string()))
+                }
+                TokenTree::Group(group) => idents(group.stream(), name),
+                _ => {}
+            }
+            lifetime = matches!(&token, TokenTree::Punct(punct) if punct.as_char() == '\'');
+        }
+    }
+    let mut name = String::new();
+    for ty in types {
+        idents(quote!(#ty), &mut name);
+    }
+    name
+}
+
+/// Expands a module whose functions are partitioned by the types of their trailing arguments and
+/// their return type, which are left as `_` in the signature. A `defunctionalize` attribute is
+/// generated for each group of functions, with the signature completed by their types and naming
+/// the enum after the module and the types, which is then expanded as usual.
+pub fn partition(
+    attr: TokenStream,
+    signature: &Signature,
+    krate: &TokenStream,
+    mod_item: &ItemMod,
+    merge: &[Ident],
+) -> TokenStream {
+    let infer = |ty: &Type| matches!(ty, Type::Infer(..));
+    if signature.inputs.iter().any(|arg| !infer(&arg.ty))
+        || !matches!(&signature.output, ReturnType::Type(.., ty) if infer(ty))
+        || !signature.generics.params.is_empty()
+    {
+        abort_call_site!(
+            "`partition` requires a signature whose types are all `_`";
+            help = "write the signature as `fn(name: _, ...) -> _`, and the types are taken from each group of functions"
+        );
+    }
+
+    let items = match &mod_item.content {
+        Some((.., items)) => items,
+        None => unreachable!(),
+    };
+    let functions = match module::case_functions(&mod_item.ident, items, merge) {
+        Ok(functions) => functions,
+        Err(error) => return error.to_compile_error(),
+    };
+    let shared = signature.inputs.len();
+    let mut groups: Vec<Group> = vec![];
+    for (.., function) in functions {
+        let types = function
+            .sig
+            .inputs
+            .iter()
+            .filter_map(|arg| match arg {
+                FnArg::Typed(arg) => Some(arg.ty.as_ref()),
+                FnArg::Receiver(..) => None,
+            })
+            .collect::<Vec<_>>();
+        if types.len() < shared {
+            emit_error!(
+                function.sig.paren_token.span,
+                "expected at least {} arguments, from the defunctionalize signature, found {}",
+                shared,
+                types.len()
+            );
+            continue;
+        }
+        let types = types[types.len() - shared..].to_vec();
+        let output = case::output_type(&function.sig.output).into_owned();
+        let key = |types: &[&Type], output: &Type| {
+            (
+                types
+                    .iter()
+                    .map(|ty| type_name::display(ty))
+                    .collect::<Vec<_>>(),
+                type_name::display(output),
+            )
+        };
+        let found = key(&types, &output);
+        match groups
+            .iter_mut()
+            .find(|group| key(&group.types, &group.output) == found)
+        {
+            Some(group) => group.functions.push(&function.sig.ident),
+            None => groups.push(Group {
+                types,
+                output,
+                functions: vec![&function.sig.ident],
+            }),
+        }
+    }
+    abort_if_dirty();
+
+    let prefix = signature
+        .ident
+        .as_ref()
+        .map(|ident| ident.to_string())
+        .unwrap_or_else(|| naming::camel_case(&mod_item.ident.unraw().to_string()));
+    let mut names: Vec<(Ident, &Group)> = vec![];
+    for group in &groups {
+        // Without shared arguments, the groups differ only by their return types.
+        let named_by = if shared == 0 {
+            vec![&group.output]
+        } else {
+            group.types.clone()
+        };
+        let name = format_ident!("{}{}", prefix, types_name(&named_by));
+        if let Some((.., other)) = names.iter().find(|(other, ..)| *other == name) {
+            abort!(
+                group.functions[0],
+                "`{}` and `{}` would both be cases of `{}`, but their types differ",
+                group.functions[0],
+                other.functions[0],
+                name;
+                help = "give both functions the same trailing arguments and return type, or move one of them to another module"
+            );
+        }
+        names.push((name, group));
+    }
+
+    let asyncness = &signature.asyncness;
+    let options = other_options(attr);
+    let attrs = names.iter().map(|(name, group)| {
+        let arg_names = signature.inputs.iter().map(|arg| &arg.ident);
+        let types = &group.types;
+        let output = &group.output;
+        let functions = &group.functions;
+        quote! {
+            #[#krate::defunctionalize(
+                #asyncness fn #name(#(#arg_names: #types),*) -> #output
+                #options,
+                __cases(#(#functions),*)
+            )]
+        }
+    });
+    quote! {
+        #(#attrs)*
+        #mod_item
+    }
+}
@@ -0,0 +1,84 @@
+use super::Case;
+
+use proc_macro2::{Literal, TokenStream};
+use quote::quote;
+use syn::{Ident, Visibility};
+
+/// Generates the `priority` method and the key by which `ByPriority` orders the enum, for when
+/// any function has a `#[defunctionalize(priority = ...)]`. Commands of equal priority are ordered
+/// by their stable tags, or else by the order of the functions, with earlier ones greater so that
+/// they are called first. The enum's own equality and ordering are left alone.
+pub fn ordering_impl(
+    visibility: &Visibility,
+    enum_name: &Ident,
+    cases: &[Case],
+    krate: &TokenStream,
+) -> Option<TokenStream> {
+    if cases.iter().all(|case| case.options.priority.is_none()) {
+        return None;
+    }
+
+    let priority_arms = cases.iter().map(|case| {
+        let cfgs = case.cfgs();
+        let wildcard = case.wildcard();
+        let priority = Literal::i32_suffixed(case.options.priority.unwrap_or(0));
+        quote!(#(#cfgs)* #enum_name::#wildcard => #priority,)
+    });
+    let index_arms = cases.iter().enumerate().map(|(index, case)| {
+        let cfgs = case.cfgs();
+        let wildcard = case.wildcard();
+        let index = Literal::u32_suffixed(case.options.tag().unwrap_or(index as u32));
+        quote!(#(#cfgs)* #enum_name::#wildcard => #index,)
+    });
+
+    Some(quote! {
+        impl #enum_name {
+            /// The priority of this command, which is `0` unless its function specifies one.
+            #visibility fn priority(&self) -> i32 {
+                match *self {
+                    #(#priority_arms)*
+                }
+            }
+        }
+
+        impl #krate::Prioritized for #enum_name {
+            #[allow(unreachable_code)]
+            fn priority_key(&self) -> #krate::PriorityKey {
+                let index = match *self {
+                    #(#index_arms)*
+                };
+                #krate::PriorityKey::new(#enum_name::priority(self), index)
+            }
+        }
+    })
+}
+
+/// Generates the `weight` method, for when any function has a `#[defunctionalize(cost = ...)]`.
+pub fn weight_impl(
+    visibility: &Visibility,
+    enum_name: &Ident,
+    cases: &[Case],
+) -> Option<TokenStream> {
+    if cases.iter().all(|case| case.options.cost.is_none()) {
+        return None;
+    }
+
+    let arms = cases.iter().map(|case| {
+        let cfgs = case.cfgs();
+        let wildcard = case.wildcard();
+        let cost = Literal::u32_suffixed(case.options.cost.unwrap_or(1));
+        quote!(#(#cfgs)* #enum_name::#wildcard => #cost,)
+    });
+
+    Some(quote! {
+        impl #enum_name {
+            /// The estimated cost of calling this command, which is `1` unless its function
+            /// specifies one.
+            #visibility fn weight(&self) -> u32 {
+                match *self {
+                    #(#arms)*
+                }
+            }
+        }
+    })
+}
@@ -0,0 +1,238 @@
+use super::{serde_impl::validate_tags, type_name, Case};
+
+use proc_macro2::{Literal, TokenStream};
+use proc_macro_error::{abort, abort_call_site, abort_if_dirty, emit_error};
+use quote::{format_ident, quote};
+use syn::{GenericArgument, Ident, PathArguments, Type, Visibility};
+
+/// The protobuf type of a Rust type which prost represents as a scalar.
+fn scalar(ty: &Type) -> Option<&'static str> {
+    let segment = match ty {
+        Type::Path(ty) if ty.qself.is_none() => ty.path.segments.last()?,
+        _ => return None,
+    };
+    if !segment.arguments.is_empty() {
+        return None;
+    }
+    let scalar = match segment.ident.to_string().as_str() {
+        "bool" => "bool",
+        "i32" => "int32",
+        "i64" => "int64",
+        "u32" => "uint32",
+        "u64" => "uint64",
+        "f32" => "float",
+        "f64" => "double",
+        "String" => "string",
+        _ => return None,
+    };
+    Some(scalar)
+}
+
+/// The type argument of a type such as `Vec<T>` or `Option<T>`, named `wrapper`.
+fn inner_type<'a>(ty: &'a Type, wrapper: &str) -> Option<&'a Type> {
+    let segment = match ty {
+        Type::Path(ty) if ty.qself.is_none() => ty.path.segments.last()?,
+        _ => return None,
+    };
+    if segment.ident != wrapper {
+        return None;
+    }
+    match &segment.arguments {
+        PathArguments::AngleBracketed(arguments) if arguments.args.len() == 1 => {
+            match &arguments.args[0] {
+                GenericArgument::Type(ty) => Some(ty),
+                _ => None,
+            }
+        }
+        _ => None,
+    }
+}
+
+/// The contents of the `#[prost(...)]` attribute of a field of type `ty`, without its tag.
+fn field_kind(ty: &Type) -> Option<TokenStream> {
+    if let Some(scalar) = scalar(ty) {
+        let scalar = format_ident!("{}", scalar);
+        return Some(quote!(#scalar));
+    }
+    if let Some(inner) = inner_type(ty, "Vec") {
+        if type_name::display(inner) == "u8" {
+            return Some(quote!(bytes = "vec"));
+        }
+        let scalar = format_ident!("{}", scalar(inner)?);
+        return Some(quote!(#scalar, repeated));
+    }
+    if let Some(inner) = inner_type(ty, "Option") {
+        let scalar = format_ident!("{}", scalar(inner)?);
+        return Some(quote!(#scalar, optional));
+    }
+    None
+}
+
+/// Generates a module of prost messages which represent the enum in protobuf, with conversions
+/// between them and the enum. The enum is a message holding a `oneof` of a message for each case,
+/// whose field number is the case's stable tag, and which holds the case's captured arguments.
+pub fn proto_impl(
+    visibility: &Visibility,
+    enum_name: &Ident,
+    proto_name: &Ident,
+    cases: &[Case],
+    krate: &TokenStream,
+) -> TokenStream {
+    if !validate_tags(cases) {
+        abort_call_site!(
+            "`protobuf` requires every function to have a stable tag";
+            help = "add `#[defunctionalize(tag = ...)]` to each function"
+        );
+    }
+    for case in cases {
+        let tag = case.options.tag().unwrap();
+        if tag == 0 || (19000..20000).contains(&tag) || tag > 536_870_911 {
+            abort!(
+                case.options.tag.as_ref().unwrap(),
+                "`{}` is not a valid protobuf field number", tag;
+                help = "field numbers must be between 1 and 536870911, excluding 19000 to 19999"
+            );
+        }
+        for capture in &case.captures {
+            if field_kind(&capture.ty).is_none() {
+                emit_error!(
+                    capture.ty,
+                    "`{}` has no protobuf representation",
+                    type_name::display(&capture.ty);
+                    help = "captured arguments must be `bool`, `i32`, `i64`, `u32`, `u64`, `f32`, \
+                            `f64`, `String`, `Vec<u8>`, or a `Vec` or `Option` of one of those"
+                );
+            }
+        }
+    }
+    abort_if_dirty();
+
+    let tags = cases
+        .iter()
+        .map(|case| case.options.tag().unwrap().to_string())
+        .collect::<Vec<_>>()
+        .join(", ");
+    let messages = cases.iter().map(|case| {
+        let cfgs = case.cfgs();
+        let name = format_ident!("{}Args", case.name);
+        let fields = case.captures.iter().enumerate().map(|(index, capture)| {
+            let ident = &capture.ident;
+            let ty = &capture.ty;
+            let kind = field_kind(ty).unwrap();
+            let tag = (index + 1).to_string();
+            quote! {
+                #[prost(#kind, tag = #tag)]
+                pub #ident: #ty,
+            }
+        });
+        quote! {
+            #(#cfgs)*
+            #[derive(::core::clone::Clone, ::core::cmp::PartialEq, ::prost::Message)]
+            pub struct #name {
+                #(#fields)*
+            }
+        }
+    });
+    let variants = cases.iter().map(|case| {
+        let cfgs = case.cfgs();
+        let name = &case.name;
+        let message = format_ident!("{}Args", case.name);
+        let tag = case.options.tag().unwrap().to_string();
+        quote! {
+            #(#cfgs)*
+            #[prost(message, tag = #tag)]
+            #name(#message),
+        }
+    });
+    let (into_arms, from_arms): (Vec<_>, Vec<_>) = cases
+        .iter()
+        .map(|case| {
+            let cfgs = case.cfgs().collect::<Vec<_>>();
+            let name = &case.name;
+            let message = format_ident!("{}Args", case.name);
+            let idents = case
+                .captures
+                .iter()
+                .map(|capture| &capture.ident)
+                .collect::<Vec<_>>();
+            let pattern = if idents.is_empty() {
+                quote!(#enum_name::#name)
+            } else {
+                quote!(#enum_name::#name(#(#idents),*))
+            };
+            (
+                quote! {
+                    #(#cfgs)*
+                    #pattern => #proto_name::Command::#name(#proto_name::#message { #(#idents),* }),
+                },
+                quote! {
+                    #(#cfgs)*
+                    ::core::option::Option::Some(#proto_name::Command::#name(#proto_name::#message { #(#idents),* })) => {
+                        ::core::result::Result::Ok(#pattern)
+                    }
+                },
+            )
+        })
+        .unzip();
+    let tags = Literal::string(&tags);
+    let prost = quote!(#krate::__private::prost);
+
+    quote! {
+        /// The protobuf messages representing each command.
+        #visibility mod #proto_name {
+            /// A command, as a protobuf message.
+            #[derive(::core::clone::Clone, ::core::cmp::PartialEq, ::prost::Message)]
+            pub struct #enum_name {
+                #[prost(oneof = "Command", tags = #tags)]
+                pub command: ::core::option::Option<Command>,
+            }
+
+            /// The function a command calls, with its captured arguments.
+            #[derive(::core::clone::Clone, ::core::cmp::PartialEq, ::prost::Oneof)]
+            pub enum Command {
+                #(#variants)*
+            }
+
+            #(#messages)*
+        }
+
+        impl ::core::convert::From<#enum_name> for #proto_name::#enum_name {
+            #[allow(unreachable_code)]
+            fn from(command: #enum_name) -> Self {
+                let command = match command {
+                    #(#into_arms)*
+                };
+                #proto_name::#enum_name {
+                    command: ::core::option::Option::Some(command),
+                }
+            }
+        }
+
+        impl ::core::convert::TryFrom<#proto_name::#enum_name> for #enum_name {
+            type Error = #krate::ProtoError;
+
+            fn try_from(message: #proto_name::#enum_name) -> ::core::result::Result<Self, Self::Error> {
+                match message.command {
+                    #(#from_arms)*
+                    ::core::option::Option::None => ::core::result::Result::Err(#krate::ProtoError::MissingCommand),
+                }
+            }
+        }
+
+        impl #enum_name {
+            /// Encodes this command as a protobuf message.
+            #visibility fn encode_proto(self) -> ::std::vec::Vec<u8> {
+                #prost::Message::encode_to_vec(&#proto_name::#enum_name::from(self))
+            }
+
+            /// Decodes a command which was encoded as a protobuf message.
+            #visibility fn decode_proto(bytes: &[u8]) -> ::core::result::Result<Self, #krate::ProtoError> {
+                let message: #proto_name::#enum_name = ::core::result::Result::map_err(
+                    #prost::Message::decode(bytes),
+                    #krate::ProtoError::Decode,
+                )?;
+                ::core::convert::TryFrom::try_from(message)
+            }
+        }
+    }
+}
@@ -0,0 +1,139 @@
+use super::Case;
+
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{ext::IdentExt, punctuated::Punctuated, Attribute, Ident, Path, Token};
+
+fn is_trait(path: &Path, name: &str) -> bool {
+    path.segments
+        .last()
+        .is_some_and(|segment| segment.ident == name)
+}
+
+/// Removes the trait `name` from the traits derived for the enum, by the `derive` option or a
+/// `derive` attribute, returning whether it was there, so that it can be implemented by the
+/// macro instead. A derived `Debug` would show redacted arguments, so it is implemented by
+/// `debug_impl`.
+pub fn take_derive(derives: &mut Vec<Path>, attrs: &mut Vec<Attribute>, name: &str) -> bool {
+    let mut derived = derives.iter().any(|path| is_trait(path, name));
+    derives.retain(|path| !is_trait(path, name));
+    attrs.retain_mut(|attr| {
+        if !attr.path.is_ident("derive") {
+            return true;
+        }
+        let paths = match attr.parse_args_with(Punctuated::<Path, Token![,]>::parse_terminated) {
+            Ok(paths) => paths,
+            Err(..) => return true,
+        };
+        if !paths.iter().any(|path| is_trait(path, name)) {
+            return true;
+        }
+        derived = true;
+        let paths = paths.into_iter().filter(|path| !is_trait(path, name));
+        let paths = quote!(#(#paths),*);
+        if paths.is_empty() {
+            return false;
+        }
+        attr.tokens = quote!((#paths));
+        true
+    });
+    derived
+}
+
+/// Generates an implementation of `Debug` like the derived one, but which shows each redacted
+/// argument as `<redacted>`.
+pub fn debug_impl(enum_name: &Ident, cases: &[Case]) -> TokenStream {
+    let arms = cases.iter().map(|case| {
+        let cfgs = case.cfgs();
+        let name = &case.name;
+        let name_str = name.to_string();
+        if case.captures.is_empty() {
+            return quote!(#(#cfgs)* #enum_name::#name => f.write_str(#name_str),);
+        }
+        let bindings = case.captures.iter().map(|capture| {
+            let ident = &capture.ident;
+            if capture.options.redact {
+                quote!(_)
+            } else {
+                quote!(ref #ident)
+            }
+        });
+        let fields = case.captures.iter().map(|capture| {
+            let ident = &capture.ident;
+            if capture.options.redact {
+                quote!(.field(&::core::format_args!("<redacted>")))
+            } else {
+                quote!(.field(#ident))
+            }
+        });
+        quote! {
+            #(#cfgs)*
+            #enum_name::#name(#(#bindings),*) => f.debug_tuple(#name_str) #(#fields)* .finish(),
+        }
+    });
+
+    quote! {
+        impl ::core::fmt::Debug for #enum_name {
+            fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+                match *self {
+                    #(#arms)*
+                }
+            }
+        }
+    }
+}
+
+/// Generates an implementation of `Debug` which shows each command as a call to its function,
+/// such as `multiply_and_add(coefficient: 3, constant: 4)`, with redacted arguments shown as
+/// `<redacted>`.
+pub fn debug_call_impl(enum_name: &Ident, cases: &[Case]) -> TokenStream {
+    let arms = cases.iter().map(|case| {
+        let cfgs = case.cfgs();
+        let name = &case.name;
+        let call = format!("{}(", case.ident.unraw());
+        if case.captures.is_empty() {
+            let call = format!("{})", call);
+            return quote!(#(#cfgs)* #enum_name::#name => f.write_str(#call),);
+        }
+        let bindings = case.captures.iter().map(|capture| {
+            let ident = &capture.ident;
+            if capture.options.redact {
+                quote!(_)
+            } else {
+                quote!(ref #ident)
+            }
+        });
+        let args = case.captures.iter().enumerate().map(|(i, capture)| {
+            let ident = &capture.ident;
+            let separator = if i == 0 { "" } else { ", " };
+            let label = format!("{}{}: ", separator, capture.ident.unraw());
+            let value = if capture.options.redact {
+                quote!(f.write_str("<redacted>")?;)
+            } else {
+                quote!(::core::fmt::Debug::fmt(#ident, f)?;)
+            };
+            quote! {
+                f.write_str(#label)?;
+                #value
+            }
+        });
+        quote! {
+            #(#cfgs)*
+            #enum_name::#name(#(#bindings),*) => {
+                f.write_str(#call)?;
+                #(#args)*
+                f.write_str(")")
+            }
+        }
+    });
+
+    quote! {
+        impl ::core::fmt::Debug for #enum_name {
+            fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+                match *self {
+                    #(#arms)*
+                }
+            }
+        }
+    }
+}
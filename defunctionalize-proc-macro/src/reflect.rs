@@ -0,0 +1,164 @@
+use super::{type_name, Case};
+
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{ext::IdentExt, Ident, Visibility};
+
+/// Generates methods describing the function and captured arguments of each command, and the
+/// `SCHEMA` describing every command, with its `FINGERPRINT`.
+pub fn reflect_impl(
+    visibility: &Visibility,
+    enum_name: &Ident,
+    cases: &[Case],
+    krate: &TokenStream,
+) -> TokenStream {
+    let name_arms = cases.iter().map(|case| {
+        let cfgs = case.cfgs();
+        let wildcard = case.wildcard();
+        let name = case.ident.unraw().to_string();
+        quote!(#(#cfgs)* #enum_name::#wildcard => #name,)
+    });
+    let description_arms = cases.iter().map(|case| {
+        let cfgs = case.cfgs();
+        let wildcard = case.wildcard();
+        let description = case.doc().unwrap_or_default();
+        let description = description.lines().next().unwrap_or_default();
+        quote!(#(#cfgs)* #enum_name::#wildcard => #description,)
+    });
+    let arg_names_arms = cases.iter().map(|case| {
+        let cfgs = case.cfgs();
+        let wildcard = case.wildcard();
+        let names = case
+            .captures
+            .iter()
+            .map(|capture| capture.ident.unraw().to_string());
+        quote!(#(#cfgs)* #enum_name::#wildcard => &[#(#names),*],)
+    });
+    let descs = cases.iter().enumerate().map(|(index, case)| {
+        let cfgs = case.cfgs();
+        let name = case.ident.unraw().to_string();
+        let arg_names = case
+            .captures
+            .iter()
+            .map(|capture| capture.ident.unraw().to_string());
+        let arg_types = case
+            .captures
+            .iter()
+            .map(|capture| type_name::display(&capture.ty));
+        let doc = match case.doc() {
+            Some(doc) => {
+                let summary = doc.split("\n\n").next().unwrap().replace('\n', " ");
+                quote!(::core::option::Option::Some(#summary))
+            }
+            None => quote!(::core::option::Option::None),
+        };
+        quote! {
+            #(#cfgs)*
+            #krate::CommandDesc {
+                name: #name,
+                index: #index,
+                arg_names: &[#(#arg_names),*],
+                arg_types: &[#(#arg_types),*],
+                doc: #doc,
+            }
+        }
+    });
+
+    quote! {
+        impl #enum_name {
+            /// Describes every command, for tools which list them.
+            #visibility const SCHEMA: &'static [#krate::CommandDesc] = &[#(#descs),*];
+
+            /// A hash of the names of the commands, in order, and the types of their captured
+            /// arguments, for checking that another process has the same commands before
+            /// exchanging serialized commands with it.
+            #visibility const FINGERPRINT: u64 = #krate::fingerprint(Self::SCHEMA);
+
+            /// The name of the function this command calls.
+            #visibility fn name(&self) -> &'static str {
+                match *self {
+                    #(#name_arms)*
+                }
+            }
+
+            /// The number of arguments captured by this command.
+            #visibility fn arity(&self) -> usize {
+                self.arg_names().len()
+            }
+
+            /// The names of the arguments captured by this command.
+            #visibility fn arg_names(&self) -> &'static [&'static str] {
+                match *self {
+                    #(#arg_names_arms)*
+                }
+            }
+
+            /// The first line of the doc comment of the function this command calls, or `""` if
+            /// it has none.
+            #visibility fn description(&self) -> &'static str {
+                match *self {
+                    #(#description_arms)*
+                }
+            }
+        }
+    }
+}
+
+/// Generates the `audit_record` method, which records the name and captured arguments of a
+/// command for an audit log. The captured arguments must implement `Debug`, unless they are
+/// redacted.
+pub fn audit_impl(
+    visibility: &Visibility,
+    enum_name: &Ident,
+    cases: &[Case],
+    krate: &TokenStream,
+) -> TokenStream {
+    let arms = cases.iter().map(|case| {
+        let cfgs = case.cfgs();
+        let name = &case.name;
+        let function = case.ident.unraw().to_string();
+        let arg_names = case
+            .captures
+            .iter()
+            .map(|capture| capture.ident.unraw().to_string());
+        let bindings = case.captures.iter().map(|capture| {
+            let ident = &capture.ident;
+            if capture.options.redact {
+                quote!(_)
+            } else {
+                quote!(ref #ident)
+            }
+        });
+        let values = case.captures.iter().map(|capture| {
+            let ident = &capture.ident;
+            if capture.options.redact {
+                quote!(::std::string::String::from("<redacted>"))
+            } else {
+                quote!(::std::format!("{:?}", #ident))
+            }
+        });
+        let pattern = if case.captures.is_empty() {
+            quote!(#enum_name::#name)
+        } else {
+            quote!(#enum_name::#name(#(#bindings),*))
+        };
+        quote! {
+            #(#cfgs)*
+            #pattern => #krate::AuditRecord {
+                name: #function,
+                args: ::std::vec![#((#arg_names, #values)),*],
+            },
+        }
+    });
+
+    quote! {
+        impl #enum_name {
+            /// Records the function and captured arguments of this command, for an audit log.
+            #visibility fn audit_record(&self) -> #krate::AuditRecord {
+                match *self {
+                    #(#arms)*
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,93 @@
+use super::Signature;
+
+use proc_macro2::TokenStream;
+use proc_macro_error::abort;
+use quote::{format_ident, quote};
+use syn::{spanned::Spanned, Ident, ItemFn, Path, ReturnType, Visibility};
+
+/// The name of the type of registrations for an enum, which `register` must also be able to
+/// derive from the path to the enum.
+fn registration_name(enum_name: &Ident) -> Ident {
+    format_ident!("{}Registration", enum_name)
+}
+
+/// Generates the registration type for functions registered to an enum from other crates, and the
+/// methods to call them by name.
+pub fn registry_impl(
+    visibility: &Visibility,
+    enum_name: &Ident,
+    signature: &Signature,
+    krate: &TokenStream,
+) -> TokenStream {
+    if !signature.generics.params.is_empty() {
+        abort!(
+            signature.generics.span(),
+            "`registry` cannot be used with a generic signature"
+        );
+    }
+
+    let inventory = quote!(#krate::__private::inventory);
+    let registration = registration_name(enum_name);
+    let inputs = &signature.inputs;
+    let input_types = inputs.iter().map(|arg| &arg.ty).collect::<Vec<_>>();
+    let input_names = inputs.iter().map(|arg| &arg.ident);
+    let output_type = match &signature.output {
+        ReturnType::Default => quote!(()),
+        ReturnType::Type(.., ty) => quote!(#ty),
+    };
+    let function_type = quote!(fn(#(#input_types),*) -> #output_type);
+
+    quote! {
+        /// A function registered to be called by name, using `defunctionalize::register`.
+        #visibility struct #registration {
+            pub name: &'static str,
+            pub function: #function_type,
+        }
+
+        #inventory::collect!(#registration);
+
+        impl #enum_name {
+            /// All of the functions that have been registered to this enum.
+            #visibility fn registrations() -> impl ::core::iter::Iterator<Item = &'static #registration> {
+                ::core::iter::IntoIterator::into_iter(#inventory::iter::<#registration>)
+            }
+
+            /// Calls the registered function with the given name, if there is one.
+            #visibility fn call_registered(name: &str, #inputs) -> ::core::option::Option<#output_type> {
+                static TABLE: ::std::sync::OnceLock<
+                    ::std::collections::HashMap<&'static str, #function_type>,
+                > = ::std::sync::OnceLock::new();
+                let table = TABLE.get_or_init(|| {
+                    ::core::iter::Iterator::collect(::core::iter::Iterator::map(
+                        Self::registrations(),
+                        |registration| (registration.name, registration.function),
+                    ))
+                });
+                table.get(name).map(|function| function(#(#input_names),*))
+            }
+        }
+    }
+}
+
+/// Registers a function to an enum which was generated with the `registry` option.
+pub fn register(enum_path: Path, function: ItemFn) -> TokenStream {
+    let mut registration = enum_path;
+    let last = match registration.segments.last_mut() {
+        Some(last) => last,
+        None => abort!(registration.span(), "expected the path to an enum"),
+    };
+    last.ident = registration_name(&last.ident);
+
+    let name = &function.sig.ident;
+    let name_str = name.to_string();
+    quote! {
+        #function
+
+        ::defunctionalize::__private::inventory::submit! {
+            #registration {
+                name: #name_str,
+                function: #name,
+            }
+        }
+    }
+}
@@ -0,0 +1,85 @@
+use super::Case;
+
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{Ident, Visibility};
+
+/// Generates the methods and constants by which the layout of a `#[repr(C, u32)]` enum can be
+/// shared with C. Such an enum is laid out as a `u32` tag followed by a union, at
+/// `PAYLOAD_OFFSET`, of a `#[repr(C)]` struct of the captured arguments of each function.
+pub fn repr_c_impl(visibility: &Visibility, enum_name: &Ident, cases: &[Case]) -> TokenStream {
+    let union_align = cases.iter().flat_map(|case| {
+        case.captures.iter().map(move |capture| {
+            let cfgs = case.cfgs();
+            let ty = &capture.ty;
+            quote! {
+                #(#cfgs)*
+                if ::core::mem::align_of::<#ty>() > align {
+                    align = ::core::mem::align_of::<#ty>();
+                }
+            }
+        })
+    });
+
+    let offsets_arms = cases.iter().map(|case| {
+        let cfgs = case.cfgs();
+        let wildcard = case.wildcard();
+        let len = case.captures.len();
+        let fields = case.captures.iter().enumerate().map(|(index, capture)| {
+            let ty = &capture.ty;
+            let next = if index + 1 < len {
+                Some(quote!(offset += ::core::mem::size_of::<#ty>();))
+            } else {
+                None
+            };
+            quote! {
+                let align = ::core::mem::align_of::<#ty>();
+                offset = (offset + align - 1) & !(align - 1);
+                offsets[#index] = #enum_name::PAYLOAD_OFFSET + offset;
+                #next
+            }
+        });
+        quote! {
+            #(#cfgs)*
+            #enum_name::#wildcard => {
+                const OFFSETS: [usize; #len] = {
+                    #[allow(unused_mut, unused_variables)]
+                    let mut offset = 0;
+                    #[allow(unused_mut)]
+                    let mut offsets = [0; #len];
+                    #(#fields)*
+                    offsets
+                };
+                &OFFSETS
+            }
+        }
+    });
+
+    quote! {
+        impl #enum_name {
+            /// The offset in bytes of the union of captured arguments from the start of a
+            /// command, after its `u32` tag.
+            #visibility const PAYLOAD_OFFSET: usize = {
+                #[allow(unused_mut)]
+                let mut align = ::core::mem::align_of::<u32>();
+                #(#union_align)*
+                (::core::mem::size_of::<u32>() + align - 1) & !(align - 1)
+            };
+
+            /// The tag which identifies this command's function in its layout, which is its stable
+            /// tag if its function has one.
+            #visibility fn tag(&self) -> u32 {
+                // Safety: an enum with a primitive representation starts with its discriminant.
+                unsafe { *(self as *const Self as *const u32) }
+            }
+
+            /// The offset in bytes of each of this command's captured arguments from the start of
+            /// the command.
+            #visibility fn arg_offsets(&self) -> &'static [usize] {
+                match *self {
+                    #(#offsets_arms)*
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,114 @@
+use super::{Case, Signature};
+
+use proc_macro2::TokenStream;
+use proc_macro_error::{abort_if_dirty, emit_error};
+use quote::quote;
+use std::collections::HashSet;
+use syn::{Ident, ReturnType, Visibility};
+
+/// Generates the table of HTTP routes to the functions which have a
+/// `#[defunctionalize(route = "...")]`, and methods to handle a request by calling the function
+/// at its route. The captured arguments are read from the request body as JSON: a single value if
+/// there is one argument, or an array if there are several.
+pub fn routes_impl(
+    visibility: &Visibility,
+    enum_name: &Ident,
+    cases: &[Case],
+    signature: &Signature,
+    krate: &TokenStream,
+) -> Option<TokenStream> {
+    if cases.iter().all(|case| case.options.route.is_none()) {
+        return None;
+    }
+
+    let mut seen = HashSet::new();
+    for case in cases {
+        if let Some(route) = &case.options.route {
+            if !seen.insert((&route.method, &route.path)) {
+                emit_error!(
+                    case.ident.span(),
+                    "the route `{} {}` is already used by another function",
+                    route.method,
+                    route.path
+                );
+            }
+        }
+    }
+    abort_if_dirty();
+
+    let http = quote!(#krate::http);
+    let serde_json = quote!(#krate::__private::serde_json);
+    let routes = cases
+        .iter()
+        .filter_map(|case| Some((case, case.options.route.as_ref()?)))
+        .map(|(case, route)| {
+            let cfgs = case.cfgs();
+            let name = &case.name;
+            let method = &route.method;
+            let path = &route.path;
+            let types = case.captures.iter().map(|capture| &capture.ty);
+            let idents = case
+                .captures
+                .iter()
+                .map(|capture| &capture.ident)
+                .collect::<Vec<_>>();
+            let parse = match case.captures.len() {
+                0 => quote!(|_| ::core::result::Result::Ok(#enum_name::#name)),
+                1 => quote!(|body| ::core::result::Result::map(
+                    #serde_json::from_slice::<#(#types)*>(body),
+                    #enum_name::#name,
+                )),
+                _ => quote!(|body| ::core::result::Result::map(
+                    #serde_json::from_slice::<(#(#types,)*)>(body),
+                    |(#(#idents,)*)| #enum_name::#name(#(#idents),*),
+                )),
+            };
+            quote! {
+                #(#cfgs)*
+                #http::Route {
+                    method: #method,
+                    path: #path,
+                    parse: #parse,
+                },
+            }
+        });
+
+    let generics = &signature.generics;
+    let where_clause = &signature.generics.where_clause;
+    let inputs = signature.inputs.iter();
+    let input_names = signature.inputs.iter().map(|arg| &arg.ident);
+    let output_type = match &signature.output {
+        ReturnType::Default => quote!(()),
+        ReturnType::Type(.., ty) => quote!(#ty),
+    };
+
+    Some(quote! {
+        impl #enum_name {
+            /// The routes to the functions which can be called by HTTP requests.
+            #visibility fn routes() -> &'static [#http::Route<#enum_name>] {
+                const ROUTES: &[#http::Route<#enum_name>] = &[#(#routes)*];
+                ROUTES
+            }
+
+            /// Reads the command for the function at the route of a request from its body.
+            #visibility fn from_request(
+                method: &str,
+                path: &str,
+                body: &[u8],
+            ) -> ::core::result::Result<Self, #http::RouteError> {
+                #http::parse(Self::routes(), method, path, body)
+            }
+
+            /// Handles a request by calling the function at its route.
+            #visibility fn dispatch_request #generics (
+                method: &str,
+                path: &str,
+                body: &[u8],
+                #(#inputs,)*
+            ) -> ::core::result::Result<#output_type, #http::RouteError> #where_clause {
+                let command = Self::from_request(method, path, body)?;
+                ::core::result::Result::Ok(#enum_name::call(command, #(#input_names),*))
+            }
+        }
+    })
+}
@@ -0,0 +1,487 @@
+use super::{naming, options::SerdeOptions, Case};
+
+use proc_macro2::{Literal, TokenStream};
+use proc_macro_error::{
+    abort, abort_call_site, abort_if_dirty, diagnostic, emit_error, Level::Error,
+};
+use quote::{format_ident, quote};
+use std::collections::HashMap;
+use syn::{punctuated::Punctuated, Attribute, Ident, LitInt, Path, Token, Visibility};
+
+/// Checks that either all or none of the cases have a stable tag, and that no two cases share a
+/// tag. Returns whether the cases are tagged.
+pub fn validate_tags(cases: &[Case]) -> bool {
+    if cases.iter().all(|case| case.options.tag.is_none()) {
+        return false;
+    }
+
+    let mut seen = HashMap::new();
+    for case in cases {
+        let tag = match &case.options.tag {
+            Some(tag) => tag,
+            None => {
+                diagnostic!(
+                    case.ident.span(),
+                    Error,
+                    "`{}` has no tag, but other functions in this module do",
+                    case.ident
+                )
+                .help("add `#[defunctionalize(tag = ...)]` to this function".to_owned())
+                .emit();
+                continue;
+            }
+        };
+        if let Some(previous) = seen.insert(case.options.tag(), tag) {
+            diagnostic!(tag.span(), Error, "duplicate tag `{}`", tag)
+                .span_note(previous.span(), "previously used here".to_owned())
+                .emit();
+        }
+    }
+    abort_if_dirty();
+    true
+}
+
+/// Whether the enum derives the trait `name`, by the `derive` option or a `derive` attribute.
+pub fn derives(derives: &[Path], attrs: &[Attribute], name: &str) -> bool {
+    let derived = attrs
+        .iter()
+        .filter(|attr| attr.path.is_ident("derive"))
+        .filter_map(|attr| {
+            attr.parse_args_with(Punctuated::<Path, Token![,]>::parse_terminated)
+                .ok()
+        })
+        .flatten()
+        .collect::<Vec<_>>();
+    derives.iter().chain(&derived).any(|path| {
+        path.segments
+            .last()
+            .is_some_and(|segment| segment.ident == name)
+    })
+}
+
+/// The names by which a case was previously serialized, from the `alias` option of its function.
+/// Each is the name of the function, converted to a case name.
+fn aliases(case: &Case) -> Vec<String> {
+    case.options
+        .aliases
+        .iter()
+        .map(|alias| naming::camel_case(&alias.value()))
+        .collect()
+}
+
+/// Generates the `#[serde(alias = ...)]` attributes of a case's variant, for use with the serde
+/// derives. Without the derives, the aliases can only be used by the implementations for stable
+/// tags.
+pub fn alias_attr(case: &Case, derived: bool, tagged: bool) -> Option<TokenStream> {
+    let alias = case.options.aliases.first()?;
+    if tagged {
+        return None;
+    }
+    if !derived {
+        abort!(
+            alias.span(),
+            "`alias` requires the enum to derive `Deserialize`, or its functions to have tags"
+        );
+    }
+    let aliases = aliases(case);
+    Some(quote!(#[serde(#(alias = #aliases),*)]))
+}
+
+/// Generates the `#[serde(...)]` attribute selecting the representation of the enum, for use
+/// with the serde derives.
+pub fn serde_attr(options: &SerdeOptions, cases: &[Case]) -> TokenStream {
+    let span = options.span.unwrap();
+    if cases.iter().any(|case| case.options.tag.is_some()) {
+        abort!(
+            span,
+            "serde representation options cannot be combined with stable tags"
+        );
+    }
+    if options.untagged && (options.tag.is_some() || options.content.is_some()) {
+        abort!(
+            span,
+            "`untagged` cannot be combined with `tag` or `content`"
+        );
+    }
+    if options.content.is_some() && options.tag.is_none() {
+        abort!(span, "`content` requires `tag` to also be set");
+    }
+    if options.tag.is_some() && options.content.is_none() {
+        // Internally tagged enums can't represent the tuple variants which are generated for
+        // functions that capture more than one argument.
+        for case in cases.iter().filter(|case| case.captures.len() > 1) {
+            emit_error!(
+                case.ident.span(),
+                "`{}` captures more than one argument, which an internally tagged enum cannot represent",
+                case.ident;
+                help = "add `content = \"...\"` to the serde options to use adjacent tagging"
+            );
+        }
+        abort_if_dirty();
+    }
+
+    let tag = options.tag.iter();
+    let content = options.content.iter();
+    let untagged = if options.untagged {
+        Some(quote!(untagged))
+    } else {
+        None
+    };
+    quote!(#[serde(#(tag = #tag,)* #(content = #content,)* #untagged)])
+}
+
+/// Generates `Serialize` and `Deserialize` implementations which identify each variant by its
+/// stable tag rather than by its position, so that binary formats remain compatible as functions
+/// are added, removed, or reordered. Self-describing formats still identify variants by name.
+pub fn serde_impls(enum_name: &Ident, cases: &[Case], krate: &TokenStream) -> Option<TokenStream> {
+    if !validate_tags(cases) {
+        return None;
+    }
+
+    let serde = quote!(#krate::__private::serde);
+    let enum_str = enum_name.to_string();
+    let names = cases
+        .iter()
+        .map(|case| case.name.to_string())
+        .collect::<Vec<_>>();
+    let tags = cases
+        .iter()
+        .map(|case| Literal::u32_suffixed(case.options.tag().unwrap()))
+        .collect::<Vec<_>>();
+    let tags_u64 = cases
+        .iter()
+        .map(|case| Literal::u64_suffixed(case.options.tag().unwrap().into()));
+    let cfgs = cases.iter().map(|case| case.cfgs().collect::<Vec<_>>());
+    let cfgs = &cfgs.collect::<Vec<_>>();
+
+    // Previous names are accepted as well as the current ones.
+    let accepted = cases
+        .iter()
+        .zip(&names)
+        .zip(&tags)
+        .zip(cfgs)
+        .flat_map(|(((case, name), tag), cfgs)| {
+            std::iter::once(name.clone())
+                .chain(aliases(case))
+                .map(move |name| (name, tag, cfgs))
+        })
+        .collect::<Vec<_>>();
+    let accepted_names = accepted.iter().map(|(name, ..)| name);
+    let accepted_bytes = accepted
+        .iter()
+        .map(|(name, ..)| syn::LitByteStr::new(name.as_bytes(), enum_name.span()));
+    let accepted_tags = accepted.iter().map(|(_, tag, ..)| tag).collect::<Vec<_>>();
+    let accepted_cfgs = accepted.iter().map(|(.., cfgs)| cfgs).collect::<Vec<_>>();
+
+    let serialize_arms = cases.iter().zip(&names).zip(&tags).map(|((case, name), tag)| {
+        let cfgs = case.cfgs();
+        let pattern = case.ref_pattern();
+        let idents = case.captures.iter().map(|capture| &capture.ident);
+        let body = match case.captures.len() {
+            0 => quote!(#serde::Serializer::serialize_unit_variant(serializer, #enum_str, #tag, #name)),
+            1 => quote!(#serde::Serializer::serialize_newtype_variant(serializer, #enum_str, #tag, #name, #(#idents)*)),
+            len => quote!({
+                let mut state = #serde::Serializer::serialize_tuple_variant(serializer, #enum_str, #tag, #name, #len)?;
+                #(#serde::ser::SerializeTupleVariant::serialize_field(&mut state, #idents)?;)*
+                #serde::ser::SerializeTupleVariant::end(state)
+            }),
+        };
+        quote!(#(#cfgs)* #enum_name::#pattern => #body,)
+    });
+
+    let deserialize_arms = cases.iter().zip(&tags).map(|(case, tag)| {
+        let cfgs = case.cfgs();
+        let name = &case.name;
+        let body = match case.captures.len() {
+            0 => quote!({
+                #serde::de::VariantAccess::unit_variant(variant)?;
+                ::core::result::Result::Ok(#enum_name::#name)
+            }),
+            1 => {
+                let ty = &case.captures[0].ty;
+                quote! {
+                    ::core::result::Result::map(
+                        #serde::de::VariantAccess::newtype_variant::<#ty>(variant),
+                        #enum_name::#name,
+                    )
+                }
+            }
+            len => {
+                let visitor = format_ident!("__{}Visitor", name);
+                let expecting = format!("tuple variant {}::{}", enum_str, name);
+                let idents = case.captures.iter().map(|capture| &capture.ident).collect::<Vec<_>>();
+                let types = case.captures.iter().map(|capture| &capture.ty);
+                let indices = 0..len;
+                quote!({
+                    struct #visitor;
+
+                    impl<'de> #serde::de::Visitor<'de> for #visitor {
+                        type Value = #enum_name;
+
+                        fn expecting(&self, formatter: &mut ::core::fmt::Formatter) -> ::core::fmt::Result {
+                            ::core::fmt::Formatter::write_str(formatter, #expecting)
+                        }
+
+                        fn visit_seq<A>(self, mut seq: A) -> ::core::result::Result<Self::Value, A::Error>
+                        where
+                            A: #serde::de::SeqAccess<'de>,
+                        {
+                            #(let #idents = match #serde::de::SeqAccess::next_element::<#types>(&mut seq)? {
+                                ::core::option::Option::Some(value) => value,
+                                ::core::option::Option::None => {
+                                    return ::core::result::Result::Err(
+                                        #serde::de::Error::invalid_length(#indices, &self),
+                                    );
+                                }
+                            };)*
+                            ::core::result::Result::Ok(#enum_name::#name(#(#idents),*))
+                        }
+                    }
+
+                    #serde::de::VariantAccess::tuple_variant(variant, #len, #visitor)
+                })
+            }
+        };
+        quote!(#(#cfgs)* #tag => #body,)
+    });
+
+    let expecting = format!("enum {}", enum_str);
+
+    Some(quote! {
+        impl #serde::Serialize for #enum_name {
+            fn serialize<S>(&self, serializer: S) -> ::core::result::Result<S::Ok, S::Error>
+            where
+                S: #serde::Serializer,
+            {
+                match *self {
+                    #(#serialize_arms)*
+                }
+            }
+        }
+
+        impl<'de> #serde::Deserialize<'de> for #enum_name {
+            fn deserialize<D>(deserializer: D) -> ::core::result::Result<Self, D::Error>
+            where
+                D: #serde::Deserializer<'de>,
+            {
+                const VARIANTS: &[&str] = &[#(#(#cfgs)* #names,)*];
+
+                struct __Tag(u32);
+
+                struct __TagVisitor;
+
+                impl<'de> #serde::de::Visitor<'de> for __TagVisitor {
+                    type Value = __Tag;
+
+                    fn expecting(&self, formatter: &mut ::core::fmt::Formatter) -> ::core::fmt::Result {
+                        ::core::fmt::Formatter::write_str(formatter, "variant tag or identifier")
+                    }
+
+                    fn visit_u64<E>(self, value: u64) -> ::core::result::Result<__Tag, E>
+                    where
+                        E: #serde::de::Error,
+                    {
+                        match value {
+                            #(#(#cfgs)* #tags_u64 => ::core::result::Result::Ok(__Tag(#tags)),)*
+                            _ => ::core::result::Result::Err(#serde::de::Error::invalid_value(
+                                #serde::de::Unexpected::Unsigned(value),
+                                &self,
+                            )),
+                        }
+                    }
+
+                    fn visit_str<E>(self, value: &str) -> ::core::result::Result<__Tag, E>
+                    where
+                        E: #serde::de::Error,
+                    {
+                        match value {
+                            #(#(#accepted_cfgs)* #accepted_names => ::core::result::Result::Ok(__Tag(#accepted_tags)),)*
+                            _ => ::core::result::Result::Err(#serde::de::Error::unknown_variant(value, VARIANTS)),
+                        }
+                    }
+
+                    fn visit_bytes<E>(self, value: &[u8]) -> ::core::result::Result<__Tag, E>
+                    where
+                        E: #serde::de::Error,
+                    {
+                        match value {
+                            #(#(#accepted_cfgs)* #accepted_bytes => ::core::result::Result::Ok(__Tag(#accepted_tags)),)*
+                            _ => ::core::result::Result::Err(#serde::de::Error::invalid_value(
+                                #serde::de::Unexpected::Bytes(value),
+                                &self,
+                            )),
+                        }
+                    }
+                }
+
+                impl<'de> #serde::Deserialize<'de> for __Tag {
+                    fn deserialize<D>(deserializer: D) -> ::core::result::Result<Self, D::Error>
+                    where
+                        D: #serde::Deserializer<'de>,
+                    {
+                        #serde::Deserializer::deserialize_identifier(deserializer, __TagVisitor)
+                    }
+                }
+
+                struct __Visitor;
+
+                impl<'de> #serde::de::Visitor<'de> for __Visitor {
+                    type Value = #enum_name;
+
+                    fn expecting(&self, formatter: &mut ::core::fmt::Formatter) -> ::core::fmt::Result {
+                        ::core::fmt::Formatter::write_str(formatter, #expecting)
+                    }
+
+                    fn visit_enum<A>(self, data: A) -> ::core::result::Result<Self::Value, A::Error>
+                    where
+                        A: #serde::de::EnumAccess<'de>,
+                    {
+                        let (__Tag(tag), variant) = #serde::de::EnumAccess::variant::<__Tag>(data)?;
+                        match tag {
+                            #(#deserialize_arms)*
+                            _ => ::core::unreachable!(),
+                        }
+                    }
+                }
+
+                #serde::Deserializer::deserialize_enum(deserializer, #enum_str, VARIANTS, __Visitor)
+            }
+        }
+    })
+}
+
+/// Generates the `schema_version` method and the `SchemaVersion` implementation of an enum with a
+/// `version = ...` option, by which it is serialized as a `Versioned`.
+pub fn version_impl(
+    visibility: &Visibility,
+    enum_name: &Ident,
+    version: &LitInt,
+    krate: &TokenStream,
+) -> TokenStream {
+    let version = Literal::u32_suffixed(version.base10_parse().unwrap());
+    quote! {
+        impl #enum_name {
+            /// The version of the serialized representation of this enum.
+            #visibility const fn schema_version() -> u32 {
+                #version
+            }
+        }
+
+        impl #krate::SchemaVersion for #enum_name {
+            const SCHEMA_VERSION: u32 = #version;
+        }
+    }
+}
+
+/// Generates `encode` and `decode` methods using postcard, which need no allocation, for sending
+/// commands between devices. Cases are identified by their stable tags.
+pub fn postcard_impl(
+    visibility: &Visibility,
+    enum_name: &Ident,
+    cases: &[Case],
+    krate: &TokenStream,
+) -> TokenStream {
+    if !validate_tags(cases) {
+        abort_call_site!(
+            "`postcard` requires every function to have a stable tag";
+            help = "add `#[defunctionalize(tag = ...)]` to each function"
+        );
+    }
+
+    let postcard = quote!(#krate::__private::postcard);
+    quote! {
+        impl #enum_name {
+            /// Encodes this command into `buffer`, returning the number of bytes written.
+            #visibility fn encode(&self, buffer: &mut [u8]) -> ::core::result::Result<usize, #krate::PostcardError> {
+                ::core::result::Result::map(#postcard::to_slice(self, buffer), |used| used.len())
+            }
+
+            /// Decodes a command which was encoded with `encode`.
+            #visibility fn decode(bytes: &[u8]) -> ::core::result::Result<Self, #krate::PostcardError> {
+                #postcard::from_bytes(bytes)
+            }
+        }
+    }
+}
+
+/// Generates a test for each function, which serializes a sample of its command, with each
+/// argument set to its default, and checks that deserializing and serializing it again gives the
+/// same JSON, and the same postcard bytes unless the representation is customized by the `serde`
+/// option, which postcard cannot read. Arguments serialized as `"<redacted>"` cannot be read
+/// back, so functions with them are not tested.
+pub fn roundtrip_tests_impl(
+    enum_name: &Ident,
+    tests_name: &Ident,
+    cases: &[Case],
+    binary: bool,
+    krate: &TokenStream,
+) -> TokenStream {
+    let serde_json = quote!(#krate::__private::serde_json);
+    let postcard = quote!(#krate::__private::postcard);
+    let tests = cases
+        .iter()
+        .filter(|case| {
+            !case
+                .captures
+                .iter()
+                .any(|capture| capture.options.redact_serde)
+        })
+        .map(|case| {
+            let cfgs = case.cfgs();
+            let name = &case.name;
+            let test_name = &case.ident;
+            let command = if case.captures.is_empty() {
+                quote!(#enum_name::#name)
+            } else {
+                let samples = case.captures.iter().map(|capture| {
+                    if capture.options.default.is_some() {
+                        let default_fn = case.default_fn(capture);
+                        quote!(#enum_name::#default_fn())
+                    } else {
+                        quote!(::core::default::Default::default())
+                    }
+                });
+                quote!(#enum_name::#name(#(#samples),*))
+            };
+            quote! {
+                #(#cfgs)*
+                #[test]
+                fn #test_name() {
+                    __roundtrip(#command);
+                }
+            }
+        });
+    let binary = if binary {
+        Some(quote! {
+            let bytes = #postcard::to_extend(&command, ::std::vec::Vec::new()).unwrap();
+            let decoded: #enum_name = #postcard::from_bytes(&bytes).unwrap();
+            ::core::assert_eq!(
+                #postcard::to_extend(&decoded, ::std::vec::Vec::new()).unwrap(),
+                bytes,
+                "the command changed when encoded with postcard and decoded",
+            );
+        })
+    } else {
+        None
+    };
+
+    quote! {
+        #[cfg(test)]
+        mod #tests_name {
+            use super::#enum_name;
+
+            fn __roundtrip(command: #enum_name) {
+                let json = #serde_json::to_string(&command).unwrap();
+                let decoded: #enum_name = #serde_json::from_str(&json).unwrap();
+                ::core::assert_eq!(
+                    #serde_json::to_string(&decoded).unwrap(),
+                    json,
+                    "the command changed when serialized to JSON and deserialized",
+                );
+                #binary
+            }
+
+            #(#tests)*
+        }
+    }
+}
@@ -1,22 +1,92 @@
 use super::SimpleArg;
 
+use proc_macro2::TokenStream;
+use quote::quote;
 use syn::{
     parenthesized,
     parse::{Parse, ParseStream},
     punctuated::Punctuated,
-    token::Paren,
-    Generics, Ident, ReturnType, Token, WhereClause,
+    visit_mut::{self, VisitMut},
+    Generics, Ident, Lifetime, ReturnType, Token, Type, TypeReference, WhereClause, WherePredicate,
 };
 
 pub struct Signature {
-    pub fn_token: Token![fn],
+    pub asyncness: Option<Token![async]>,
     pub ident: Option<Ident>,
     pub generics: Generics,
-    pub paren_token: Paren,
     pub inputs: Punctuated<SimpleArg, Token![,]>,
     pub output: ReturnType,
 }
 
+/// Replaces elided lifetimes with a named one, for when the types of the signature are used
+/// somewhere that elision is not allowed.
+struct NameLifetimes<'a> {
+    lifetime: &'a Lifetime,
+    named: bool,
+}
+
+impl VisitMut for NameLifetimes<'_> {
+    fn visit_type_reference_mut(&mut self, reference: &mut TypeReference) {
+        if reference.lifetime.is_none() {
+            reference.lifetime = Some(self.lifetime.clone());
+            self.named = true;
+        }
+        visit_mut::visit_type_reference_mut(self, reference);
+    }
+
+    fn visit_lifetime_mut(&mut self, lifetime: &mut Lifetime) {
+        if lifetime.ident == "_" {
+            *lifetime = self.lifetime.clone();
+            self.named = true;
+        }
+    }
+}
+
+impl Signature {
+    /// Whether `ty` is one of the signature's type parameters.
+    pub fn is_type_param(&self, ty: &Type) -> bool {
+        match ty {
+            Type::Path(path) if path.qself.is_none() => {
+                path.path.get_ident().is_some_and(|ident| {
+                    self.generics
+                        .type_params()
+                        .any(|param| param.ident == *ident)
+                })
+            }
+            Type::Group(group) => self.is_type_param(&group.elem),
+            Type::Paren(paren) => self.is_type_param(&paren.elem),
+            _ => false,
+        }
+    }
+
+    /// The types of the inputs and the output, with elided lifetimes replaced by `lifetime`, and
+    /// whether there were any.
+    pub fn with_named_lifetimes(&self, lifetime: &Lifetime) -> (Vec<Type>, TokenStream, bool) {
+        let mut visitor = NameLifetimes {
+            lifetime,
+            named: false,
+        };
+        let types = self
+            .inputs
+            .iter()
+            .map(|arg| {
+                let mut ty = (*arg.ty).clone();
+                visitor.visit_type_mut(&mut ty);
+                ty
+            })
+            .collect();
+        let output = match &self.output {
+            ReturnType::Default => quote!(()),
+            ReturnType::Type(.., ty) => {
+                let mut ty = (**ty).clone();
+                visitor.visit_type_mut(&mut ty);
+                quote!(#ty)
+            }
+        };
+        (types, output, visitor.named)
+    }
+}
+
 fn parse_fn_args(input: ParseStream) -> syn::Result<Punctuated<SimpleArg, Token![,]>> {
     let mut args = Punctuated::new();
     while !input.is_empty() {
@@ -30,24 +100,55 @@ fn parse_fn_args(input: ParseStream) -> syn::Result<Punctuated<SimpleArg, Token!
     Ok(args)
 }
 
+fn parse_where_clause(input: ParseStream) -> syn::Result<Option<WhereClause>> {
+    let where_token: Token![where] = match input.parse()? {
+        Some(where_token) => where_token,
+        None => return Ok(None),
+    };
+    let mut predicates = Punctuated::new();
+    loop {
+        predicates.push_value(input.parse::<WherePredicate>()?);
+        // Options may follow the where clause, so only continue on a comma if the tokens after
+        // it are another predicate.
+        if !input.peek(Token![,]) || input.fork().parse::<WhereContinuation>().is_err() {
+            break;
+        }
+        predicates.push_punct(input.parse()?);
+    }
+    Ok(Some(WhereClause {
+        where_token,
+        predicates,
+    }))
+}
+
+struct WhereContinuation;
+
+impl Parse for WhereContinuation {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        input.parse::<Token![,]>()?;
+        input.parse::<WherePredicate>()?;
+        Ok(WhereContinuation)
+    }
+}
+
 impl Parse for Signature {
     fn parse(input: ParseStream) -> syn::Result<Self> {
-        let fn_token: Token![fn] = input.parse()?;
+        let asyncness: Option<Token![async]> = input.parse()?;
+        input.parse::<Token![fn]>()?;
         let ident: Option<Ident> = input.parse()?;
         let generics: Generics = input.parse()?;
         let content;
-        let paren_token: Paren = parenthesized!(content in input);
+        parenthesized!(content in input);
         let inputs: Punctuated<SimpleArg, Token![,]> = parse_fn_args(&content)?;
         let output: ReturnType = input.parse()?;
-        let where_clause: Option<WhereClause> = input.parse()?;
+        let where_clause: Option<WhereClause> = parse_where_clause(input)?;
         Ok(Signature {
-            fn_token,
+            asyncness,
             ident,
             generics: Generics {
                 where_clause,
                 ..generics
             },
-            paren_token,
             inputs,
             output,
         })
@@ -0,0 +1,84 @@
+use super::{naming, Case, CaseOptions, Signature};
+
+use proc_macro2::TokenStream;
+use proc_macro_error::abort;
+use quote::{format_ident, quote};
+use syn::{ext::IdentExt, ItemFn, ReturnType};
+
+/// Generates a struct for a single function, which holds its captured arguments and implements
+/// `DeFn` by calling the function with them.
+pub fn defunctionalize_fn(signature: Signature, function: ItemFn) -> TokenStream {
+    if let Some(asyncness) = &signature.asyncness {
+        abort!(
+            asyncness,
+            "`defunctionalize_fn` cannot be used with an async signature"
+        );
+    }
+    let ident = &function.sig.ident;
+    let struct_name = signature
+        .ident
+        .clone()
+        .unwrap_or_else(|| format_ident!("{}", naming::camel_case(&ident.unraw().to_string())));
+    let mut case = match Case::new(
+        TokenStream::new(),
+        &function,
+        (vec![], CaseOptions::default()),
+        &signature,
+        false,
+    ) {
+        Ok(case) => case,
+        Err(diagnostic) => diagnostic.abort(),
+    };
+    case.path = quote!(#ident);
+
+    let visibility = &function.vis;
+    let types = case.captures.iter().map(|capture| &capture.ty);
+    let idents = case
+        .captures
+        .iter()
+        .map(|capture| &capture.ident)
+        .collect::<Vec<_>>();
+    let struct_def = if case.captures.is_empty() {
+        quote!(#visibility struct #struct_name;)
+    } else {
+        quote!(#visibility struct #struct_name(#(pub #types),*);)
+    };
+    let destructure = if case.captures.is_empty() {
+        quote!(let #struct_name = self;)
+    } else {
+        quote!(let #struct_name(#(#idents),*) = self;)
+    };
+
+    let generics = &signature.generics;
+    let where_clause = &signature.generics.where_clause;
+    let inputs = &signature.inputs;
+    let input_types = inputs.iter().map(|arg| &arg.ty).collect::<Vec<_>>();
+    let input_names = inputs.iter().map(|arg| &arg.ident).collect::<Vec<_>>();
+    let call = case.call(&input_names);
+    let output = &signature.output;
+    let output_type = match output {
+        ReturnType::Default => quote!(()),
+        ReturnType::Type(.., ty) => quote!(#ty),
+    };
+
+    quote! {
+        #function
+
+        #struct_def
+
+        impl #struct_name {
+            #visibility fn call #generics (self, #inputs) #output #where_clause {
+                #destructure
+                #call
+            }
+        }
+
+        impl #generics ::defunctionalize::DeFn<(#(#input_types),*)> for #struct_name #where_clause {
+            type Output = #output_type;
+
+            fn call(self, (#(#input_names),*): (#(#input_types),*)) #output {
+                #struct_name::call(self, #(#input_names),*)
+            }
+        }
+    }
+}
@@ -0,0 +1,42 @@
+use super::Case;
+
+use proc_macro2::TokenStream;
+use quote::{quote, quote_spanned};
+use syn::{ext::IdentExt, spanned::Spanned};
+
+/// Generates assertions, evaluated at compile time, that no captured argument nor the captured
+/// arguments of any function together are larger than `max_size` bytes, so that the size of the
+/// enum does not grow unnoticed.
+pub fn size_assertions(cases: &[Case], max_size: usize) -> TokenStream {
+    let assertions = cases.iter().filter(|case| !case.captures.is_empty()).map(|case| {
+        let cfgs = case.cfgs().collect::<Vec<_>>();
+        let function = case.ident.unraw();
+        let arguments = case.captures.iter().map(|capture| {
+            let ty = &capture.ty;
+            let message = format!(
+                "the argument `{}` of `{}` is larger than the maximum size of {} bytes",
+                capture.ident.unraw(),
+                function,
+                max_size,
+            );
+            quote_spanned! {ty.span()=>
+                #(#cfgs)*
+                const _: () = ::core::assert!(::core::mem::size_of::<#ty>() <= #max_size, #message);
+            }
+        });
+        if case.captures.len() == 1 {
+            return quote!(#(#arguments)*);
+        }
+        let types = case.captures.iter().map(|capture| &capture.ty);
+        let message = format!(
+            "the arguments of `{}` are larger than the maximum size of {} bytes",
+            function, max_size,
+        );
+        quote_spanned! {case.ident.span()=>
+            #(#arguments)*
+            #(#cfgs)*
+            const _: () = ::core::assert!(::core::mem::size_of::<(#(#types,)*)>() <= #max_size, #message);
+        }
+    });
+    quote!(#(#assertions)*)
+}
@@ -0,0 +1,82 @@
+use super::Case;
+
+use proc_macro2::TokenStream;
+use proc_macro_error::{abort_if_dirty, emit_error};
+use quote::quote;
+use syn::{Ident, Visibility};
+
+/// Generates the `invert` method, which maps each command to the command which undoes it, for
+/// functions which declared their inverse with `#[defunctionalize(undo = ...)]`.
+pub fn invert_impl(
+    visibility: &Visibility,
+    enum_name: &Ident,
+    cases: &[Case],
+    krate: &TokenStream,
+) -> Option<TokenStream> {
+    if cases.iter().all(|case| case.options.undo.is_none()) {
+        return None;
+    }
+
+    let arms = cases
+        .iter()
+        .map(|case| {
+            let cfgs = case.cfgs();
+            let undo = match &case.options.undo {
+                Some(undo) => undo,
+                None => {
+                    let wildcard = case.wildcard();
+                    return quote!(#(#cfgs)* #enum_name::#wildcard => ::core::option::Option::None,);
+                }
+            };
+            let inverse = match cases.iter().find(|other| other.ident == undo) {
+                Some(inverse) => inverse,
+                None => {
+                    emit_error!(
+                        undo.span(),
+                        "there is no function `{}` in this module",
+                        undo
+                    );
+                    return quote!();
+                }
+            };
+            if inverse.captures.len() != case.captures.len() {
+                emit_error!(
+                    undo.span(),
+                    "`{}` cannot undo `{}`, as it captures {} arguments instead of {}",
+                    undo,
+                    case.ident,
+                    inverse.captures.len(),
+                    case.captures.len()
+                );
+                return quote!();
+            }
+            let pattern = case.pattern();
+            let inverse_name = &inverse.name;
+            let inverse = if case.captures.is_empty() {
+                quote!(#enum_name::#inverse_name)
+            } else {
+                let idents = case.captures.iter().map(|capture| &capture.ident);
+                quote!(#enum_name::#inverse_name(#(#idents),*))
+            };
+            quote!(#(#cfgs)* #enum_name::#pattern => ::core::option::Option::Some(#inverse),)
+        })
+        .collect::<Vec<_>>();
+    abort_if_dirty();
+
+    Some(quote! {
+        impl #enum_name {
+            /// The command which undoes this one, if it has an inverse.
+            #visibility fn invert(self) -> ::core::option::Option<Self> {
+                match self {
+                    #(#arms)*
+                }
+            }
+        }
+
+        impl #krate::Invert for #enum_name {
+            fn invert(self) -> ::core::option::Option<Self> {
+                #enum_name::invert(self)
+            }
+        }
+    })
+}
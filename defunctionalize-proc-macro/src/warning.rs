@@ -0,0 +1,16 @@
+use proc_macro2::{Span, TokenStream};
+use quote::{format_ident, quote_spanned};
+
+/// Generates a warning pointing at `span`. Proc macros cannot emit warnings on stable, so the
+/// warning is the deprecation of an item named `name` which is used immediately.
+pub fn warning(span: Span, name: &str, message: &str) -> TokenStream {
+    let marker = format_ident!("{}", name, span = span);
+    quote_spanned! {span=>
+        const _: () = {
+            #[deprecated(note = #message)]
+            #[allow(non_camel_case_types)]
+            struct #marker;
+            let _ = #marker;
+        };
+    }
+}
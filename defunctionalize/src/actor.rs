@@ -0,0 +1,96 @@
+//! Running defunctionalized functions as an actor, which owns the shared arguments and handles
+//! commands sent to it one at a time.
+//!
+//! The `actor` option of the `defunctionalize` attribute generates a handle with a method for each
+//! function, which sends the command through a [`Sender`] and waits for its output. The actor
+//! receives the commands from the corresponding [`Mailbox`].
+
+use super::DeFn;
+
+use std::fmt::{self, Display};
+use tokio::sync::{mpsc, oneshot};
+
+/// A command which was sent to the actor, and the means of replying to it.
+pub struct Envelope<E, O> {
+    pub command: E,
+    reply: oneshot::Sender<O>,
+}
+
+impl<E, O> Envelope<E, O> {
+    /// Calls the command, and replies with its output. If the sender is no longer waiting for the
+    /// reply, the output is discarded.
+    pub fn call<I>(self, args: I)
+    where
+        E: DeFn<I, Output = O>,
+    {
+        let _ = self.reply.send(self.command.call(args));
+    }
+}
+
+/// The error returned by a [`Sender`] when the actor has stopped.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Closed;
+
+impl Display for Closed {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "the actor has stopped")
+    }
+}
+
+impl std::error::Error for Closed {}
+
+/// Sends commands to an actor, and waits for their outputs.
+pub struct Sender<E, O> {
+    sender: mpsc::Sender<Envelope<E, O>>,
+}
+
+impl<E, O> Clone for Sender<E, O> {
+    fn clone(&self) -> Self {
+        Sender {
+            sender: self.sender.clone(),
+        }
+    }
+}
+
+impl<E, O> Sender<E, O> {
+    /// Sends a command to the actor, and waits for the output of calling it.
+    pub async fn send(&self, command: E) -> Result<O, Closed> {
+        let (reply, output) = oneshot::channel();
+        self.sender
+            .send(Envelope { command, reply })
+            .await
+            .map_err(|_| Closed)?;
+        output.await.map_err(|_| Closed)
+    }
+}
+
+/// Receives the commands sent to an actor.
+pub struct Mailbox<E, O> {
+    receiver: mpsc::Receiver<Envelope<E, O>>,
+}
+
+impl<E, O> Mailbox<E, O> {
+    /// Waits for the next command, returning `None` once every [`Sender`] has been dropped.
+    pub async fn recv(&mut self) -> Option<Envelope<E, O>> {
+        self.receiver.recv().await
+    }
+
+    /// Handles commands in the order they are received, calling each with the same `state`, until
+    /// every [`Sender`] has been dropped.
+    pub async fn run<W>(mut self, state: &mut W)
+    where
+        W: ?Sized,
+        E: for<'a> DeFn<&'a mut W, Output = O>,
+    {
+        while let Some(envelope) = self.recv().await {
+            envelope.call(&mut *state);
+        }
+    }
+}
+
+/// Creates a channel to an actor, which holds up to `buffer` commands that have not yet been
+/// received.
+pub fn channel<E, O>(buffer: usize) -> (Sender<E, O>, Mailbox<E, O>) {
+    let (sender, receiver) = mpsc::channel(buffer);
+    (Sender { sender }, Mailbox { receiver })
+}
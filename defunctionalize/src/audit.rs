@@ -0,0 +1,25 @@
+use std::fmt::{self, Display};
+
+/// A record of a command, for logging each command which is called along with its arguments.
+/// Generated by the `audit_record` method of enums with the `audit` option.
+///
+/// It is displayed on one line as the name of the function followed by each argument as
+/// `name=value`, where the value is formatted with `Debug`.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct AuditRecord {
+    /// The name of the function the command calls.
+    pub name: &'static str,
+    /// The name of each of the arguments captured by the command, with its `Debug` formatting.
+    pub args: Vec<(&'static str, String)>,
+}
+
+impl Display for AuditRecord {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.name)?;
+        for (name, value) in &self.args {
+            write!(f, " {}={}", name, value)?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,40 @@
+use super::DeFn;
+
+/// Calls each of the commands in order with a clone of `args`, returning their outputs in the same
+/// order.
+pub fn dispatch_all<E, I>(commands: impl IntoIterator<Item = E>, args: I) -> Vec<E::Output>
+where
+    E: DeFn<I>,
+    I: Clone,
+{
+    commands
+        .into_iter()
+        .map(|command| command.call(args.clone()))
+        .collect()
+}
+
+/// Calls each of the commands in order with the same `state`, so each sees the changes made by
+/// those before it, returning their outputs in the same order.
+pub fn dispatch_all_mut<E, W, O>(commands: impl IntoIterator<Item = E>, state: &mut W) -> Vec<O>
+where
+    W: ?Sized,
+    E: for<'a> DeFn<&'a mut W, Output = O>,
+{
+    commands
+        .into_iter()
+        .map(|command| command.call(&mut *state))
+        .collect()
+}
+
+/// Applies each of the events to the state in order, returning the final state, such as to rebuild
+/// an aggregate from the events which produced it.
+pub fn fold_events<S, E>(initial: S, events: impl IntoIterator<Item = E>) -> S
+where
+    E: for<'a> DeFn<&'a mut S>,
+{
+    let mut state = initial;
+    for event in events {
+        event.call(&mut state);
+    }
+    state
+}
@@ -0,0 +1,26 @@
+use super::DeFn;
+
+/// Two commands which are called one after the other with the same input, returning both of
+/// their outputs.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Default)]
+pub struct Chain<A, B>(pub A, pub B);
+
+impl<A, B> Chain<A, B> {
+    pub fn new(first: A, second: B) -> Self {
+        Chain(first, second)
+    }
+}
+
+impl<A, B, I> DeFn<I> for Chain<A, B>
+where
+    A: DeFn<I>,
+    B: DeFn<I>,
+    I: Clone,
+{
+    type Output = (A::Output, B::Output);
+
+    fn call(self, args: I) -> Self::Output {
+        let first = self.0.call(args.clone());
+        (first, self.1.call(args))
+    }
+}
@@ -0,0 +1,5 @@
+pub const trait DeFn<Input> {
+    type Output;
+
+    fn call(self, args: Input) -> Self::Output;
+}
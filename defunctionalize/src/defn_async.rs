@@ -0,0 +1,9 @@
+use std::future::Future;
+
+/// A command of an async signature, whose call returns a future which can be sent to other
+/// threads, such as to be spawned as a task.
+pub trait DeFnAsync<Input> {
+    type Output;
+
+    fn call_async(self, args: Input) -> impl Future<Output = Self::Output> + Send;
+}
@@ -0,0 +1,91 @@
+//! Calling commands on other threads, as work items.
+//!
+//! [`spawn_dispatch`] moves a command and its arguments onto a thread chosen by an [`Executor`],
+//! and returns a [`JoinHandle`] through which to wait for its output. Any function which runs a
+//! job, such as `rayon::spawn`, is an executor; [`NewThread`] runs each job on its own thread.
+
+use super::DeFn;
+
+use std::fmt::{self, Display};
+use std::sync::mpsc;
+use std::thread;
+
+/// A job which is run by an [`Executor`].
+pub type Job = Box<dyn FnOnce() + Send + 'static>;
+
+/// Runs jobs, usually on other threads.
+pub trait Executor {
+    fn execute(&self, job: Job);
+}
+
+impl<F> Executor for F
+where
+    F: Fn(Job),
+{
+    fn execute(&self, job: Job) {
+        self(job)
+    }
+}
+
+/// An [`Executor`] which runs each job on a new thread.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct NewThread;
+
+impl Executor for NewThread {
+    fn execute(&self, job: Job) {
+        thread::spawn(job);
+    }
+}
+
+/// The error returned by a [`JoinHandle`] when the command was never called, or panicked, so
+/// there is no output.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Canceled;
+
+impl Display for Canceled {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "the command did not complete")
+    }
+}
+
+impl std::error::Error for Canceled {}
+
+/// Waits for the output of a command which was spawned by [`spawn_dispatch`].
+#[derive(Debug)]
+pub struct JoinHandle<O> {
+    output: mpsc::Receiver<O>,
+}
+
+impl<O> JoinHandle<O> {
+    /// Waits for the command to complete, returning its output.
+    pub fn join(self) -> Result<O, Canceled> {
+        self.output.recv().map_err(|_| Canceled)
+    }
+
+    /// Returns the output of the command if it has completed, or else the handle, to try again
+    /// later.
+    pub fn try_join(self) -> Result<Result<O, Canceled>, Self> {
+        match self.output.try_recv() {
+            Ok(output) => Ok(Ok(output)),
+            Err(mpsc::TryRecvError::Disconnected) => Ok(Err(Canceled)),
+            Err(mpsc::TryRecvError::Empty) => Err(self),
+        }
+    }
+}
+
+/// Calls a command with `args` on a thread of the `executor`. The arguments are moved to the
+/// thread, so shared arguments should be cloned for each command, such as by holding them in an
+/// `Arc`.
+pub fn spawn_dispatch<X, D, I>(executor: &X, command: D, args: I) -> JoinHandle<D::Output>
+where
+    X: Executor + ?Sized,
+    D: DeFn<I> + Send + 'static,
+    D::Output: Send + 'static,
+    I: Send + 'static,
+{
+    let (sender, output) = mpsc::sync_channel(1);
+    executor.execute(Box::new(move || {
+        let _ = sender.send(command.call(args));
+    }));
+    JoinHandle { output }
+}
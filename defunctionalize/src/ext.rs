@@ -0,0 +1,25 @@
+use super::{Fallback, Inspect, InspectOutput};
+
+/// Combinators for commands.
+pub trait DeFnExt: Sized {
+    /// Calls `fallback` with the same input if this command fails. See [`Fallback`].
+    fn or_else<B>(self, fallback: B) -> Fallback<Self, B> {
+        Fallback(self, fallback)
+    }
+
+    /// Calls `f` with a reference to this command before calling it. See [`Inspect`].
+    fn inspect<F>(self, f: F) -> Inspect<Self, F>
+    where
+        F: FnOnce(&Self),
+    {
+        Inspect(self, f)
+    }
+
+    /// Calls `f` with a reference to the output of this command before returning it. See
+    /// [`InspectOutput`].
+    fn inspect_output<F>(self, f: F) -> InspectOutput<Self, F> {
+        InspectOutput(self, f)
+    }
+}
+
+impl<D> DeFnExt for D {}
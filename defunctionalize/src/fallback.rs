@@ -0,0 +1,28 @@
+use super::DeFn;
+
+/// Two commands, the second of which is called with the same input when the first fails,
+/// returning its output instead.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Default)]
+pub struct Fallback<A, B>(pub A, pub B);
+
+impl<A, B> Fallback<A, B> {
+    pub fn new(first: A, fallback: B) -> Self {
+        Fallback(first, fallback)
+    }
+}
+
+impl<A, B, I, T, E, F> DeFn<I> for Fallback<A, B>
+where
+    A: DeFn<I, Output = Result<T, E>>,
+    B: DeFn<I, Output = Result<T, F>>,
+    I: Clone,
+{
+    type Output = Result<T, F>;
+
+    fn call(self, args: I) -> Self::Output {
+        match self.0.call(args.clone()) {
+            Ok(output) => Ok(output),
+            Err(..) => self.1.call(args),
+        }
+    }
+}
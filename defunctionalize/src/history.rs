@@ -0,0 +1,83 @@
+use super::DeFn;
+
+/// Commands which may have another command that undoes them. Implemented for enums with functions
+/// that declare their inverse with `#[defunctionalize(undo = ...)]`.
+pub trait Invert: Sized {
+    /// The command which undoes this one, if it has an inverse.
+    fn invert(self) -> Option<Self>;
+}
+
+/// The commands which have been applied, so that they can be undone and redone.
+#[derive(Clone, Debug)]
+pub struct History<E> {
+    done: Vec<E>,
+    undone: Vec<E>,
+}
+
+impl<E> Default for History<E> {
+    fn default() -> Self {
+        History::new()
+    }
+}
+
+impl<E> History<E> {
+    pub fn new() -> Self {
+        History {
+            done: Vec::new(),
+            undone: Vec::new(),
+        }
+    }
+
+    /// Calls a command, and records it so that it can be undone. Any commands which were undone
+    /// can no longer be redone.
+    pub fn apply<I>(&mut self, command: E, args: I) -> E::Output
+    where
+        E: DeFn<I> + Clone,
+    {
+        self.undone.clear();
+        self.done.push(command.clone());
+        command.call(args)
+    }
+
+    /// Calls the inverse of the most recently applied command, returning its output. Returns
+    /// `None`, leaving the history unchanged, if there is nothing to undo or the command has no
+    /// inverse.
+    pub fn undo<I>(&mut self, args: I) -> Option<E::Output>
+    where
+        E: DeFn<I> + Invert + Clone,
+    {
+        let inverse = self.done.last()?.clone().invert()?;
+        self.undone.extend(self.done.pop());
+        Some(inverse.call(args))
+    }
+
+    /// Calls the most recently undone command again, returning its output.
+    pub fn redo<I>(&mut self, args: I) -> Option<E::Output>
+    where
+        E: DeFn<I> + Clone,
+    {
+        let command = self.undone.pop()?;
+        self.done.push(command.clone());
+        Some(command.call(args))
+    }
+
+    /// Whether the most recently applied command can be undone.
+    pub fn can_undo(&self) -> bool
+    where
+        E: Invert + Clone,
+    {
+        self.done
+            .last()
+            .is_some_and(|command| command.clone().invert().is_some())
+    }
+
+    pub fn can_redo(&self) -> bool {
+        !self.undone.is_empty()
+    }
+
+    /// Forgets all of the commands, so that none can be undone or redone.
+    pub fn clear(&mut self) {
+        self.done.clear();
+        self.undone.clear();
+    }
+}
@@ -0,0 +1,50 @@
+//! Calling defunctionalized functions from HTTP requests.
+//!
+//! Functions are given routes with `#[defunctionalize(route = "METHOD /path")]`, after which the
+//! enum has a table of [`Route`]s, and methods to read a command from a request and call it.
+//! Routing only matches exact paths, so it is intended to be used from within the handler of a
+//! web framework rather than in place of its router.
+
+use std::fmt::{self, Display};
+
+/// The route to one of the functions of an enum.
+pub struct Route<E> {
+    pub method: &'static str,
+    pub path: &'static str,
+    /// Reads the command from the body of a request, as JSON.
+    pub parse: fn(&[u8]) -> Result<E, serde_json::Error>,
+}
+
+/// An error which occurred while reading a command from a request.
+#[derive(Debug)]
+pub enum RouteError {
+    /// No function has the route of the request.
+    NotFound,
+    /// The body of the request could not be read as the arguments of the function.
+    BadRequest(serde_json::Error),
+}
+
+impl Display for RouteError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            RouteError::NotFound => write!(f, "not found"),
+            RouteError::BadRequest(error) => write!(f, "bad request: {}", error),
+        }
+    }
+}
+
+impl std::error::Error for RouteError {}
+
+/// Reads a command from a request, using the route which matches its method and path.
+pub fn parse<E>(
+    routes: &[Route<E>],
+    method: &str,
+    path: &str,
+    body: &[u8],
+) -> Result<E, RouteError> {
+    let route = routes
+        .iter()
+        .find(|route| route.method == method && route.path == path)
+        .ok_or(RouteError::NotFound)?;
+    (route.parse)(body).map_err(RouteError::BadRequest)
+}
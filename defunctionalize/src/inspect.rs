@@ -0,0 +1,38 @@
+use super::DeFn;
+
+/// A command with a function which is called with a reference to the command just before it is
+/// called, for observing commands without changing them, such as to log them.
+#[derive(Clone, Copy, Debug)]
+pub struct Inspect<D, F>(pub D, pub F);
+
+impl<D, F, I> DeFn<I> for Inspect<D, F>
+where
+    D: DeFn<I>,
+    F: FnOnce(&D),
+{
+    type Output = D::Output;
+
+    fn call(self, args: I) -> Self::Output {
+        (self.1)(&self.0);
+        self.0.call(args)
+    }
+}
+
+/// A command with a function which is called with a reference to the output of the command before
+/// it is returned.
+#[derive(Clone, Copy, Debug)]
+pub struct InspectOutput<D, F>(pub D, pub F);
+
+impl<D, F, I> DeFn<I> for InspectOutput<D, F>
+where
+    D: DeFn<I>,
+    F: FnOnce(&D::Output),
+{
+    type Output = D::Output;
+
+    fn call(self, args: I) -> Self::Output {
+        let output = self.0.call(args);
+        (self.1)(&output);
+        output
+    }
+}
@@ -0,0 +1,76 @@
+use super::DeFn;
+
+use std::time::{Duration, SystemTime};
+
+/// A command to be run as a background job, with what a queue of jobs needs to retry it when it
+/// fails. It is serializable with the `serde` feature, so that it can be stored until it runs.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Job<E> {
+    pub command: E,
+    /// The number of times the job has been run.
+    pub attempts: u32,
+    /// The number of times the job may be run again after it is first run.
+    pub max_retries: u32,
+    /// The time before which the job should not be run.
+    pub next_run_at: SystemTime,
+    /// A key which identifies the job, so that it is not run again after it has succeeded, such as
+    /// when it was enqueued twice.
+    pub idempotency_key: String,
+}
+
+impl<E> Job<E> {
+    /// A job which may run immediately, and is not retried.
+    pub fn new(idempotency_key: impl Into<String>, command: E) -> Self {
+        Job {
+            command,
+            attempts: 0,
+            max_retries: 0,
+            next_run_at: SystemTime::UNIX_EPOCH,
+            idempotency_key: idempotency_key.into(),
+        }
+    }
+
+    pub fn with_max_retries(self, max_retries: u32) -> Self {
+        Job {
+            max_retries,
+            ..self
+        }
+    }
+
+    /// Delays the job until `time`.
+    pub fn run_at(self, time: SystemTime) -> Self {
+        Job {
+            next_run_at: time,
+            ..self
+        }
+    }
+
+    /// Whether the job is due to run at `now`, and has attempts remaining.
+    pub fn should_run(&self, now: SystemTime) -> bool {
+        !self.is_exhausted() && self.next_run_at <= now
+    }
+
+    /// Whether the job has been run as many times as it may be.
+    pub fn is_exhausted(&self) -> bool {
+        self.attempts > self.max_retries
+    }
+
+    /// Runs a clone of the command, counting the attempt.
+    pub fn attempt<I>(&mut self, args: I) -> E::Output
+    where
+        E: DeFn<I> + Clone,
+    {
+        self.attempts += 1;
+        self.command.clone().call(args)
+    }
+
+    /// Delays the next attempt of a job which failed until `delay` after `now`, doubling the
+    /// delay for each attempt which has already been made.
+    pub fn backoff(&mut self, now: SystemTime, delay: Duration) {
+        let factor = 1u32
+            .checked_shl(self.attempts.saturating_sub(1))
+            .unwrap_or(u32::MAX);
+        self.next_run_at = now + delay.saturating_mul(factor);
+    }
+}
@@ -1,9 +1,106 @@
+#![cfg_attr(feature = "const-trait", feature(const_trait_impl))]
+
+#[cfg(not(feature = "const-trait"))]
 pub trait DeFn<Input> {
     type Output;
 
     fn call(self, args: Input) -> Self::Output;
 }
 
+// The syntax of a `const` trait is not accepted by stable compilers, even when configured out.
+#[cfg(feature = "const-trait")]
+mod const_trait;
+#[cfg(feature = "const-trait")]
+pub use const_trait::DeFn;
+
+/// Calls a boxed command, such as a large command stored in a queue, by moving it out of its box.
+impl<Input, D> DeFn<Input> for Box<D>
+where
+    D: DeFn<Input>,
+{
+    type Output = D::Output;
+
+    fn call(self, args: Input) -> Self::Output {
+        (*self).call(args)
+    }
+}
+
+/// Calls a command which may have failed to be constructed, passing on the error if it did.
+impl<Input, D, E> DeFn<Input> for Result<D, E>
+where
+    D: DeFn<Input>,
+{
+    type Output = Result<D::Output, E>;
+
+    fn call(self, args: Input) -> Self::Output {
+        self.map(|command| command.call(args))
+    }
+}
+
+#[cfg(feature = "actor")]
+pub mod actor;
+mod audit;
+mod batch;
+mod chain;
+mod defn_async;
+pub mod executor;
+mod ext;
+mod fallback;
+mod history;
+#[cfg(feature = "http")]
+pub mod http;
+mod inspect;
+mod job;
+mod once;
+#[cfg(feature = "prost")]
+mod proto;
+mod queue;
+mod registry;
+#[cfg(feature = "remote")]
+pub mod remote;
+mod scheduler;
+mod schema;
+mod shared;
+mod stable_hash;
+mod step;
+#[cfg(feature = "tokio")]
+pub mod tokio;
+mod tuple;
+mod versioned;
+
+pub use audit::AuditRecord;
+pub use batch::{dispatch_all, dispatch_all_mut, fold_events};
+pub use chain::Chain;
+pub use defn_async::DeFnAsync;
+pub use ext::DeFnExt;
+pub use fallback::Fallback;
+pub use history::{History, Invert};
+pub use inspect::{Inspect, InspectOutput};
+pub use job::Job;
+pub use once::Once;
+#[cfg(feature = "prost")]
+pub use proto::ProtoError;
+pub use queue::CommandQueue;
+pub use registry::{DynDeFn, Registry};
+pub use scheduler::{ByPriority, Prioritized, PriorityKey, Scheduler};
+pub use schema::{fingerprint, CommandDesc};
+pub use shared::Shared;
+pub use stable_hash::{stable_hash, StableHasher};
+pub use step::{run_to_completion, Step};
+pub use tuple::{Tupled, Untupled};
+#[cfg(feature = "serde")]
+pub use versioned::decode_versioned;
+pub use versioned::{DecodeError, SchemaVersion, Versioned};
+
+/// The error returned by the `encode` and `decode` methods generated by the `postcard` option.
+#[cfg(feature = "postcard")]
+pub use postcard::Error as PostcardError;
+
+/// The error returned by the `from_json_str` and `dispatch_json` methods generated by the `json`
+/// option.
+#[cfg(feature = "serde_json")]
+pub use serde_json::Error as JsonError;
+
 #[cfg(feature = "proc-macro")]
 #[macro_use]
 #[allow(unused_imports)]
@@ -11,3 +108,30 @@ extern crate defunctionalize_proc_macro;
 #[cfg(feature = "proc-macro")]
 #[doc(hidden)]
 pub use defunctionalize_proc_macro::*;
+
+#[doc(hidden)]
+pub mod __private {
+    #[cfg(feature = "clap")]
+    pub use clap;
+    #[cfg(feature = "inventory")]
+    pub use inventory;
+    #[cfg(feature = "postcard")]
+    pub use postcard;
+    #[cfg(feature = "prost")]
+    pub use prost;
+    #[cfg(feature = "schemars")]
+    pub use schemars;
+    #[cfg(feature = "serde")]
+    pub use serde;
+
+    /// Serializes an argument given `#[defunctionalize(redact(serde))]` in place of its value.
+    #[cfg(feature = "serde")]
+    pub fn serialize_redacted<T, S>(_: &T, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.serialize_str("<redacted>")
+    }
+    #[cfg(feature = "serde_json")]
+    pub use serde_json;
+}
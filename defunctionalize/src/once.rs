@@ -0,0 +1,63 @@
+use super::DeFn;
+
+/// A command which can be called only once, even through a mutable reference, such as when it is
+/// stored in a shared structure. Calling it again returns `None`, or panics if it was created by
+/// [`Once::panicking`].
+#[derive(Debug)]
+pub struct Once<D> {
+    command: Option<D>,
+    panics: bool,
+}
+
+impl<D> Once<D> {
+    pub fn new(command: D) -> Self {
+        Once {
+            command: Some(command),
+            panics: false,
+        }
+    }
+
+    /// A command which panics if it is called again, for when doing so would be a bug.
+    pub fn panicking(command: D) -> Self {
+        Once {
+            command: Some(command),
+            panics: true,
+        }
+    }
+
+    /// Calls the command, if it has not been called already.
+    pub fn call_mut<I>(&mut self, args: I) -> Option<D::Output>
+    where
+        D: DeFn<I>,
+    {
+        match self.command.take() {
+            Some(command) => Some(command.call(args)),
+            None if self.panics => panic!("a `Once` command was called more than once"),
+            None => None,
+        }
+    }
+
+    pub fn is_called(&self) -> bool {
+        self.command.is_none()
+    }
+
+    /// The command, if it has not been called.
+    pub fn get(&self) -> Option<&D> {
+        self.command.as_ref()
+    }
+
+    pub fn into_inner(self) -> Option<D> {
+        self.command
+    }
+}
+
+impl<D, I> DeFn<I> for &mut Once<D>
+where
+    D: DeFn<I>,
+{
+    type Output = Option<D::Output>;
+
+    fn call(self, args: I) -> Self::Output {
+        self.call_mut(args)
+    }
+}
@@ -0,0 +1,23 @@
+use std::fmt::{self, Display};
+
+/// The error returned when converting a protobuf message generated by the `protobuf` option into
+/// a command.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ProtoError {
+    /// The bytes could not be decoded as the message.
+    Decode(prost::DecodeError),
+    /// The message does not hold any command, such as when it was encoded with a function which
+    /// this version of the enum does not have.
+    MissingCommand,
+}
+
+impl Display for ProtoError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ProtoError::Decode(error) => write!(f, "{}", error),
+            ProtoError::MissingCommand => write!(f, "the message holds no command"),
+        }
+    }
+}
+
+impl std::error::Error for ProtoError {}
@@ -0,0 +1,69 @@
+use super::DeFn;
+
+use std::collections::VecDeque;
+
+/// A queue of commands to be called later, for when they are produced while the arguments they
+/// need are unavailable, such as while iterating over the world they are going to modify.
+#[derive(Clone, Debug)]
+pub struct CommandQueue<E> {
+    commands: VecDeque<E>,
+}
+
+impl<E> Default for CommandQueue<E> {
+    fn default() -> Self {
+        CommandQueue::new()
+    }
+}
+
+impl<E> CommandQueue<E> {
+    pub fn new() -> Self {
+        CommandQueue {
+            commands: VecDeque::new(),
+        }
+    }
+
+    /// Adds a command to the end of the queue.
+    pub fn push(&mut self, command: E) {
+        self.commands.push_back(command);
+    }
+
+    pub fn len(&self) -> usize {
+        self.commands.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.commands.is_empty()
+    }
+
+    /// Discards all of the commands without calling them.
+    pub fn clear(&mut self) {
+        self.commands.clear();
+    }
+
+    /// Calls each of the queued commands in order with the same `world`, leaving the queue empty.
+    /// Their outputs are discarded.
+    pub fn apply_all<W>(&mut self, world: &mut W)
+    where
+        W: ?Sized,
+        E: for<'a> DeFn<&'a mut W>,
+    {
+        for command in self.commands.drain(..) {
+            command.call(&mut *world);
+        }
+    }
+}
+
+impl<E> Extend<E> for CommandQueue<E> {
+    fn extend<T: IntoIterator<Item = E>>(&mut self, iter: T) {
+        self.commands.extend(iter);
+    }
+}
+
+impl<E> IntoIterator for CommandQueue<E> {
+    type Item = E;
+    type IntoIter = std::collections::vec_deque::IntoIter<E>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.commands.into_iter()
+    }
+}
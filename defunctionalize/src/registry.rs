@@ -0,0 +1,82 @@
+use super::DeFn;
+
+use std::collections::BTreeMap;
+
+/// A command which can be called through a reference to a trait object, unlike [`DeFn`], by
+/// calling a clone of it.
+pub trait DynDeFn<I, O> {
+    fn call_dyn(&self, args: I) -> O;
+}
+
+impl<D, I, O> DynDeFn<I, O> for D
+where
+    D: DeFn<I, Output = O> + Clone,
+{
+    fn call_dyn(&self, args: I) -> O {
+        self.clone().call(args)
+    }
+}
+
+/// Commands of any type stored by name, which take the same input and produce the same output,
+/// for dispatching dynamically, such as to plugins.
+pub struct Registry<I, O> {
+    commands: BTreeMap<String, Box<dyn DynDeFn<I, O>>>,
+}
+
+impl<I, O> Default for Registry<I, O> {
+    fn default() -> Self {
+        Registry::new()
+    }
+}
+
+impl<I, O> Registry<I, O> {
+    pub fn new() -> Self {
+        Registry {
+            commands: BTreeMap::new(),
+        }
+    }
+
+    /// Stores a command under `name`, returning the command it replaced, if any.
+    pub fn register<D>(
+        &mut self,
+        name: impl Into<String>,
+        command: D,
+    ) -> Option<Box<dyn DynDeFn<I, O>>>
+    where
+        D: DynDeFn<I, O> + 'static,
+    {
+        self.commands.insert(name.into(), Box::new(command))
+    }
+
+    pub fn remove(&mut self, name: &str) -> Option<Box<dyn DynDeFn<I, O>>> {
+        self.commands.remove(name)
+    }
+
+    pub fn get(&self, name: &str) -> Option<&dyn DynDeFn<I, O>> {
+        self.commands.get(name).map(|command| command.as_ref())
+    }
+
+    pub fn contains(&self, name: &str) -> bool {
+        self.commands.contains_key(name)
+    }
+
+    /// Calls the command stored under `name`, if there is one.
+    pub fn dispatch(&self, name: &str, args: I) -> Option<O> {
+        self.get(name).map(|command| command.call_dyn(args))
+    }
+
+    pub fn len(&self) -> usize {
+        self.commands.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.commands.is_empty()
+    }
+
+    /// The names of the commands in order, with the commands themselves.
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &dyn DynDeFn<I, O>)> {
+        self.commands
+            .iter()
+            .map(|(name, command)| (name.as_str(), command.as_ref()))
+    }
+}
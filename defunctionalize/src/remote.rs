@@ -0,0 +1,174 @@
+//! Calling defunctionalized functions across a process boundary.
+//!
+//! Requests and responses are sent as newline-delimited JSON. A server reads requests with
+//! [`serve`], calling each command with its own local shared arguments, and a [`Client`] sends
+//! requests and waits for their responses.
+
+use super::DeFn;
+
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use std::fmt::{self, Display};
+use std::io::{self, BufRead, Write};
+
+/// A command to be called remotely.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Request<E> {
+    pub id: u64,
+    pub command: E,
+}
+
+/// The result of calling the command of the [`Request`] with the same `id`.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Response<T> {
+    pub id: u64,
+    pub result: Result<T, RemoteError>,
+}
+
+/// An error which occurred on the server while handling a request.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum RemoteError {
+    /// The request could not be decoded as a command.
+    BadRequest(String),
+}
+
+impl Display for RemoteError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            RemoteError::BadRequest(message) => write!(f, "bad request: {}", message),
+        }
+    }
+}
+
+impl std::error::Error for RemoteError {}
+
+/// An error which occurred while making a request with a [`Client`].
+#[derive(Debug)]
+pub enum ClientError {
+    Io(io::Error),
+    Json(serde_json::Error),
+    Remote(RemoteError),
+    /// The server responded to a different request than the one that was sent.
+    Mismatched {
+        expected: u64,
+        found: u64,
+    },
+}
+
+impl Display for ClientError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ClientError::Io(error) => error.fmt(f),
+            ClientError::Json(error) => error.fmt(f),
+            ClientError::Remote(error) => error.fmt(f),
+            ClientError::Mismatched { expected, found } => write!(
+                f,
+                "expected response to request {}, but received response to request {}",
+                expected, found
+            ),
+        }
+    }
+}
+
+impl std::error::Error for ClientError {}
+
+impl From<io::Error> for ClientError {
+    fn from(error: io::Error) -> Self {
+        ClientError::Io(error)
+    }
+}
+
+impl From<serde_json::Error> for ClientError {
+    fn from(error: serde_json::Error) -> Self {
+        ClientError::Json(error)
+    }
+}
+
+#[derive(Deserialize)]
+struct RawRequest {
+    id: u64,
+    command: serde_json::Value,
+}
+
+/// Serves requests read from `input` until it is exhausted, calling each command with a clone of
+/// `args` and writing the responses to `output`.
+///
+/// Requests which cannot be decoded are answered with a [`RemoteError::BadRequest`] when their
+/// `id` can be determined, and skipped otherwise.
+pub fn serve<E, I, R, W>(input: R, mut output: W, args: I) -> io::Result<()>
+where
+    E: DeFn<I> + DeserializeOwned,
+    E::Output: Serialize,
+    I: Clone,
+    R: BufRead,
+    W: Write,
+{
+    for line in input.lines() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let request: RawRequest = match serde_json::from_str(&line) {
+            Ok(request) => request,
+            Err(..) => continue,
+        };
+        let result = serde_json::from_value::<E>(request.command)
+            .map(|command| command.call(args.clone()))
+            .map_err(|error| RemoteError::BadRequest(error.to_string()));
+        let response = Response {
+            id: request.id,
+            result,
+        };
+        serde_json::to_writer(&mut output, &response)?;
+        output.write_all(b"\n")?;
+        output.flush()?;
+    }
+    Ok(())
+}
+
+/// Sends requests to a server which is running [`serve`].
+pub struct Client<R, W> {
+    input: R,
+    output: W,
+    next_id: u64,
+}
+
+impl<R, W> Client<R, W>
+where
+    R: BufRead,
+    W: Write,
+{
+    /// Creates a client which reads responses from `input` and writes requests to `output`.
+    pub fn new(input: R, output: W) -> Self {
+        Client {
+            input,
+            output,
+            next_id: 0,
+        }
+    }
+
+    /// Sends a command to the server, and waits for the output of calling it.
+    pub fn call<E, T>(&mut self, command: &E) -> Result<T, ClientError>
+    where
+        E: Serialize,
+        T: DeserializeOwned,
+    {
+        let id = self.next_id;
+        self.next_id += 1;
+        serde_json::to_writer(&mut self.output, &Request { id, command })?;
+        self.output.write_all(b"\n")?;
+        self.output.flush()?;
+
+        let mut line = String::new();
+        if self.input.read_line(&mut line)? == 0 {
+            return Err(ClientError::Io(io::ErrorKind::UnexpectedEof.into()));
+        }
+        let response: Response<T> = serde_json::from_str(&line)?;
+        if response.id != id {
+            return Err(ClientError::Mismatched {
+                expected: id,
+                found: response.id,
+            });
+        }
+        response.result.map_err(ClientError::Remote)
+    }
+}
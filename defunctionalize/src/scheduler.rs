@@ -0,0 +1,135 @@
+use super::DeFn;
+
+use std::cmp::{Ordering, Reverse};
+use std::collections::BinaryHeap;
+use std::iter::FromIterator;
+
+/// The order of a command among others by priority. Commands of a greater priority have greater
+/// keys, and among commands of equal priority, those with a lesser index have greater keys.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+pub struct PriorityKey {
+    priority: i32,
+    index: Reverse<u32>,
+}
+
+impl PriorityKey {
+    pub const fn new(priority: i32, index: u32) -> Self {
+        PriorityKey {
+            priority,
+            index: Reverse(index),
+        }
+    }
+}
+
+/// Commands which have a priority, by which a `Scheduler` orders them. Implemented for enums with
+/// functions that declare a `#[defunctionalize(priority = ...)]`.
+pub trait Prioritized {
+    /// The key by which commands are called, greatest first.
+    fn priority_key(&self) -> PriorityKey;
+}
+
+/// A command ordered by its priority without requiring the command itself to be ordered, so that
+/// commands which are equal need not be of equal priority. Commands wrapped in `ByPriority` can be
+/// kept in a `BinaryHeap`, from which those of the greatest priority are popped first.
+#[derive(Clone, Debug)]
+pub struct ByPriority<E>(pub E);
+
+impl<E: Prioritized> PartialEq for ByPriority<E> {
+    fn eq(&self, other: &Self) -> bool {
+        self.0.priority_key() == other.0.priority_key()
+    }
+}
+
+impl<E: Prioritized> Eq for ByPriority<E> {}
+
+impl<E: Prioritized> PartialOrd for ByPriority<E> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<E: Prioritized> Ord for ByPriority<E> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.0.priority_key().cmp(&other.0.priority_key())
+    }
+}
+
+/// Commands which are called in order of priority, greatest first, as given by their
+/// `priority_key`.
+#[derive(Clone, Debug)]
+pub struct Scheduler<E: Prioritized> {
+    commands: BinaryHeap<ByPriority<E>>,
+}
+
+impl<E: Prioritized> Default for Scheduler<E> {
+    fn default() -> Self {
+        Scheduler::new()
+    }
+}
+
+impl<E: Prioritized> Scheduler<E> {
+    pub fn new() -> Self {
+        Scheduler {
+            commands: BinaryHeap::new(),
+        }
+    }
+
+    pub fn push(&mut self, command: E) {
+        self.commands.push(ByPriority(command));
+    }
+
+    /// The command which will be called next.
+    pub fn peek(&self) -> Option<&E> {
+        self.commands.peek().map(|command| &command.0)
+    }
+
+    pub fn len(&self) -> usize {
+        self.commands.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.commands.is_empty()
+    }
+
+    /// Discards all of the commands without calling them.
+    pub fn clear(&mut self) {
+        self.commands.clear();
+    }
+
+    /// Calls the command of the greatest priority, returning its output, or `None` if there are no
+    /// commands.
+    pub fn run_next<I>(&mut self, args: I) -> Option<E::Output>
+    where
+        E: DeFn<I>,
+    {
+        self.commands.pop().map(|command| command.0.call(args))
+    }
+
+    /// Calls all of the commands in order of priority with clones of `args`, leaving the scheduler
+    /// empty, and returns their outputs in the same order.
+    pub fn run_all<I>(&mut self, args: I) -> Vec<E::Output>
+    where
+        E: DeFn<I>,
+        I: Clone,
+    {
+        let mut outputs = Vec::with_capacity(self.commands.len());
+        while let Some(output) = self.run_next(args.clone()) {
+            outputs.push(output);
+        }
+        outputs
+    }
+}
+
+impl<E: Prioritized> Extend<E> for Scheduler<E> {
+    fn extend<T: IntoIterator<Item = E>>(&mut self, iter: T) {
+        self.commands.extend(iter.into_iter().map(ByPriority));
+    }
+}
+
+impl<E: Prioritized> FromIterator<E> for Scheduler<E> {
+    fn from_iter<T: IntoIterator<Item = E>>(iter: T) -> Self {
+        Scheduler {
+            commands: iter.into_iter().map(ByPriority).collect(),
+        }
+    }
+}
@@ -0,0 +1,53 @@
+/// A description of one of the commands of an enum, for tools which list the commands without
+/// reading the source, such as admin interfaces or generators of code in other languages. Every
+/// enum has a `SCHEMA` describing each of its commands.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct CommandDesc {
+    /// The name of the function the command calls.
+    pub name: &'static str,
+    /// The position of the function among all of the enum's functions.
+    pub index: usize,
+    /// The names of the arguments captured by the command.
+    pub arg_names: &'static [&'static str],
+    /// The types of the arguments captured by the command, as written in the source.
+    pub arg_types: &'static [&'static str],
+    /// The first paragraph of the function's doc comment, if it has one.
+    pub doc: Option<&'static str>,
+}
+
+const FNV_OFFSET: u64 = 0xcbf2_9ce4_8422_2325;
+const FNV_PRIME: u64 = 0x0000_0100_0000_01b3;
+
+/// Adds a string to an FNV-1a hash, followed by a byte which never occurs in a string, so that
+/// the boundaries between strings are hashed too.
+const fn hash_str(mut hash: u64, string: &str) -> u64 {
+    let bytes = string.as_bytes();
+    let mut i = 0;
+    while i < bytes.len() {
+        hash ^= bytes[i] as u64;
+        hash = hash.wrapping_mul(FNV_PRIME);
+        i += 1;
+    }
+    hash ^= 0xff;
+    hash.wrapping_mul(FNV_PRIME)
+}
+
+/// Hashes the names of the commands, in order, and the types of their captured arguments, which
+/// determine their serialized representation. Used for the `FINGERPRINT` of every enum.
+pub const fn fingerprint(schema: &[CommandDesc]) -> u64 {
+    let mut hash = FNV_OFFSET;
+    let mut i = 0;
+    while i < schema.len() {
+        hash = hash_str(hash, schema[i].name);
+        let mut j = 0;
+        while j < schema[i].arg_types.len() {
+            hash = hash_str(hash, schema[i].arg_types[j]);
+            j += 1;
+        }
+        hash ^= 0xfe;
+        hash = hash.wrapping_mul(FNV_PRIME);
+        i += 1;
+    }
+    hash
+}
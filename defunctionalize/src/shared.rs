@@ -0,0 +1,48 @@
+use super::DeFn;
+
+use std::ops::Deref;
+use std::sync::Arc;
+
+/// A command which is shared by reference counting, so that it can be cheaply cloned and handed to
+/// many threads, each of which may call it. Calling it moves the command out if it is no longer
+/// shared, and otherwise calls a clone of it.
+#[derive(Debug, PartialEq, Eq, Hash, Default)]
+pub struct Shared<D>(pub Arc<D>);
+
+impl<D> Shared<D> {
+    pub fn new(command: D) -> Self {
+        Shared(Arc::new(command))
+    }
+}
+
+impl<D> Clone for Shared<D> {
+    fn clone(&self) -> Self {
+        Shared(Arc::clone(&self.0))
+    }
+}
+
+impl<D> Deref for Shared<D> {
+    type Target = D;
+
+    fn deref(&self) -> &D {
+        &self.0
+    }
+}
+
+impl<D> From<D> for Shared<D> {
+    fn from(command: D) -> Self {
+        Shared::new(command)
+    }
+}
+
+impl<D, I> DeFn<I> for Shared<D>
+where
+    D: DeFn<I> + Clone,
+{
+    type Output = D::Output;
+
+    fn call(self, args: I) -> Self::Output {
+        let command = Arc::try_unwrap(self.0).unwrap_or_else(|shared| (*shared).clone());
+        command.call(args)
+    }
+}
@@ -0,0 +1,27 @@
+use super::DeFn;
+
+/// The result of a command which may continue with another command, such as a state machine's
+/// transition, or a defunctionalized continuation.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Step<E, T> {
+    /// The command to call next.
+    Continue(E),
+    /// The final result.
+    Done(T),
+}
+
+/// Calls a command with a clone of `args`, then each command that it continues with, until one
+/// is done.
+pub fn run_to_completion<E, I, T>(command: E, args: I) -> T
+where
+    E: DeFn<I, Output = Step<E, T>>,
+    I: Clone,
+{
+    let mut command = command;
+    loop {
+        match command.call(args.clone()) {
+            Step::Continue(next) => command = next,
+            Step::Done(output) => return output,
+        }
+    }
+}
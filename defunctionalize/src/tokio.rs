@@ -0,0 +1,38 @@
+//! Running commands of async signatures as Tokio tasks.
+//!
+//! [`spawn`] calls a command on the runtime as its own task, and [`spawn_loop`] starts a task
+//! which calls the commands sent to it one at a time, with clones of the same arguments.
+
+use super::DeFnAsync;
+
+use ::tokio::sync::mpsc;
+use ::tokio::task::JoinHandle;
+
+/// Calls a command as a new task, returning the handle through which to wait for its output.
+pub fn spawn<D, I>(command: D, args: I) -> JoinHandle<D::Output>
+where
+    D: DeFnAsync<I>,
+    D::Output: Send + 'static,
+    I: 'static,
+    D: 'static,
+{
+    ::tokio::spawn(command.call_async(args))
+}
+
+/// Starts a task which calls each command sent through the returned sender in order, waiting for
+/// each to complete before calling the next, with a clone of `args`. The channel holds up to
+/// `buffer` commands which have not yet been received. The task ends once every sender has been
+/// dropped and the remaining commands have been called.
+pub fn spawn_loop<D, I>(buffer: usize, args: I) -> (mpsc::Sender<D>, JoinHandle<()>)
+where
+    D: DeFnAsync<I, Output = ()> + Send + 'static,
+    I: Clone + Send + 'static,
+{
+    let (sender, mut receiver) = mpsc::channel::<D>(buffer);
+    let handle = ::tokio::spawn(async move {
+        while let Some(command) = receiver.recv().await {
+            command.call_async(args.clone()).await;
+        }
+    });
+    (sender, handle)
+}
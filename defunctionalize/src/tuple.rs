@@ -0,0 +1,32 @@
+use super::DeFn;
+
+/// Adapts a command taking a single input to take that input as a 1-tuple, for generic code which
+/// passes inputs as tuples of any length.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Default)]
+pub struct Tupled<D>(pub D);
+
+impl<D, I> DeFn<(I,)> for Tupled<D>
+where
+    D: DeFn<I>,
+{
+    type Output = D::Output;
+
+    fn call(self, (args,): (I,)) -> Self::Output {
+        self.0.call(args)
+    }
+}
+
+/// Adapts a command taking a 1-tuple to take the single input directly.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Default)]
+pub struct Untupled<D>(pub D);
+
+impl<D, I> DeFn<I> for Untupled<D>
+where
+    D: DeFn<(I,)>,
+{
+    type Output = D::Output;
+
+    fn call(self, args: I) -> Self::Output {
+        self.0.call((args,))
+    }
+}
@@ -0,0 +1,193 @@
+use std::fmt::{self, Display};
+
+/// Commands whose serialized representation has a version, which changes whenever commands
+/// serialized by an older version can no longer be read. Implemented for enums generated with
+/// the `version = ...` option.
+pub trait SchemaVersion {
+    const SCHEMA_VERSION: u32;
+}
+
+impl<T> SchemaVersion for &T
+where
+    T: SchemaVersion + ?Sized,
+{
+    const SCHEMA_VERSION: u32 = T::SCHEMA_VERSION;
+}
+
+/// A command which is serialized along with its schema version, as a struct with a `version` and
+/// a `command` field. Commands of a different version are rejected when deserializing; use
+/// [`decode_versioned`] to tell them apart from corrupt payloads.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Versioned<T>(pub T);
+
+/// The error returned by [`decode_versioned`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum DecodeError<E> {
+    /// The command was serialized with a different schema version.
+    UnknownVersion { found: u32, expected: u32 },
+    /// The payload could not be deserialized.
+    Corrupt(E),
+}
+
+impl<E> Display for DecodeError<E>
+where
+    E: Display,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            DecodeError::UnknownVersion { found, expected } => {
+                write!(f, "unknown schema version {}, expected {}", found, expected)
+            }
+            DecodeError::Corrupt(error) => write!(f, "corrupt payload: {}", error),
+        }
+    }
+}
+
+impl<E> std::error::Error for DecodeError<E> where E: std::error::Error {}
+
+#[cfg(feature = "serde")]
+mod serde_impl {
+    use super::{DecodeError, SchemaVersion, Versioned};
+
+    use serde::de::{self, Deserialize, Deserializer, MapAccess, SeqAccess, Visitor};
+    use serde::ser::{Serialize, SerializeStruct, Serializer};
+    use std::cell::Cell;
+    use std::fmt;
+    use std::marker::PhantomData;
+
+    const FIELDS: &[&str] = &["version", "command"];
+
+    #[derive(serde::Deserialize)]
+    #[serde(field_identifier, rename_all = "lowercase")]
+    enum Field {
+        Version,
+        Command,
+    }
+
+    impl<T> Serialize for Versioned<T>
+    where
+        T: SchemaVersion + Serialize,
+    {
+        fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+        where
+            S: Serializer,
+        {
+            let mut state = serializer.serialize_struct("Versioned", 2)?;
+            state.serialize_field("version", &T::SCHEMA_VERSION)?;
+            state.serialize_field("command", &self.0)?;
+            state.end()
+        }
+    }
+
+    /// Reads the version before the command, recording a version which does not match, so that
+    /// the error can be told apart from those of the command.
+    struct VersionedVisitor<'a, T> {
+        mismatch: &'a Cell<Option<u32>>,
+        command: PhantomData<T>,
+    }
+
+    impl<T> VersionedVisitor<'_, T>
+    where
+        T: SchemaVersion,
+    {
+        fn check<E>(&self, version: u32) -> Result<(), E>
+        where
+            E: de::Error,
+        {
+            if version == T::SCHEMA_VERSION {
+                return Ok(());
+            }
+            self.mismatch.set(Some(version));
+            Err(E::custom(format_args!(
+                "unknown schema version {}, expected {}",
+                version,
+                T::SCHEMA_VERSION
+            )))
+        }
+    }
+
+    impl<'de, T> Visitor<'de> for VersionedVisitor<'_, T>
+    where
+        T: SchemaVersion + Deserialize<'de>,
+    {
+        type Value = T;
+
+        fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            write!(f, "a versioned command")
+        }
+
+        fn visit_seq<A>(self, mut seq: A) -> Result<T, A::Error>
+        where
+            A: SeqAccess<'de>,
+        {
+            let version = seq
+                .next_element()?
+                .ok_or_else(|| de::Error::invalid_length(0, &self))?;
+            self.check(version)?;
+            seq.next_element()?
+                .ok_or_else(|| de::Error::invalid_length(1, &self))
+        }
+
+        fn visit_map<A>(self, mut map: A) -> Result<T, A::Error>
+        where
+            A: MapAccess<'de>,
+        {
+            match map.next_key()? {
+                Some(Field::Version) => self.check(map.next_value()?)?,
+                Some(Field::Command) => {
+                    return Err(de::Error::custom("expected `version` before `command`"))
+                }
+                None => return Err(de::Error::missing_field("version")),
+            }
+            match map.next_key()? {
+                Some(Field::Command) => map.next_value(),
+                Some(Field::Version) => Err(de::Error::duplicate_field("version")),
+                None => Err(de::Error::missing_field("command")),
+            }
+        }
+    }
+
+    impl<'de, T> Deserialize<'de> for Versioned<T>
+    where
+        T: SchemaVersion + Deserialize<'de>,
+    {
+        fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+        where
+            D: Deserializer<'de>,
+        {
+            let visitor = VersionedVisitor {
+                mismatch: &Cell::new(None),
+                command: PhantomData,
+            };
+            deserializer
+                .deserialize_struct("Versioned", FIELDS, visitor)
+                .map(Versioned)
+        }
+    }
+
+    /// Deserializes a command which was serialized as a [`Versioned`], distinguishing a command
+    /// of another schema version from a corrupt payload.
+    pub fn decode_versioned<'de, T, D>(deserializer: D) -> Result<T, DecodeError<D::Error>>
+    where
+        T: SchemaVersion + Deserialize<'de>,
+        D: Deserializer<'de>,
+    {
+        let mismatch = Cell::new(None);
+        let visitor = VersionedVisitor {
+            mismatch: &mismatch,
+            command: PhantomData,
+        };
+        deserializer
+            .deserialize_struct("Versioned", FIELDS, visitor)
+            .map_err(|error| match mismatch.get() {
+                Some(found) => DecodeError::UnknownVersion {
+                    found,
+                    expected: T::SCHEMA_VERSION,
+                },
+                None => DecodeError::Corrupt(error),
+            })
+    }
+}
+
+#[cfg(feature = "serde")]
+pub use serde_impl::decode_versioned;
@@ -0,0 +1,59 @@
+#![cfg(feature = "actor")]
+
+use defunctionalize::actor::Closed;
+use defunctionalize::defunctionalize;
+
+#[derive(Default)]
+pub struct Counter {
+    count: u32,
+}
+
+#[defunctionalize(fn(counter: &mut Counter) -> u32, actor)]
+mod counting {
+    use super::Counter;
+
+    pub fn add(amount: u32, counter: &mut Counter) -> u32 {
+        counter.count += amount;
+        counter.count
+    }
+
+    pub fn get(counter: &mut Counter) -> u32 {
+        counter.count
+    }
+}
+
+#[defunctionalize(fn(counter: &mut Counter), actor = Resetter)]
+mod resetting {
+    use super::Counter;
+
+    pub fn reset(counter: &mut Counter) {
+        counter.count = 0;
+    }
+}
+
+#[tokio::test]
+async fn handle_sends_commands_to_the_actor() {
+    let (handle, mailbox) = Counting::actor(4);
+    let actor = tokio::spawn(async move {
+        let mut counter = Counter::default();
+        mailbox.run(&mut counter).await;
+        counter.count
+    });
+
+    assert_eq!(handle.add(2).await, Ok(2));
+    let other = handle.clone();
+    assert_eq!(other.add(3).await, Ok(5));
+    assert_eq!(handle.get().await, Ok(5));
+
+    drop(handle);
+    drop(other);
+    assert_eq!(actor.await.unwrap(), 5);
+}
+
+#[tokio::test]
+async fn handle_fails_once_the_actor_stops() {
+    let (handle, mailbox) = Resetting::actor(1);
+    drop(mailbox);
+    let _: &Resetter = &handle;
+    assert_eq!(handle.reset().await, Err(Closed));
+}
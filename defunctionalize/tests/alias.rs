@@ -0,0 +1,41 @@
+use defunctionalize::defunctionalize;
+use serde::{Deserialize, Serialize};
+
+#[defunctionalize(fn(x: u32) -> u32)]
+#[derive(Serialize, Deserialize, PartialEq, Debug)]
+mod operation {
+    #[defunctionalize(alias = "plus")]
+    #[defunctionalize(alias = "increase_by")]
+    pub fn add(amount: u32, x: u32) -> u32 {
+        x + amount
+    }
+}
+
+#[test]
+fn previous_names_are_accepted() {
+    // Commands are serialized by the names of their cases, so the old functions' cases are too.
+    let add: Operation = serde_json::from_str(r#"{"Plus":1}"#).unwrap();
+    assert_eq!(add, Operation::Add(1));
+    let add: Operation = serde_json::from_str(r#"{"IncreaseBy":2}"#).unwrap();
+    assert_eq!(add, Operation::Add(2));
+    assert_eq!(serde_json::to_string(&add).unwrap(), r#"{"Add":2}"#);
+}
+
+#[cfg(feature = "serde")]
+#[defunctionalize(fn(x: u32) -> u32)]
+#[derive(PartialEq, Debug)]
+mod tagged {
+    #[defunctionalize(tag = 1, alias = "plus")]
+    pub fn add(amount: u32, x: u32) -> u32 {
+        x + amount
+    }
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn previous_names_of_tagged_functions_are_accepted() {
+    let add: Tagged = serde_json::from_str(r#"{"Plus":1}"#).unwrap();
+    assert_eq!(add, Tagged::Add(1));
+    let add: Tagged = serde_json::from_str(r#"{"Add":1}"#).unwrap();
+    assert_eq!(add, Tagged::Add(1));
+}
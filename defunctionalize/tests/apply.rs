@@ -0,0 +1,39 @@
+use defunctionalize::{defunctionalize, DeFn};
+
+pub struct Config {
+    scale: u32,
+}
+
+#[defunctionalize(fn(config: &Config, x: u32, y: u32) -> u32, apply)]
+mod operation {
+    use super::Config;
+
+    pub fn add(config: &Config, x: u32, y: u32) -> u32 {
+        (x + y) * config.scale
+    }
+
+    pub fn add_plus_n(n: u32, config: &Config, x: u32, y: u32) -> u32 {
+        (x + y + n) * config.scale
+    }
+}
+
+#[defunctionalize(fn(first: u32, second: u32) -> u32, apply = Bound)]
+mod pair {
+    pub fn subtract(first: u32, second: u32) -> u32 {
+        first - second
+    }
+}
+
+#[test]
+fn binds_the_first_argument() {
+    let config = Config { scale: 2 };
+    let applied: OperationApplied<&Config> = Operation::AddPlusN(1).apply(&config);
+    assert_eq!(applied.call(2, 3), 12);
+    assert_eq!(DeFn::call(Operation::Add.apply(&config), (2, 3)), 10);
+}
+
+#[test]
+fn applied_type_can_be_named() {
+    let bound: Bound<u32> = Pair::Subtract.apply(5);
+    assert_eq!(bound.call(3), 2);
+}
@@ -0,0 +1,61 @@
+use defunctionalize::defunctionalize;
+
+pub struct Database {
+    rows: Vec<u32>,
+}
+
+#[defunctionalize(fn(db: &Database, offset: u32, limit: u32) -> Vec<u32>, args)]
+mod query {
+    use super::Database;
+
+    pub fn all(db: &Database, offset: u32, limit: u32) -> Vec<u32> {
+        db.rows
+            .iter()
+            .copied()
+            .skip(offset as usize)
+            .take(limit as usize)
+            .collect()
+    }
+
+    pub fn above(min: u32, db: &Database, offset: u32, limit: u32) -> Vec<u32> {
+        all(db, offset, limit)
+            .into_iter()
+            .filter(|row| *row > min)
+            .collect()
+    }
+}
+
+#[defunctionalize(fn<T: Clone>(value: T, count: usize) -> Vec<T>, args = Repetition)]
+mod repeat {
+    pub fn repeat<T: Clone>(value: T, count: usize) -> Vec<T> {
+        vec![value; count]
+    }
+}
+
+#[test]
+fn arguments_are_named_at_the_call() {
+    let db = Database {
+        rows: vec![1, 5, 2, 8],
+    };
+    let args = QueryArgs {
+        db: &db,
+        offset: 1,
+        limit: 2,
+    };
+    assert_eq!(Query::All.call_with(args), [5, 2]);
+    let args = QueryArgs {
+        db: &db,
+        offset: 0,
+        limit: 4,
+    };
+    assert_eq!(Query::Above(2).call_with(args), [5, 8]);
+}
+
+#[test]
+fn struct_can_be_named_and_generic() {
+    let args = Repetition {
+        value: 'a',
+        count: 3,
+    };
+    assert_eq!(Repeat::Repeat.call_with(args), ['a', 'a', 'a']);
+}
@@ -0,0 +1,49 @@
+use defunctionalize::defunctionalize;
+use serde::Deserialize;
+
+const DEFAULT_LIMIT: u32 = 100;
+
+#[defunctionalize(fn(x: u32) -> u32)]
+#[derive(Deserialize, PartialEq, Debug)]
+mod operation {
+    #[defunctionalize(default)]
+    pub fn scale(
+        factor: u32,
+        #[defunctionalize(default = 0)] offset: u32,
+        #[defunctionalize(default = DEFAULT_LIMIT)] limit: u32,
+        x: u32,
+    ) -> u32 {
+        (x * factor + offset).min(limit)
+    }
+
+    pub fn double(x: u32) -> u32 {
+        x * 2
+    }
+}
+
+// Without the `priority` option, no method is named `priority`, so a constructor may be.
+#[defunctionalize(fn(x: u32) -> u32)]
+mod task {
+    pub fn priority(level: u32, #[defunctionalize(default = 1)] boost: u32, x: u32) -> u32 {
+        x + level * boost
+    }
+}
+
+#[test]
+fn constructors_leave_out_defaulted_arguments() {
+    assert_eq!(Operation::scale(3), Operation::Scale(3, 0, 100));
+    assert_eq!(Operation::scale(3).call(50), 100);
+    assert_eq!(Operation::default(), Operation::Scale(0, 0, 100));
+    assert_eq!(Task::priority(2).call(1), 3);
+}
+
+#[test]
+fn missing_arguments_are_filled_in_when_deserializing() {
+    let command: Operation = serde_json::from_str(r#"{"Scale":[2]}"#).unwrap();
+    assert_eq!(command, Operation::Scale(2, 0, 100));
+    let command: Operation = serde_json::from_str(r#"{"Scale":[2,1]}"#).unwrap();
+    assert_eq!(command, Operation::Scale(2, 1, 100));
+    let command: Operation = serde_json::from_str(r#"{"Scale":[2,1,5]}"#).unwrap();
+    assert_eq!(command, Operation::Scale(2, 1, 5));
+    assert!(serde_json::from_str::<Operation>(r#"{"Scale":[]}"#).is_err());
+}
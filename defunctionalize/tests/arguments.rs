@@ -0,0 +1,36 @@
+use defunctionalize::defunctionalize;
+
+pub struct Context {
+    base: u32,
+}
+
+pub type Ctx = Context;
+
+#[defunctionalize(fn(ctx: &Context, total: &mut u32) -> u32)]
+mod commands {
+    use super::*;
+
+    pub fn add(n: u32, ctx: &Ctx, total: &mut u32) -> u32 {
+        *total += ctx.base + n;
+        *total
+    }
+
+    pub fn peek(ctx: &Context, total: &u32) -> u32 {
+        ctx.base + *total
+    }
+}
+
+#[test]
+fn arguments_may_be_named_by_aliases() {
+    let ctx = Context { base: 10 };
+    let mut total = 0;
+    assert_eq!(Commands::Add(1).call(&ctx, &mut total), 11);
+    assert_eq!(total, 11);
+}
+
+#[test]
+fn arguments_are_coerced_as_in_a_call() {
+    let ctx = Context { base: 10 };
+    let mut total = 5;
+    assert_eq!(Commands::Peek.call(&ctx, &mut total), 15);
+}
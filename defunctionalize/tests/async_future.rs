@@ -0,0 +1,93 @@
+use defunctionalize::{defunctionalize, DeFnAsync};
+use futures::executor::block_on;
+use std::future::Future;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+/// A future which is pending the first time it is polled, so that the futures of commands are
+/// polled more than once.
+struct YieldNow(bool);
+
+impl Future for YieldNow {
+    type Output = ();
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+        if self.0 {
+            Poll::Ready(())
+        } else {
+            self.0 = true;
+            cx.waker().wake_by_ref();
+            Poll::Pending
+        }
+    }
+}
+
+pub struct Database {
+    values: Vec<u32>,
+}
+
+#[defunctionalize(async fn(db: &Database) -> u32)]
+mod query {
+    use super::{Database, YieldNow};
+
+    pub async fn count(db: &Database) -> u32 {
+        YieldNow(false).await;
+        db.values.len() as u32
+    }
+
+    pub async fn sum_from(start: usize, db: &Database) -> u32 {
+        // The borrow of this local is held across the awaits, so the future must stay pinned.
+        let values = db.values[start..].to_vec();
+        let mut total = 0;
+        for value in &values {
+            YieldNow(false).await;
+            total += value;
+        }
+        total
+    }
+}
+
+#[test]
+fn futures_are_polled_to_completion() {
+    let db = Database {
+        values: vec![1, 2, 3, 4],
+    };
+    assert_eq!(block_on(Query::Count.call(&db)), 4);
+    assert_eq!(block_on(Query::SumFrom(1).call(&db)), 9);
+}
+
+#[test]
+fn futures_are_polled_in_place() {
+    let db = Database { values: vec![5, 6] };
+    let mut future = Box::pin(Query::SumFrom(0).call(&db));
+    let waker = futures::task::noop_waker();
+    let mut cx = Context::from_waker(&waker);
+    assert_eq!(future.as_mut().poll(&mut cx), Poll::Pending);
+    assert_eq!(future.as_mut().poll(&mut cx), Poll::Pending);
+    assert_eq!(future.as_mut().poll(&mut cx), Poll::Ready(11));
+}
+
+#[defunctionalize(async fn(x: u32) -> u32, send)]
+mod delayed {
+    pub async fn double(x: u32) -> u32 {
+        super::YieldNow(false).await;
+        x * 2
+    }
+
+    pub async fn add(amount: u32, x: u32) -> u32 {
+        x + amount
+    }
+}
+
+fn assert_send<T: Send>(value: T) -> T {
+    value
+}
+
+#[test]
+fn sendable_futures_implement_defn_async() {
+    let future = assert_send(Delayed::Double.call_async(4));
+    assert_eq!(block_on(future), 8);
+    assert_eq!(block_on(DeFnAsync::call_async(Delayed::Add(1), 4)), 5);
+    let handle = std::thread::spawn(move || block_on(Delayed::Add(2).call(3)));
+    assert_eq!(handle.join().unwrap(), 5);
+}
@@ -0,0 +1,43 @@
+use defunctionalize::{defunctionalize, AuditRecord};
+
+pub struct Ledger;
+
+#[defunctionalize(fn(ledger: &mut Ledger), audit)]
+mod command {
+    use super::Ledger;
+
+    pub fn transfer(from: String, amount: u64, ledger: &mut Ledger) {
+        let _ = (from, amount, ledger);
+    }
+
+    pub fn close(ledger: &mut Ledger) {
+        let _ = ledger;
+    }
+}
+
+#[test]
+fn commands_are_recorded_with_their_arguments() {
+    let record = Command::Transfer("alice".to_owned(), 10).audit_record();
+    assert_eq!(
+        record,
+        AuditRecord {
+            name: "transfer",
+            args: vec![
+                ("from", r#""alice""#.to_owned()),
+                ("amount", "10".to_owned())
+            ],
+        }
+    );
+    assert_eq!(record.to_string(), r#"transfer from="alice" amount=10"#);
+    assert_eq!(Command::Close.audit_record().to_string(), "close");
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn records_are_serializable() {
+    let record = Command::Transfer("bob".to_owned(), 2).audit_record();
+    assert_eq!(
+        serde_json::to_string(&record).unwrap(),
+        r#"{"name":"transfer","args":[["from","\"bob\""],["amount","2"]]}"#
+    );
+}
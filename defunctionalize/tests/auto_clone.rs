@@ -0,0 +1,37 @@
+use defunctionalize::defunctionalize;
+
+#[defunctionalize(fn(x: u32) -> u32, auto_clone)]
+mod copied {
+    pub fn add(amount: u32, x: u32) -> u32 {
+        x + amount
+    }
+
+    pub fn scale(factors: (u32, &'static u32), x: u32) -> u32 {
+        x * factors.0 * factors.1
+    }
+}
+
+#[defunctionalize(fn(x: u32) -> u32, auto_clone)]
+mod cloned {
+    pub fn add_length(text: String, x: u32) -> u32 {
+        x + text.len() as u32
+    }
+}
+
+fn is_copy<T: Copy>(_: &T) {}
+
+#[test]
+fn copy_arguments_make_the_enum_copy() {
+    let command = Copied::Add(1);
+    is_copy(&command);
+    assert_eq!(command.call(1), 2);
+    assert_eq!(command.call(2), 3);
+    assert_eq!(Copied::Scale((2, &3)).call(1), 6);
+}
+
+#[test]
+fn other_arguments_make_the_enum_clone() {
+    let command = Cloned::AddLength("four".to_owned());
+    assert_eq!(command.clone().call(1), 5);
+    assert_eq!(command.call(2), 6);
+}
@@ -0,0 +1,30 @@
+use defunctionalize::{defunctionalize, DeFn};
+use std::cell::RefCell;
+
+thread_local! {
+    static LOG: RefCell<Vec<String>> = const { RefCell::new(Vec::new()) };
+}
+
+#[defunctionalize]
+#[derive(Clone, PartialEq, Debug)]
+mod log {
+    pub fn info(message: &'static str) {
+        super::LOG.with(|log| log.borrow_mut().push(format!("info: {}", message)));
+    }
+
+    pub fn warn(message: &'static str, code: u32) {
+        super::LOG.with(|log| log.borrow_mut().push(format!("warn {}: {}", code, message)));
+    }
+}
+
+#[test]
+fn calls_are_recorded_and_run_later() {
+    let recorded = vec![Log::Info("started"), Log::Warn("slow", 3)];
+    assert_eq!(recorded[1], Log::Warn("slow", 3));
+    for call in recorded {
+        call.call();
+    }
+    DeFn::call(Log::Info("done"), ());
+    let log = LOG.with(|log| log.borrow().clone());
+    assert_eq!(log, ["info: started", "warn 3: slow", "info: done"]);
+}
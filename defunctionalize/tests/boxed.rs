@@ -0,0 +1,31 @@
+use defunctionalize::{defunctionalize, DeFn};
+
+#[defunctionalize(fn(x: u32) -> u32)]
+mod operation {
+    pub fn add(amount: u32, x: u32) -> u32 {
+        x + amount
+    }
+
+    pub fn lookup(table: [u32; 64], x: u32) -> u32 {
+        table[x as usize % 64]
+    }
+}
+
+fn call_all<D: DeFn<u32, Output = u32>>(commands: Vec<D>, x: u32) -> Vec<u32> {
+    commands
+        .into_iter()
+        .map(|command| command.call(x))
+        .collect()
+}
+
+#[test]
+fn boxed_commands_are_called_directly() {
+    let mut table = [0; 64];
+    table[3] = 9;
+    let commands = vec![
+        Box::new(Operation::Add(1)),
+        Box::new(Operation::Lookup(table)),
+    ];
+    assert_eq!(call_all(commands, 3), [4, 9]);
+    assert_eq!(DeFn::call(Box::new(Box::new(Operation::Add(2))), 1), 3);
+}
@@ -0,0 +1,29 @@
+use defunctionalize::{defunctionalize, DeFn};
+
+#[defunctionalize(fn(x: u32) -> u32)]
+mod operation {
+    pub fn add(amount: u32, x: u32) -> u32 {
+        x + amount
+    }
+
+    pub fn scale(factor: &'static u32, x: u32) -> u32 {
+        x * factor
+    }
+}
+
+static TABLE: &[Operation] = &[Operation::Add(1), Operation::Scale(&3)];
+
+fn call_all<'a, D>(commands: &'a [D], x: u32) -> Vec<u32>
+where
+    &'a D: DeFn<u32, Output = u32>,
+{
+    commands.iter().map(|command| command.call(x)).collect()
+}
+
+#[test]
+fn commands_in_tables_are_called_by_reference() {
+    assert_eq!(call_all(TABLE, 2), [3, 6]);
+    assert_eq!(DeFn::call(&TABLE[0], (4,)), 5);
+    // The table still holds every command.
+    assert_eq!(call_all(TABLE, 1), [2, 3]);
+}
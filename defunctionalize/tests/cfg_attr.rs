@@ -0,0 +1,35 @@
+use defunctionalize::defunctionalize;
+use serde::Serialize;
+
+#[defunctionalize(fn(x: u32) -> u32)]
+#[cfg_attr(all(), derive(PartialEq, Debug, Serialize))]
+#[cfg_attr(any(), derive(Eq))]
+mod arithmetic {
+    #[cfg_attr(all(), serde(rename = "plus"))]
+    pub fn add(amount: u32, x: u32) -> u32 {
+        x + amount
+    }
+
+    #[cfg_attr(any(), serde(rename = "never"))]
+    pub fn double(x: u32) -> u32 {
+        x * 2
+    }
+}
+
+#[test]
+fn module_cfg_attr_applies_to_the_enum() {
+    assert_eq!(Arithmetic::Add(1), Arithmetic::Add(1));
+    assert_eq!(format!("{:?}", Arithmetic::Double), "Double");
+}
+
+#[test]
+fn function_cfg_attr_applies_to_the_variant() {
+    assert_eq!(
+        serde_json::to_string(&Arithmetic::Add(1)).unwrap(),
+        r#"{"plus":1}"#
+    );
+    assert_eq!(
+        serde_json::to_string(&Arithmetic::Double).unwrap(),
+        r#""Double""#
+    );
+}
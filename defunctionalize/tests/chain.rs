@@ -0,0 +1,40 @@
+use defunctionalize::{defunctionalize, Chain, DeFn};
+use std::cell::RefCell;
+
+#[defunctionalize(fn(x: u32, y: u32) -> u32)]
+mod operation {
+    pub fn add(x: u32, y: u32) -> u32 {
+        x + y
+    }
+
+    pub fn mult(x: u32, y: u32) -> u32 {
+        x * y
+    }
+}
+
+#[defunctionalize(fn(log: &RefCell<Vec<&'static str>>) -> usize)]
+mod logged {
+    use std::cell::RefCell;
+
+    pub fn write(message: &'static str, log: &RefCell<Vec<&'static str>>) -> usize {
+        log.borrow_mut().push(message);
+        log.borrow().len()
+    }
+}
+
+#[test]
+fn calls_both_commands_with_the_same_input() {
+    let (first, second) = Chain(Operation::Add, Operation::Mult).call((2, 3));
+    assert_eq!((first, second), (5, 6));
+}
+
+#[test]
+fn chains_nest_and_call_in_order() {
+    let log = RefCell::new(vec![]);
+    let chain = Chain::new(
+        Logged::Write("first"),
+        Chain(Logged::Write("second"), Logged::Write("third")),
+    );
+    assert_eq!(chain.call(&log), (1, (2, 3)));
+    assert_eq!(*log.borrow(), ["first", "second", "third"]);
+}
@@ -0,0 +1,59 @@
+use defunctionalize::{defunctionalize, DeFn};
+
+#[defunctionalize(fn(x: i32) -> i32, chunked = 2)]
+mod operation {
+    pub fn add(amount: i32, x: i32) -> i32 {
+        x + amount
+    }
+
+    pub fn sub(amount: i32, x: i32) -> i32 {
+        x - amount
+    }
+
+    pub fn mult(factor: i32, x: i32) -> i32 {
+        x * factor
+    }
+
+    pub fn div(divisor: i32, x: i32) -> i32 {
+        x / divisor
+    }
+
+    pub fn neg(x: i32) -> i32 {
+        -x
+    }
+}
+
+#[test]
+fn every_chunk_is_dispatched() {
+    let commands = vec![
+        Operation::Add(2),
+        Operation::Sub(2),
+        Operation::Mult(2),
+        Operation::Div(2),
+        Operation::Neg,
+    ];
+    let results: Vec<_> = commands.iter().map(|command| command.call(10)).collect();
+    assert_eq!(results, [12, 8, 20, 5, -10]);
+    let results: Vec<_> = commands
+        .into_iter()
+        .map(|command| command.call(10))
+        .collect();
+    assert_eq!(results, [12, 8, 20, 5, -10]);
+}
+
+#[defunctionalize(fn(x: i32) -> i32, chunked)]
+mod offset {
+    pub fn left(amount: i32, x: i32) -> i32 {
+        x - amount
+    }
+
+    pub fn right(amount: i32, x: i32) -> i32 {
+        x + amount
+    }
+}
+
+#[test]
+fn default_chunk_size_holds_small_modules() {
+    assert_eq!(Offset::Left(1).call(0), -1);
+    assert_eq!(DeFn::call(Offset::Right(1), (0,)), 1);
+}
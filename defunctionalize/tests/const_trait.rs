@@ -0,0 +1,43 @@
+#![cfg(feature = "const-trait")]
+#![cfg_attr(feature = "const-trait", feature(const_trait_impl))]
+
+use defunctionalize::{defunctionalize, DeFn};
+
+#[defunctionalize(fn(x: u32) -> u32)]
+mod operation {
+    pub const fn add(amount: u32, x: u32) -> u32 {
+        x + amount
+    }
+
+    pub const fn double(x: u32) -> u32 {
+        x * 2
+    }
+}
+
+const ADDED: u32 = Operation::Add(2).call(3);
+const DOUBLED: u32 = DeFn::call(Operation::Double, (4,));
+
+#[test]
+fn commands_are_called_in_constant_contexts() {
+    assert_eq!(ADDED, 5);
+    assert_eq!(DOUBLED, 8);
+    const BOTH: u32 = Operation::Double.call(Operation::Add(1).call(2));
+    assert_eq!(BOTH, 6);
+}
+
+#[defunctionalize(fn(x: u32) -> u32)]
+mod mixed {
+    pub const fn double(x: u32) -> u32 {
+        x * 2
+    }
+
+    pub fn halve(x: u32) -> u32 {
+        x / 2
+    }
+}
+
+#[test]
+fn modules_with_other_functions_are_called_at_runtime() {
+    assert_eq!(Mixed::Double.call(2), 4);
+    assert_eq!(Mixed::Halve.call(4), 2);
+}
@@ -0,0 +1,53 @@
+use defunctionalize::defunctionalize;
+
+#[defunctionalize(fn(x: u32) -> u32)]
+mod operation {
+    #[defunctionalize(cost = 5)]
+    pub fn expensive(x: u32) -> u32 {
+        x.pow(3)
+    }
+
+    #[defunctionalize(cost = 0)]
+    pub fn free(x: u32) -> u32 {
+        x
+    }
+
+    pub fn add(amount: u32, x: u32) -> u32 {
+        x + amount
+    }
+}
+
+/// Splits the commands into batches whose total weight is at most `budget`.
+fn batches(commands: Vec<Operation>, budget: u32) -> Vec<Vec<Operation>> {
+    let mut batches: Vec<Vec<Operation>> = vec![];
+    let mut remaining = 0;
+    for command in commands {
+        if command.weight() > remaining || batches.is_empty() {
+            batches.push(vec![]);
+            remaining = budget;
+        }
+        remaining = remaining.saturating_sub(command.weight());
+        batches.last_mut().unwrap().push(command);
+    }
+    batches
+}
+
+#[test]
+fn commands_are_weighed_by_their_cost() {
+    assert_eq!(Operation::Expensive.weight(), 5);
+    assert_eq!(Operation::Free.weight(), 0);
+    assert_eq!(Operation::Add(1).weight(), 1);
+}
+
+#[test]
+fn commands_are_packed_by_weight() {
+    let commands = vec![
+        Operation::Add(1),
+        Operation::Expensive,
+        Operation::Free,
+        Operation::Add(2),
+        Operation::Expensive,
+    ];
+    let sizes: Vec<_> = batches(commands, 6).iter().map(Vec::len).collect();
+    assert_eq!(sizes, [3, 2]);
+}
@@ -0,0 +1,33 @@
+use defunctionalize::defunctionalize;
+
+#[defunctionalize(fn(x: u32, y: u32, z: u32) -> u32, curry)]
+mod operation {
+    pub fn sum(x: u32, y: u32, z: u32) -> u32 {
+        x + y + z
+    }
+
+    pub fn weighted(weight: u32, x: u32, y: u32, z: u32) -> u32 {
+        (x + y + z) * weight
+    }
+}
+
+#[defunctionalize(fn(x: u32, y: String) -> String, curry)]
+mod pair {
+    pub fn repeat(x: u32, y: String) -> String {
+        y.repeat(x as usize)
+    }
+}
+
+#[test]
+fn takes_one_argument_at_a_time() {
+    assert_eq!(Operation::Sum.call_curried()(1)(2)(3), 6);
+    assert_eq!(Operation::Weighted(2).call_curried()(1)(2)(3), 12);
+}
+
+#[test]
+fn closures_can_be_kept_between_arguments() {
+    let add_to_one = Operation::Sum.call_curried()(1);
+    let add_to_three = add_to_one(2);
+    assert_eq!(add_to_three(4), 7);
+    assert_eq!(Pair::Repeat.call_curried()(2)("ab".to_owned()), "abab");
+}
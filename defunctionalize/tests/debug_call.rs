@@ -0,0 +1,34 @@
+use defunctionalize::defunctionalize;
+
+#[defunctionalize(fn(x: i32) -> i32, debug_call)]
+#[derive(Debug, Clone)]
+mod operation {
+    pub fn multiply_and_add(coefficient: i32, constant: i32, x: i32) -> i32 {
+        x * coefficient + constant
+    }
+
+    pub fn login(user: String, #[defunctionalize(redact)] password: String, x: i32) -> i32 {
+        let _ = (user, password);
+        x
+    }
+
+    pub fn negate(x: i32) -> i32 {
+        -x
+    }
+}
+
+#[test]
+fn commands_are_shown_as_calls() {
+    assert_eq!(
+        format!("{:?}", Operation::MultiplyAndAdd(3, 4)),
+        "multiply_and_add(coefficient: 3, constant: 4)"
+    );
+    assert_eq!(format!("{:?}", Operation::Negate), "negate()");
+    assert_eq!(
+        format!(
+            "{:?}",
+            Operation::Login("alice".to_owned(), "hunter2".to_owned())
+        ),
+        r#"login(user: "alice", password: <redacted>)"#
+    );
+}
@@ -0,0 +1,20 @@
+use defunctionalize::defunctionalize;
+use std::fs;
+use std::path::Path;
+
+#[defunctionalize(fn(x: u32) -> u32, debug_expand = "../target/defunctionalize-debug-expand")]
+mod operation {
+    pub fn add(amount: u32, x: u32) -> u32 {
+        x + amount
+    }
+}
+
+#[test]
+fn expanded_code_is_written_to_a_file() {
+    let path = Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("../target/defunctionalize-debug-expand/Operation.rs");
+    let expanded = fs::read_to_string(path).unwrap();
+    assert!(expanded.contains("enum Operation"));
+    assert!(expanded.contains("fn call"));
+    assert_eq!(Operation::Add(1).call(2), 3);
+}
@@ -0,0 +1,26 @@
+use defunctionalize::defunctionalize;
+
+#[defunctionalize(fn(x: u32) -> u32)]
+#[derive(PartialEq, Debug)]
+mod operation {
+    pub fn double(x: u32) -> u32 {
+        x * 2
+    }
+
+    #[defunctionalize(default)]
+    pub fn add(amount: u32, label: String, x: u32) -> u32 {
+        let _ = label;
+        x + amount
+    }
+}
+
+#[derive(Default)]
+struct Settings {
+    on_change: Operation,
+}
+
+#[test]
+fn default_function_is_the_default_command() {
+    assert_eq!(Operation::default(), Operation::Add(0, String::new()));
+    assert_eq!(Settings::default().on_change.call(3), 3);
+}
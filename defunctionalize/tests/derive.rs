@@ -0,0 +1,40 @@
+use defunctionalize::DeFn;
+
+mod operation {
+    pub fn add(x: u32, y: u32) -> u32 {
+        x + y
+    }
+
+    pub fn add_plus_n(n: u32, x: u32, y: u32) -> u32 {
+        x + y + n
+    }
+
+    pub fn scale(factor: u32, offset: u32, x: u32, y: u32) -> u32 {
+        (x + y) * factor + offset
+    }
+}
+
+#[derive(DeFn)]
+#[defn(fn(x: u32, y: u32) -> u32)]
+pub enum Operation {
+    #[defn(call = operation::add)]
+    Add,
+    #[defn(call = operation::add_plus_n)]
+    AddPlusN(u32),
+    #[defn(call = operation::scale)]
+    Scale { factor: u32, offset: u32 },
+}
+
+#[test]
+fn calls_the_function_of_each_variant_with_its_fields() {
+    assert_eq!(Operation::Add.call(2, 3), 5);
+    assert_eq!(DeFn::call(Operation::AddPlusN(1), (2, 3)), 6);
+    assert_eq!(
+        Operation::Scale {
+            factor: 2,
+            offset: 1
+        }
+        .call(2, 3),
+        11
+    );
+}
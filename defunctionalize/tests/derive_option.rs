@@ -0,0 +1,29 @@
+use defunctionalize::defunctionalize;
+
+#[defunctionalize(fn(x: u32) -> u32, derive(Clone, PartialEq, Debug))]
+mod arithmetic {
+    pub fn add(amount: u32, x: u32) -> u32 {
+        x + amount
+    }
+}
+
+#[defunctionalize(fn(x: u32) -> u32, derive(Clone))]
+#[derive(PartialEq, Debug)]
+mod combined {
+    pub fn add(amount: u32, x: u32) -> u32 {
+        x + amount
+    }
+}
+
+#[test]
+fn derives_the_listed_traits() {
+    let command = Arithmetic::Add(1);
+    assert_eq!(command.clone(), command);
+    assert_eq!(format!("{:?}", command), "Add(1)");
+}
+
+#[test]
+fn derives_with_the_module_derive_attribute() {
+    let command = Combined::Add(1);
+    assert_eq!(command.clone(), command);
+}
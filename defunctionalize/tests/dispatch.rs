@@ -0,0 +1,44 @@
+use defunctionalize::defunctionalize;
+
+pub trait Handler {
+    fn handle(self, x: u32) -> u32;
+}
+
+pub trait Describe {
+    fn apply(&self, x: u32) -> u32;
+}
+
+#[defunctionalize(
+    fn(x: u32) -> u32,
+    dispatch = Handler::handle,
+    dispatch = Describe::apply(&self)
+)]
+mod arithmetic {
+    pub fn add(amount: u32, x: u32) -> u32 {
+        x + amount
+    }
+
+    pub fn append(text: String, x: u32) -> u32 {
+        x + text.len() as u32
+    }
+}
+
+#[test]
+fn forwards_the_trait_method_to_each_function() {
+    assert_eq!(Arithmetic::Add(2).handle(1), 3);
+    assert_eq!(Arithmetic::Append("ab".to_owned()).handle(1), 3);
+}
+
+#[test]
+fn borrowing_methods_clone_the_extra_arguments() {
+    let commands: Vec<Box<dyn Describe>> = vec![
+        Box::new(Arithmetic::Add(2)),
+        Box::new(Arithmetic::Append("abc".to_owned())),
+    ];
+    let outputs = commands
+        .iter()
+        .map(|command| command.apply(1))
+        .collect::<Vec<_>>();
+    assert_eq!(outputs, [3, 4]);
+    assert_eq!(commands[0].apply(2), 4);
+}
@@ -0,0 +1,47 @@
+use defunctionalize::{defunctionalize, dispatch_all, dispatch_all_mut};
+
+#[defunctionalize(fn(x: u32) -> u32)]
+mod operation {
+    pub fn add(amount: u32, x: u32) -> u32 {
+        x + amount
+    }
+
+    pub fn double(x: u32) -> u32 {
+        x * 2
+    }
+}
+
+#[defunctionalize(fn(balance: &mut i64) -> i64)]
+mod transaction {
+    pub fn deposit(amount: i64, balance: &mut i64) -> i64 {
+        *balance += amount;
+        *balance
+    }
+
+    pub fn withdraw(amount: i64, balance: &mut i64) -> i64 {
+        *balance -= amount;
+        *balance
+    }
+}
+
+#[test]
+fn commands_are_called_with_the_same_arguments() {
+    let outputs = dispatch_all(
+        vec![Operation::Add(1), Operation::Double, Operation::Add(3)],
+        5,
+    );
+    assert_eq!(outputs, [6, 10, 8]);
+}
+
+#[test]
+fn commands_see_the_changes_of_those_before_them() {
+    let mut balance = 10;
+    let transactions = vec![
+        Transaction::Deposit(5),
+        Transaction::Withdraw(12),
+        Transaction::Deposit(1),
+    ];
+    assert_eq!(dispatch_all_mut(transactions, &mut balance), [15, 3, 4]);
+    assert_eq!(balance, 4);
+    assert!(dispatch_all_mut(Vec::<Transaction>::new(), &mut balance).is_empty());
+}
@@ -0,0 +1,58 @@
+use defunctionalize::{defunctionalize, DeFn, Registry};
+
+#[defunctionalize(fn(x: u32, y: u32) -> u32, auto_clone, reflect)]
+mod operation {
+    pub fn add(x: u32, y: u32) -> u32 {
+        x + y
+    }
+
+    pub fn mult(x: u32, y: u32) -> u32 {
+        x * y
+    }
+}
+
+#[test]
+fn dispatches_commands_by_name() {
+    let mut registry = Registry::new();
+    for command in [Operation::Add, Operation::Mult].iter() {
+        registry.register(command.name(), *command);
+    }
+    assert_eq!(registry.dispatch("mult", (2, 3)), Some(6));
+    assert_eq!(registry.dispatch("add", (2, 3)), Some(5));
+    assert_eq!(registry.dispatch("sub", (2, 3)), None);
+}
+
+#[derive(Clone)]
+struct Offset(u32);
+
+impl DeFn<(u32, u32)> for Offset {
+    type Output = u32;
+
+    fn call(self, (x, y): (u32, u32)) -> u32 {
+        x + y + self.0
+    }
+}
+
+#[test]
+fn holds_commands_of_different_types() {
+    let mut registry = Registry::default();
+    registry.register("add", Operation::Add);
+    registry.register("offset", Offset(10));
+    assert_eq!(registry.len(), 2);
+    assert_eq!(registry.dispatch("offset", (1, 2)), Some(13));
+    assert_eq!(
+        registry.iter().map(|(name, ..)| name).collect::<Vec<_>>(),
+        ["add", "offset"]
+    );
+}
+
+#[test]
+fn replaces_and_removes_commands() {
+    let mut registry = Registry::new();
+    assert!(registry.register("op", Operation::Add).is_none());
+    assert!(registry.register("op", Operation::Mult).is_some());
+    assert_eq!(registry.get("op").unwrap().call_dyn((2, 3)), 6);
+    assert!(registry.remove("op").is_some());
+    assert!(!registry.contains("op"));
+    assert!(registry.is_empty());
+}
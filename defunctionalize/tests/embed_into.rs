@@ -0,0 +1,69 @@
+use defunctionalize::defunctionalize;
+use std::convert::TryFrom;
+
+#[defunctionalize(fn(x: i32) -> i32, embed_into = Command, embed_into = self::other::Wide::Math)]
+#[derive(PartialEq, Debug)]
+mod operation {
+    pub fn add(amount: i32, x: i32) -> i32 {
+        x + amount
+    }
+}
+
+#[defunctionalize(fn(x: i32) -> i32, embed_into = Command)]
+#[derive(PartialEq, Debug)]
+mod comparison {
+    pub fn max(other: i32, x: i32) -> i32 {
+        x.max(other)
+    }
+}
+
+#[defunctionalize(
+    fn(x: i32) -> i32,
+    extra(
+        Operation(Operation) => |operation, x| operation.call(x),
+        Comparison(Comparison) => |comparison, x| comparison.call(x),
+    ),
+)]
+#[derive(PartialEq, Debug)]
+mod command {
+    pub fn negate(x: i32) -> i32 {
+        -x
+    }
+}
+
+mod other {
+    #[derive(PartialEq, Debug)]
+    pub enum Wide {
+        Math(super::Operation),
+        Nothing,
+    }
+}
+
+#[test]
+fn commands_are_embedded_into_wider_enums() {
+    let command = Command::from(Operation::Add(2));
+    assert_eq!(command, Command::Operation(Operation::Add(2)));
+    assert_eq!(command.call(1), 3);
+    assert_eq!(Command::from(Comparison::Max(5)).call(1), 5);
+    assert_eq!(
+        other::Wide::from(Operation::Add(1)),
+        other::Wide::Math(Operation::Add(1))
+    );
+}
+
+#[test]
+fn commands_are_taken_back_out_of_wider_enums() {
+    assert_eq!(
+        Operation::try_from(Command::Operation(Operation::Add(2))),
+        Ok(Operation::Add(2))
+    );
+    assert_eq!(Operation::try_from(Command::Negate), Err(Command::Negate));
+    assert_eq!(
+        Comparison::try_from(Command::Operation(Operation::Add(2))),
+        Err(Command::Operation(Operation::Add(2))),
+    );
+    assert_eq!(
+        Operation::try_from(other::Wide::Nothing),
+        Err(other::Wide::Nothing)
+    );
+}
@@ -0,0 +1,66 @@
+use defunctionalize::{defunctionalize, DeFn, DeFnAsync, Prioritized, PriorityKey};
+use futures::executor::block_on;
+
+pub trait Handler {
+    fn handle(self, x: u32) -> u32;
+}
+
+#[defunctionalize(
+    fn(x: u32) -> u32,
+    empty = allow,
+    auto_clone,
+    chunked = 2,
+    dispatch = Handler::handle,
+    reflect,
+)]
+#[derive(PartialEq, Eq, Hash, Debug, serde::Serialize, serde::Deserialize)]
+mod nothing {}
+
+// Every function is configured out.
+#[defunctionalize(fn(x: u32) -> u32, auto_clone)]
+#[derive(PartialEq, Debug)]
+mod configured_out {
+    #[cfg(any())]
+    pub fn double(x: u32) -> u32 {
+        x * 2
+    }
+
+    #[cfg(any())]
+    pub fn add(amount: u32, x: u32) -> u32 {
+        x + amount
+    }
+}
+
+#[defunctionalize(fn(log: &mut Vec<String>), empty = allow)]
+mod no_tasks {
+    #[cfg(any())]
+    #[defunctionalize(priority = 1)]
+    pub fn alert(log: &mut Vec<String>) {
+        log.push("alert".to_owned());
+    }
+}
+
+#[defunctionalize(async fn(x: u32) -> u32, empty = allow, send)]
+mod no_futures {}
+
+fn call<D: DeFn<u32, Output = u32>>(command: D) -> u32 {
+    command.call(1)
+}
+
+#[test]
+fn enums_without_cases_have_no_values() {
+    assert_eq!(std::mem::size_of::<Nothing>(), 0);
+    assert_eq!(std::mem::size_of::<ConfiguredOut>(), 0);
+    assert!(Nothing::SCHEMA.is_empty());
+    assert!(serde_json::from_str::<Nothing>(r#""Double""#).is_err());
+}
+
+#[test]
+fn enums_without_cases_are_still_callable() {
+    // Nothing can be called, but the code which would call them compiles.
+    let _: fn(Nothing) -> u32 = call;
+    let _: fn(ConfiguredOut) -> u32 = call;
+    let _: fn(Nothing, u32) -> u32 = Handler::handle;
+    let _: fn(&NoTasks) -> PriorityKey = Prioritized::priority_key;
+    let _: fn(NoFutures) -> u32 = |command| block_on(command.call_async(1));
+}
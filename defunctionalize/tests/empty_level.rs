@@ -0,0 +1,18 @@
+#![deny(warnings)]
+
+use defunctionalize::defunctionalize;
+
+#[defunctionalize(fn(x: u32) -> u32, empty = allow)]
+mod operation {
+    #[allow(dead_code)]
+    fn double(x: u32) -> u32 {
+        x * 2
+    }
+}
+
+#[test]
+fn allowed_empty_modules_compile_without_warnings() {
+    let call: fn(Operation, u32) -> u32 = |command, x| command.call(x);
+    let _ = call;
+    assert_eq!(std::mem::size_of::<Operation>(), 0);
+}
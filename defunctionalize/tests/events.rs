@@ -0,0 +1,90 @@
+use defunctionalize::{defunctionalize, fold_events};
+
+#[derive(Default, PartialEq, Debug)]
+pub struct Account {
+    balance: i64,
+    closed: bool,
+}
+
+#[defunctionalize(fn(account: &mut Account), events)]
+mod account_event {
+    use super::Account;
+
+    pub fn deposited(amount: i64, account: &mut Account) {
+        account.balance += amount;
+    }
+
+    pub fn withdrew(amount: i64, account: &mut Account) {
+        account.balance -= amount;
+    }
+
+    pub fn closed(account: &mut Account) {
+        account.closed = true;
+    }
+}
+
+// Without `events`, nothing named `apply` is generated, so the enum may have its own.
+#[defunctionalize(fn(account: &mut Account))]
+mod audit_event {
+    use super::Account;
+
+    pub fn reviewed(account: &mut Account) {
+        account.closed = account.balance < 0;
+    }
+}
+
+impl AuditEvent {
+    pub fn apply(self, accounts: &mut [Account]) {
+        for account in accounts {
+            self.call(account);
+        }
+    }
+}
+
+#[test]
+fn events_are_applied_to_the_state() {
+    let mut account = Account::default();
+    AccountEvent::Deposited(10).apply(&mut account);
+    AccountEvent::Withdrew(3).apply(&mut account);
+    assert_eq!(account.balance, 7);
+}
+
+#[test]
+fn states_are_rebuilt_from_their_events() {
+    let events = vec![
+        AccountEvent::Deposited(10),
+        AccountEvent::Withdrew(4),
+        AccountEvent::Deposited(1),
+        AccountEvent::Closed,
+    ];
+    let account = fold_events(Account::default(), events);
+    assert_eq!(
+        account,
+        Account {
+            balance: 7,
+            closed: true
+        }
+    );
+    assert_eq!(
+        fold_events(Account::default(), Vec::<AccountEvent>::new()),
+        Account::default()
+    );
+}
+
+#[test]
+fn apply_is_left_to_the_enum_without_events() {
+    let mut accounts = [
+        Account {
+            balance: -1,
+            closed: false,
+        },
+        Account::default(),
+    ];
+    AuditEvent::Reviewed.apply(&mut accounts);
+    assert!(accounts[0].closed);
+    assert!(!accounts[1].closed);
+    assert_eq!(
+        fold_events(Account::default(), [AuditEvent::Reviewed]),
+        Account::default()
+    );
+}
@@ -0,0 +1,44 @@
+use defunctionalize::defunctionalize;
+use defunctionalize::executor::{spawn_dispatch, Canceled, Job, NewThread};
+use std::cell::RefCell;
+
+#[defunctionalize(fn(x: u64) -> u64)]
+mod operation {
+    pub fn add(amount: u64, x: u64) -> u64 {
+        x + amount
+    }
+
+    pub fn fail(x: u64) -> u64 {
+        panic!("failed on {}", x)
+    }
+}
+
+#[test]
+fn commands_are_called_on_new_threads() {
+    let handles: Vec<_> = (0..4)
+        .map(|amount| spawn_dispatch(&NewThread, Operation::Add(amount), 10))
+        .collect();
+    let outputs: Vec<_> = handles.into_iter().map(|handle| handle.join()).collect();
+    assert_eq!(outputs, [Ok(10), Ok(11), Ok(12), Ok(13)]);
+}
+
+#[test]
+fn panicking_commands_are_canceled() {
+    let handle = spawn_dispatch(&NewThread, Operation::Fail, 1);
+    assert_eq!(handle.join(), Err(Canceled));
+}
+
+#[test]
+fn functions_are_executors() {
+    let jobs = RefCell::new(Vec::<Job>::new());
+    let executor = |job| jobs.borrow_mut().push(job);
+    let handle = spawn_dispatch(&executor, Operation::Add(1), 2);
+    let handle = handle.try_join().unwrap_err();
+    for job in jobs.borrow_mut().drain(..) {
+        job();
+    }
+    assert_eq!(handle.try_join().unwrap(), Ok(3));
+
+    let handle = spawn_dispatch(&|_| {}, Operation::Add(1), 2);
+    assert_eq!(handle.join(), Err(Canceled));
+}
@@ -0,0 +1,46 @@
+use defunctionalize::defunctionalize;
+
+macro_rules! scalers {
+    ($($name:ident => $factor:expr),*) => {
+        $(
+            pub fn $name(x: u32) -> u32 {
+                x * $factor
+            }
+        )*
+    };
+}
+
+#[defunctionalize(
+    fn(x: u32) -> u32,
+    expect(
+        double: (x: u32) -> u32,
+        triple: (x: u32) -> u32,
+        offset: (amount: u32, x: u32) -> u32,
+    ),
+)]
+#[derive(PartialEq, Debug)]
+mod operation {
+    scalers!(double => 2, triple => 3);
+
+    macro_rules! offset {
+        () => {
+            pub fn offset(amount: u32, x: u32) -> u32 {
+                x + amount
+            }
+        };
+    }
+
+    offset!();
+
+    pub fn negate(x: u32) -> u32 {
+        !x
+    }
+}
+
+#[test]
+fn macro_generated_functions_become_cases() {
+    assert_eq!(Operation::Double.call(4), 8);
+    assert_eq!(Operation::Triple.call(4), 12);
+    assert_eq!(Operation::Offset(1).call(4), 5);
+    assert_eq!(Operation::Negate.call(0), u32::MAX);
+}
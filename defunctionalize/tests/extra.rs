@@ -0,0 +1,30 @@
+use defunctionalize::{defunctionalize, DeFn};
+
+pub struct Ctx {
+    base: u32,
+}
+
+#[defunctionalize(
+    fn(ctx: &Ctx) -> u32,
+    extra(
+        /// A command decided at runtime.
+        Custom(Box<dyn FnOnce(&Ctx) -> u32 + Send>) => |f, ctx| f(ctx),
+        Offset(u32, bool) => |amount, negative, ctx| if negative { ctx.base - amount } else { ctx.base + amount },
+    ),
+)]
+mod command {
+    use super::Ctx;
+
+    pub fn base(ctx: &Ctx) -> u32 {
+        ctx.base
+    }
+}
+
+#[test]
+fn extra_cases_are_called_by_their_closures() {
+    let ctx = Ctx { base: 10 };
+    assert_eq!(Command::Base.call(&ctx), 10);
+    assert_eq!(Command::Custom(Box::new(|ctx| ctx.base * 3)).call(&ctx), 30);
+    assert_eq!(Command::Offset(4, true).call(&ctx), 6);
+    assert_eq!(DeFn::call(Command::Offset(4, false), &ctx), 14);
+}
@@ -0,0 +1,44 @@
+use defunctionalize::{defunctionalize, DeFn, DeFnExt, Fallback};
+use std::collections::HashMap;
+
+pub type Cache = HashMap<&'static str, u32>;
+
+#[defunctionalize(fn(key: &'static str) -> Result<u32, String>)]
+#[derive(Clone)]
+mod lookup {
+    use super::Cache;
+
+    pub fn cached(cache: Cache, key: &'static str) -> Result<u32, String> {
+        cache
+            .get(key)
+            .copied()
+            .ok_or_else(|| format!("`{}` is not cached", key))
+    }
+
+    pub fn computed(key: &'static str) -> Result<u32, String> {
+        if key.is_empty() {
+            Err("empty key".to_owned())
+        } else {
+            Ok(key.len() as u32)
+        }
+    }
+}
+
+fn cache() -> Cache {
+    let mut cache = Cache::new();
+    cache.insert("answer", 42);
+    cache
+}
+
+#[test]
+fn fallback_is_not_called_on_success() {
+    let command = Lookup::Cached(cache()).or_else(Lookup::Computed);
+    assert_eq!(command.call("answer"), Ok(42));
+}
+
+#[test]
+fn fallback_is_called_with_the_same_input_on_failure() {
+    let command = Fallback::new(Lookup::Cached(cache()), Lookup::Computed);
+    assert_eq!(command.clone().call("question"), Ok(8));
+    assert_eq!(command.call(""), Err("empty key".to_owned()));
+}
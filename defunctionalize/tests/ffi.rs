@@ -0,0 +1,59 @@
+#![cfg(feature = "ffi")]
+
+use defunctionalize::defunctionalize;
+use std::ptr;
+
+#[defunctionalize(fn(x: u32) -> u32, ffi)]
+mod arithmetic {
+    #[defunctionalize(tag = 1)]
+    pub fn add_both(a: u32, b: u8, x: u32) -> u32 {
+        x + a + b as u32
+    }
+
+    #[defunctionalize(tag = 2)]
+    pub fn double(x: u32) -> u32 {
+        x * 2
+    }
+}
+
+#[defunctionalize(fn(total: *mut u32), ffi = record)]
+mod recording {
+    #[defunctionalize(tag = 5)]
+    pub fn store(value: u32, total: *mut u32) {
+        unsafe { *total = value };
+    }
+}
+
+#[test]
+fn calls_commands_by_tag_with_an_encoded_payload() {
+    let payload = postcard::to_allocvec(&(3u32, 4u8)).unwrap();
+    let mut output = 0;
+    let status = unsafe { arithmetic_dispatch(1, payload.as_ptr(), payload.len(), 2, &mut output) };
+    assert_eq!((status, output), (0, 9));
+
+    let status = unsafe { arithmetic_dispatch(2, ptr::null(), 0, 5, &mut output) };
+    assert_eq!((status, output), (0, 10));
+
+    // The output may be discarded.
+    let status = unsafe { arithmetic_dispatch(2, ptr::null(), 0, 5, ptr::null_mut()) };
+    assert_eq!(status, 0);
+}
+
+#[test]
+fn reports_unknown_tags_and_bad_payloads() {
+    let mut output = 7;
+    let status = unsafe { arithmetic_dispatch(9, ptr::null(), 0, 5, &mut output) };
+    assert_eq!((status, output), (1, 7));
+
+    let payload = [1];
+    let status = unsafe { arithmetic_dispatch(1, payload.as_ptr(), payload.len(), 5, &mut output) };
+    assert_eq!((status, output), (2, 7));
+}
+
+#[test]
+fn function_can_be_named_and_return_nothing() {
+    let payload = postcard::to_allocvec(&(42u32,)).unwrap();
+    let mut total = 0;
+    let status = unsafe { record(5, payload.as_ptr(), payload.len(), &mut total) };
+    assert_eq!((status, total), (0, 42));
+}
@@ -0,0 +1,81 @@
+use defunctionalize::{defunctionalize, DeFn};
+use std::collections::HashSet;
+
+#[defunctionalize(fn(x: u32) -> u32)]
+mod arithmetic {
+    pub fn double(x: u32) -> u32 {
+        x * 2
+    }
+
+    pub fn square(x: u32) -> u32 {
+        x * x
+    }
+}
+
+#[defunctionalize(fn(x: u32) -> u32)]
+#[derive(Clone, Copy, PartialEq, Debug)]
+mod derived {
+    pub fn increment(x: u32) -> u32 {
+        x + 1
+    }
+}
+
+pub struct Counter {
+    count: u32,
+}
+
+#[defunctionalize(fn(counter: &mut Counter) -> u32)]
+mod counting {
+    use super::Counter;
+
+    pub fn increment(counter: &mut Counter) -> u32 {
+        counter.count += 1;
+        counter.count
+    }
+
+    // The `&mut Counter` is coerced to `&Counter`, as it would be by any call.
+    pub fn read(counter: &Counter) -> u32 {
+        counter.count
+    }
+}
+
+#[test]
+fn fieldless_enum_is_copy_eq_and_hash() {
+    let command = Arithmetic::Double;
+    let copy = command;
+    assert!(command == copy);
+    let set = Arithmetic::variants()
+        .iter()
+        .copied()
+        .collect::<HashSet<_>>();
+    assert_eq!(set.len(), 2);
+    assert!(set.contains(&Arithmetic::Square));
+}
+
+#[test]
+fn fieldless_enum_dispatches_through_lookup_table() {
+    assert_eq!(Arithmetic::COUNT, 2);
+    assert_eq!(Arithmetic::Square.variant_index(), 1);
+    assert_eq!(Arithmetic::LOOKUP[Arithmetic::Square.variant_index()](3), 9);
+    assert_eq!(Arithmetic::Double.call(3), 6);
+    assert_eq!(DeFn::call(Arithmetic::Square, 4), 16);
+}
+
+#[test]
+fn derives_on_the_module_are_not_repeated() {
+    let command = Derived::Increment;
+    assert_eq!(command, command.clone());
+    assert_eq!(format!("{:?}", command), "Increment");
+    assert_eq!(command.call(1), 2);
+}
+
+#[test]
+fn lookup_table_coerces_arguments() {
+    let mut counter = Counter { count: 0 };
+    assert_eq!(Counting::Increment.call(&mut counter), 1);
+    assert_eq!(Counting::Read.call(&mut counter), 1);
+    assert_eq!(
+        Counting::LOOKUP[Counting::Read.variant_index()](&mut counter),
+        1
+    );
+}
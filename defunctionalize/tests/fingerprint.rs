@@ -0,0 +1,79 @@
+use defunctionalize::defunctionalize;
+
+#[defunctionalize(fn(x: u32) -> u32, reflect)]
+mod original {
+    /// Adds to the number.
+    pub fn add(amount: u32, x: u32) -> u32 {
+        x + amount
+    }
+
+    pub fn double(x: u32) -> u32 {
+        x * 2
+    }
+}
+
+// The same functions, with their arguments renamed and their documentation changed.
+#[defunctionalize(fn(x: u32) -> u32, reflect)]
+mod renamed_arguments {
+    /// Increases the number.
+    pub fn add(increase: u32, x: u32) -> u32 {
+        x + increase
+    }
+
+    pub fn double(y: u32) -> u32 {
+        y + y
+    }
+}
+
+#[defunctionalize(fn(x: u32) -> u32, reflect)]
+mod reordered {
+    pub fn double(x: u32) -> u32 {
+        x * 2
+    }
+
+    pub fn add(amount: u32, x: u32) -> u32 {
+        x + amount
+    }
+}
+
+#[defunctionalize(fn(x: u32) -> u32, reflect)]
+mod retyped {
+    pub fn add(amount: u64, x: u32) -> u32 {
+        x + amount as u32
+    }
+
+    pub fn double(x: u32) -> u32 {
+        x * 2
+    }
+}
+
+#[defunctionalize(fn(x: u32) -> u32, reflect)]
+mod renamed_function {
+    pub fn plus(amount: u32, x: u32) -> u32 {
+        x + amount
+    }
+
+    pub fn double(x: u32) -> u32 {
+        x * 2
+    }
+}
+
+#[test]
+fn fingerprint_ignores_argument_names_and_documentation() {
+    assert_eq!(Original::FINGERPRINT, RenamedArguments::FINGERPRINT);
+}
+
+#[test]
+fn fingerprint_changes_with_the_serialized_form() {
+    assert_ne!(Original::FINGERPRINT, Reordered::FINGERPRINT);
+    assert_ne!(Original::FINGERPRINT, Retyped::FINGERPRINT);
+    assert_ne!(Original::FINGERPRINT, RenamedFunction::FINGERPRINT);
+}
+
+#[test]
+fn fingerprint_is_stable_across_builds() {
+    // Fingerprints are compared between processes, which may have been built by different
+    // compilers, so the value must not depend on anything but the commands.
+    const FINGERPRINT: u64 = Original::FINGERPRINT;
+    assert_eq!(FINGERPRINT, 9_515_249_723_153_714_768);
+}
@@ -0,0 +1,220 @@
+//! The generated code refers to everything by its full path, so it compiles in scopes which do
+//! not have the prelude, or which shadow the names it uses.
+
+#[no_implicit_prelude]
+mod without_prelude {
+    use ::defunctionalize::defunctionalize;
+
+    #[defunctionalize(fn(x: u32) -> u32, auto_clone, reflect, markers, set)]
+    #[derive(Debug)]
+    pub mod arithmetic {
+        pub fn double(x: u32) -> u32 {
+            x * 2
+        }
+
+        #[defunctionalize(priority = 1, cost = 2, default)]
+        pub fn square(x: u32) -> u32 {
+            x * x
+        }
+    }
+
+    #[defunctionalize(fn(total: &mut u32), auto_clone, reflect, args, apply, input_struct)]
+    #[derive(PartialEq, Debug)]
+    pub mod counter {
+        #[defunctionalize(undo = remove)]
+        pub fn add(amount: u32, total: &mut u32) {
+            *total += amount;
+        }
+
+        pub fn remove(#[defunctionalize(skip_eq)] amount: u32, total: &mut u32) {
+            *total -= amount;
+        }
+
+        pub fn reset(total: &mut u32) {
+            *total = 0;
+        }
+    }
+}
+
+#[cfg(feature = "postcard")]
+#[no_implicit_prelude]
+mod tagged {
+    #![allow(dead_code)]
+
+    use ::defunctionalize::defunctionalize;
+
+    pub struct Option;
+    pub struct Result;
+    pub mod core {}
+
+    #[defunctionalize(fn(x: u32) -> u32, stable_hash, postcard, version = 2)]
+    #[derive(PartialEq, Debug)]
+    pub mod arithmetic {
+        #[defunctionalize(tag = 1)]
+        pub fn add(amount: u32, x: u32) -> u32 {
+            x + amount
+        }
+
+        #[defunctionalize(tag = 2, alias = "times")]
+        pub fn multiply(factor: u32, x: u32) -> u32 {
+            x * factor
+        }
+    }
+}
+
+#[no_implicit_prelude]
+mod asynchronous {
+    #![allow(dead_code)]
+
+    use ::defunctionalize::defunctionalize;
+
+    pub struct Option;
+    pub struct Result;
+    pub mod core {}
+
+    #[defunctionalize(async fn(x: u32) -> u32)]
+    pub mod delayed {
+        pub async fn double(x: u32) -> u32 {
+            x * 2
+        }
+
+        pub async fn add(amount: u32, x: u32) -> u32 {
+            x + amount
+        }
+    }
+}
+
+mod shadowed {
+    #![allow(dead_code)]
+
+    use ::defunctionalize::defunctionalize;
+
+    pub struct Option;
+    pub struct Result;
+    pub struct Some;
+    pub struct None;
+    pub struct Ok;
+    pub struct Err;
+    pub struct Box;
+    pub struct Vec;
+    pub struct String;
+    pub trait Clone {}
+    pub trait Copy {}
+    pub trait Fn {}
+    pub trait FnOnce {}
+    pub trait Into {}
+    pub trait Default {}
+    pub trait PartialEq {}
+    pub trait Iterator {}
+
+    pub mod core {}
+    pub mod std {}
+    pub mod alloc {}
+    pub mod defunctionalize {}
+
+    #[defunctionalize(fn(x: u32) -> u32, auto_clone, reflect, markers, set)]
+    #[derive(Debug)]
+    pub mod arithmetic {
+        pub fn double(x: u32) -> u32 {
+            x * 2
+        }
+
+        #[defunctionalize(priority = 1, cost = 2, default)]
+        pub fn square(x: u32) -> u32 {
+            x * x
+        }
+    }
+
+    #[defunctionalize(fn(total: &mut u32), auto_clone, reflect, args, apply, input_struct)]
+    pub mod counter {
+        #[defunctionalize(undo = remove)]
+        pub fn add(amount: u32, total: &mut u32) {
+            *total += amount;
+        }
+
+        pub fn remove(amount: u32, total: &mut u32) {
+            *total -= amount;
+        }
+
+        pub fn reset(total: &mut u32) {
+            *total = 0;
+        }
+    }
+}
+
+macro_rules! commands {
+    ($name:ident, $($function:ident => $factor:expr),*) => {
+        #[defunctionalize::defunctionalize(fn(x: u32) -> u32)]
+        mod $name {
+            $(
+                pub fn $function(x: u32) -> u32 {
+                    x * $factor
+                }
+            )*
+        }
+    };
+}
+
+commands!(scaling, double => 2, triple => 3);
+
+use defunctionalize::DeFn;
+
+#[test]
+fn expands_without_prelude() {
+    use without_prelude::{Arithmetic, Counter};
+
+    assert_eq!(Arithmetic::Square.call(3), 9);
+    assert_eq!(DeFn::call(Arithmetic::Double, 3), 6);
+    assert_eq!(Arithmetic::default(), Arithmetic::Square);
+    assert_eq!(Arithmetic::Square.name(), "square");
+
+    let mut total = 0;
+    Counter::Add(5).call(&mut total);
+    Counter::Add(5).invert().unwrap().call(&mut total);
+    assert_eq!(total, 0);
+    assert_eq!(Counter::Remove(1), Counter::Remove(2));
+}
+
+#[test]
+fn expands_with_shadowed_names() {
+    use shadowed::{Arithmetic, Counter};
+
+    assert_eq!(Arithmetic::Square.call(3), 9);
+    assert_eq!(Arithmetic::Double.weight(), 1);
+    assert_eq!(Arithmetic::Square.name(), "square");
+
+    let mut total = 0;
+    Counter::Add(5).call(&mut total);
+    Counter::Add(5).invert().unwrap().call(&mut total);
+    assert_eq!(total, 0);
+}
+
+#[cfg(feature = "postcard")]
+#[test]
+fn expands_tagged_commands_without_prelude() {
+    use tagged::Arithmetic;
+
+    let mut buffer = [0; 16];
+    let used = Arithmetic::Multiply(3).encode(&mut buffer).unwrap();
+    assert_eq!(
+        Arithmetic::decode(&buffer[..used]).unwrap(),
+        Arithmetic::Multiply(3)
+    );
+    assert_ne!(
+        defunctionalize::stable_hash(&Arithmetic::Add(1)),
+        defunctionalize::stable_hash(&Arithmetic::Add(2))
+    );
+}
+
+#[test]
+fn expands_async_commands_without_prelude() {
+    use asynchronous::Delayed;
+
+    let output = futures::executor::block_on(Delayed::Add(1).call(2));
+    assert_eq!(output, 3);
+}
+
+#[test]
+fn expands_inside_other_macros() {
+    assert_eq!(Scaling::Triple.call(2), 6);
+}
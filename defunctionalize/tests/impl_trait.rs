@@ -0,0 +1,36 @@
+use defunctionalize::defunctionalize;
+
+pub trait Command {
+    fn call(self, x: u32, y: u32) -> u32;
+}
+
+pub trait Operation {
+    fn call(self, x: u32, y: u32) -> u32;
+}
+
+#[defunctionalize(fn(x: u32, y: u32) -> u32, impl_trait = Command, impl_trait = Operation)]
+mod arithmetic {
+    pub fn add(x: u32, y: u32) -> u32 {
+        x + y
+    }
+
+    pub fn add_plus_n(n: u32, x: u32, y: u32) -> u32 {
+        x + y + n
+    }
+}
+
+fn run<C: Command>(command: C) -> u32 {
+    command.call(2, 3)
+}
+
+#[test]
+fn implements_the_given_traits() {
+    assert_eq!(run(Arithmetic::Add), 5);
+    assert_eq!(run(Arithmetic::AddPlusN(1)), 6);
+    assert_eq!(Operation::call(Arithmetic::Add, 1, 1), 2);
+}
+
+#[test]
+fn still_implements_defn() {
+    assert_eq!(defunctionalize::DeFn::call(Arithmetic::Add, (2, 3)), 5);
+}
@@ -0,0 +1,65 @@
+use defunctionalize::defunctionalize;
+
+pub mod model {
+    #[derive(Clone, PartialEq, Debug)]
+    pub struct Item {
+        pub name: &'static str,
+    }
+
+    pub type Price = u32;
+
+    pub mod units {
+        pub type Count = u32;
+    }
+}
+
+// None of the types which the functions capture are in scope here, beside the module.
+#[defunctionalize(fn(total: &mut u32))]
+mod order {
+    use super::model;
+    use crate::model::units::Count;
+    use crate::model::{Item, Price as Cost};
+    use std::collections::BTreeMap;
+
+    pub fn add(item: Item, cost: Cost, count: Count, total: &mut u32) {
+        let _ = item;
+        *total += cost * count;
+    }
+
+    pub fn add_all(items: Vec<(model::Item, Cost)>, total: &mut u32) {
+        *total += items.iter().map(|(.., cost)| cost).sum::<u32>();
+    }
+
+    pub fn add_priced(prices: BTreeMap<&'static str, Cost>, total: &mut u32) {
+        *total += prices.values().sum::<u32>();
+    }
+}
+
+#[test]
+fn imported_types_are_named_by_their_paths() {
+    let mut total = 0;
+    let item = model::Item { name: "apple" };
+    Order::Add(item.clone(), 2, 3).call(&mut total);
+    Order::AddAll(vec![(item, 4)]).call(&mut total);
+    Order::AddPriced(vec![("pear", 5)].into_iter().collect()).call(&mut total);
+    assert_eq!(total, 15);
+}
+
+#[defunctionalize(fn(total: &mut u32))]
+mod chained {
+    // `Item` is imported through the name `model`, which is itself imported.
+    use super::model;
+    use model::units::Count;
+    use model::Item;
+
+    pub fn add(item: Item, count: Count, total: &mut u32) {
+        *total += item.name.len() as u32 * count;
+    }
+}
+
+#[test]
+fn chained_imports_are_followed() {
+    let mut total = 0;
+    Chained::Add(model::Item { name: "pear" }, 2).call(&mut total);
+    assert_eq!(total, 8);
+}
@@ -0,0 +1,29 @@
+use defunctionalize::defunctionalize;
+
+mod external {
+    pub fn triple(x: u32) -> u32 {
+        x * 3
+    }
+
+    pub fn add_plus_n(n: u32, x: u32) -> u32 {
+        x + n
+    }
+}
+
+#[defunctionalize(
+    fn(x: u32) -> u32,
+    include(external::triple, external::add_plus_n(n: u32))
+)]
+#[derive(PartialEq, Debug)]
+mod arithmetic {
+    pub fn double(x: u32) -> u32 {
+        x * 2
+    }
+}
+
+#[test]
+fn included_functions_become_cases() {
+    assert_eq!(Arithmetic::Double.call(2), 4);
+    assert_eq!(Arithmetic::Triple.call(2), 6);
+    assert_eq!(Arithmetic::AddPlusN(5).call(2), 7);
+}
@@ -0,0 +1,36 @@
+use defunctionalize::{defunctionalize, DeFn};
+
+#[defunctionalize(fn(from: u32, to: u32) -> i64, input_struct)]
+mod distance {
+    pub fn forward(from: u32, to: u32) -> i64 {
+        to as i64 - from as i64
+    }
+
+    pub fn scaled(factor: i64, from: u32, to: u32) -> i64 {
+        (to as i64 - from as i64) * factor
+    }
+}
+
+#[defunctionalize(fn(from: u32, to: u32) -> i64, input_struct = Span)]
+mod backward {
+    pub fn backward(from: u32, to: u32) -> i64 {
+        from as i64 - to as i64
+    }
+}
+
+fn measure<D: DeFn<DistanceInput, Output = i64>>(command: D) -> i64 {
+    command.call(DistanceInput { from: 2, to: 5 })
+}
+
+#[test]
+fn commands_are_called_with_named_arguments() {
+    assert_eq!(measure(Distance::Forward), 3);
+    assert_eq!(measure(Distance::Scaled(2)), 6);
+    // The inherent `call` still takes the arguments separately.
+    assert_eq!(Distance::Forward.call(2, 5), 3);
+}
+
+#[test]
+fn input_structs_are_named_by_the_option() {
+    assert_eq!(DeFn::call(Backward::Backward, Span { from: 5, to: 2 }), 3);
+}
@@ -0,0 +1,31 @@
+use defunctionalize::{defunctionalize, DeFn, DeFnExt, Inspect};
+use std::cell::RefCell;
+
+#[defunctionalize(fn(x: u32) -> u32)]
+#[derive(Debug)]
+mod operation {
+    pub fn add(amount: u32, x: u32) -> u32 {
+        x + amount
+    }
+}
+
+#[test]
+fn commands_are_inspected_before_they_are_called() {
+    let log = RefCell::new(vec![]);
+    let command =
+        Operation::Add(2).inspect(|command| log.borrow_mut().push(format!("{:?}", command)));
+    assert!(log.borrow().is_empty());
+    assert_eq!(command.call(1), 3);
+    assert_eq!(*log.borrow(), ["Add(2)"]);
+}
+
+#[test]
+fn outputs_are_inspected_before_they_are_returned() {
+    let log = RefCell::new(vec![]);
+    let command = Inspect(Operation::Add(2), |_: &Operation| {
+        log.borrow_mut().push("call".to_owned())
+    })
+    .inspect_output(|output: &u32| log.borrow_mut().push(format!("output {}", output)));
+    assert_eq!(command.call(1), 3);
+    assert_eq!(*log.borrow(), ["call", "output 3"]);
+}
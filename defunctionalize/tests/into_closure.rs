@@ -0,0 +1,32 @@
+use defunctionalize::defunctionalize;
+
+#[defunctionalize(fn(x: u32, y: u32) -> u32)]
+mod operation {
+    pub fn add(x: u32, y: u32) -> u32 {
+        x + y
+    }
+
+    pub fn add_plus_n(n: u32, x: u32, y: u32) -> u32 {
+        x + y + n
+    }
+}
+
+fn apply(f: impl FnOnce(u32, u32) -> u32) -> u32 {
+    f(2, 3)
+}
+
+#[test]
+fn closure_calls_the_command() {
+    assert_eq!(apply(Operation::Add.into_closure()), 5);
+    let closure = Operation::AddPlusN(10).into_closure();
+    assert_eq!(closure(1, 1), 12);
+}
+
+#[test]
+fn closure_is_not_boxed() {
+    let closure = Operation::AddPlusN(10).into_closure();
+    assert_eq!(
+        std::mem::size_of_val(&closure),
+        std::mem::size_of::<Operation>()
+    );
+}
@@ -0,0 +1,56 @@
+use defunctionalize::{defunctionalize, Job};
+use std::time::{Duration, SystemTime};
+
+#[defunctionalize(fn(available: &mut u32) -> Result<(), String>)]
+#[derive(Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+mod task {
+    pub fn reserve(count: u32, available: &mut u32) -> Result<(), String> {
+        if *available < count {
+            return Err(format!("only {} available", available));
+        }
+        *available -= count;
+        Ok(())
+    }
+}
+
+#[test]
+fn failed_jobs_are_retried_until_exhausted() {
+    let start = SystemTime::UNIX_EPOCH + Duration::from_secs(100);
+    let mut job = Job::new("reserve-1", Task::Reserve(3)).with_max_retries(2);
+    let mut available = 1;
+    let mut now = start;
+    let mut delays = vec![];
+    while job.should_run(now) {
+        if job.attempt(&mut available).is_ok() {
+            break;
+        }
+        job.backoff(now, Duration::from_secs(10));
+        delays.push(job.next_run_at.duration_since(now).unwrap().as_secs());
+        assert!(!job.should_run(now));
+        now = job.next_run_at;
+    }
+    assert_eq!(job.attempts, 3);
+    assert!(job.is_exhausted());
+    assert_eq!(delays, [10, 20, 40]);
+}
+
+#[test]
+fn jobs_wait_until_they_are_due() {
+    let later = SystemTime::UNIX_EPOCH + Duration::from_secs(60);
+    let mut job = Job::new("reserve-2", Task::Reserve(1)).run_at(later);
+    assert!(!job.should_run(SystemTime::UNIX_EPOCH));
+    assert!(job.should_run(later));
+    let mut available = 1;
+    assert_eq!(job.attempt(&mut available), Ok(()));
+    assert_eq!(available, 0);
+    assert!(job.is_exhausted());
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn jobs_are_stored_until_they_run() {
+    let job = Job::new("reserve-3", Task::Reserve(2)).with_max_retries(1);
+    let json = serde_json::to_string(&job).unwrap();
+    assert_eq!(serde_json::from_str::<Job<Task>>(&json).unwrap(), job);
+}
@@ -0,0 +1,36 @@
+#![cfg(feature = "serde_json")]
+
+use defunctionalize::defunctionalize;
+use serde::Deserialize;
+
+#[defunctionalize(fn(x: u32, y: u32) -> u32, json)]
+#[derive(Deserialize, PartialEq, Debug)]
+mod operation {
+    pub fn add(x: u32, y: u32) -> u32 {
+        x + y
+    }
+
+    pub fn scale(factor: u32, x: u32, y: u32) -> u32 {
+        (x + y) * factor
+    }
+}
+
+#[test]
+fn commands_are_read_from_json() {
+    assert_eq!(
+        Operation::from_json_str(r#""Add""#).unwrap(),
+        Operation::Add
+    );
+    assert_eq!(
+        Operation::from_json_str(r#"{"Scale":2}"#).unwrap(),
+        Operation::Scale(2)
+    );
+    assert!(Operation::from_json_str(r#"{"Scale":"two"}"#).is_err());
+}
+
+#[test]
+fn commands_read_from_json_are_called() {
+    assert_eq!(Operation::dispatch_json(r#"{"Scale":3}"#, 1, 2).unwrap(), 9);
+    let error = Operation::dispatch_json(r#""Sub""#, 1, 2).unwrap_err();
+    assert!(error.to_string().contains("unknown variant `Sub`"));
+}
@@ -0,0 +1,27 @@
+#![cfg(feature = "schemars")]
+
+use defunctionalize::defunctionalize;
+
+#[defunctionalize(fn(x: u32) -> u32, json_schema)]
+mod arithmetic {
+    /// Adds an amount.
+    pub fn add(amount: u32, x: u32) -> u32 {
+        x + amount
+    }
+
+    /// Doubles the input.
+    pub fn double(x: u32) -> u32 {
+        x * 2
+    }
+}
+
+#[test]
+fn schema_describes_every_case() {
+    let schema = serde_json::to_value(schemars::schema_for!(Arithmetic)).unwrap();
+    let json = schema.to_string();
+    assert_eq!(schema["title"], "Arithmetic");
+    assert!(json.contains(r#""Add""#));
+    assert!(json.contains(r#""Double""#));
+    assert!(json.contains("Adds an amount."));
+    assert!(json.contains("Doubles the input."));
+}
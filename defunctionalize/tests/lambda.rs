@@ -0,0 +1,24 @@
+use defunctionalize::{defn, DeFn};
+
+#[test]
+fn closure_becomes_a_command() {
+    let bonus = 10;
+    let command = defn!(move |x: u32, y: u32| -> u32 { x + y + bonus });
+    assert_eq!(command.call((1, 2)), 13);
+}
+
+#[test]
+fn captures_are_moved_into_the_command() {
+    let name = String::from("world");
+    let command = defn!(move |greeting: &str| -> String { format!("{} {}", greeting, name) });
+    assert_eq!(command.call("hello"), "hello world");
+}
+
+fn run<D: DeFn<(u32, u32), Output = u32>>(command: D) -> u32 {
+    command.call((2, 3))
+}
+
+#[test]
+fn command_implements_defn() {
+    assert_eq!(run(defn!(|x: u32, y: u32| -> u32 { x * y })), 6);
+}
@@ -0,0 +1,38 @@
+use defunctionalize::defunctionalize;
+
+#[defunctionalize(fn(x: u32) -> u32, like_trait)]
+mod arithmetic {
+    pub fn double(x: u32) -> u32 {
+        x * 2
+    }
+}
+
+#[defunctionalize(fn(x: u32) -> u32, like_trait = Scaling)]
+mod scale {
+    pub fn by(factor: u32, x: u32) -> u32 {
+        x * factor
+    }
+}
+
+struct Constant(u32);
+
+impl ArithmeticLike for Constant {
+    fn call(self, _x: u32) -> u32 {
+        self.0
+    }
+}
+
+fn run(command: impl ArithmeticLike) -> u32 {
+    command.call(4)
+}
+
+#[test]
+fn generated_trait_is_implemented_by_the_enum_and_others() {
+    assert_eq!(run(Arithmetic::Double), 8);
+    assert_eq!(run(Constant(1)), 1);
+}
+
+#[test]
+fn generated_trait_can_be_named() {
+    assert_eq!(Scaling::call(Scale::By(3), 2), 6);
+}
@@ -0,0 +1,93 @@
+//! Generated items pass in crates which deny lints.
+#![deny(missing_docs, warnings, clippy::pedantic)]
+
+use defunctionalize::defunctionalize;
+
+/// A configuration which is being replaced.
+#[deprecated]
+#[derive(Clone, Copy)]
+pub struct OldScale(pub u32);
+
+/// Operations on numbers.
+#[defunctionalize(fn(x: u32) -> u32)]
+pub mod operation {
+    #[allow(deprecated)]
+    use super::OldScale;
+
+    /// Doubles the number.
+    #[must_use]
+    pub fn double(x: u32) -> u32 {
+        x * 2
+    }
+
+    /// Scales the number by an old configuration.
+    #[expect(deprecated)]
+    #[must_use]
+    pub fn scale(scale: OldScale, x: u32) -> u32 {
+        x * scale.0
+    }
+
+    /// Scales the number by a factor.
+    #[allow(deprecated)]
+    #[must_use]
+    pub fn scale_again(scale: OldScale, x: u32) -> u32 {
+        x * scale.0
+    }
+}
+
+/// Operations generating items which contain blocks, closures and array types, each of which must
+/// be given the attributes which allow the lints.
+#[defunctionalize(fn(x: u32) -> u32, set, reflect, max_size = 8)]
+pub mod toggle {
+    /// Doubles the number.
+    #[defunctionalize(priority = 1)]
+    #[must_use]
+    pub fn double(x: u32) -> u32 {
+        x * 2
+    }
+
+    /// Squares the number.
+    #[must_use]
+    pub fn square(x: u32) -> u32 {
+        x * x
+    }
+}
+
+/// Operations with arguments generating items which contain blocks, each of which must be given
+/// the attributes which allow the lints.
+#[defunctionalize(fn(x: u32) -> u32, chunked = 1, reflect, max_size = 16)]
+pub mod adjustment {
+    /// Adds an amount to the number.
+    #[defunctionalize(default, cost = 2)]
+    #[must_use]
+    pub fn add(amount: u32, x: u32) -> u32 {
+        x + amount
+    }
+
+    /// Clamps the number between two others.
+    #[must_use]
+    pub fn clamp(min: u32, max: u32, x: u32) -> u32 {
+        x.clamp(min, max)
+    }
+}
+
+#[test]
+#[allow(deprecated)]
+fn lint_attributes_are_forwarded_to_cases() {
+    assert_eq!(Operation::Double.call(2), 4);
+    assert_eq!(Operation::Scale(OldScale(3)).call(2), 6);
+    assert_eq!(Operation::ScaleAgain(OldScale(3)).call(2), 6);
+}
+
+#[test]
+fn every_generated_item_allows_the_lints() {
+    let mut set = ToggleSet::new();
+    set.insert(Toggle::Square);
+    assert!(set.contains(&Toggle::Square));
+    assert_eq!(Toggle::LOOKUP[Toggle::Double.variant_index()](3), 6);
+    assert_eq!(Toggle::Double.priority(), 1);
+    assert_eq!(Adjustment::default().call(3), 3);
+    assert_eq!(Adjustment::Clamp(2, 4).call(7), 4);
+    assert_eq!(Adjustment::Add(1).weight(), 2);
+    assert_eq!(Adjustment::SCHEMA[1].name, "clamp");
+}
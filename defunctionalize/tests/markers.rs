@@ -0,0 +1,41 @@
+use defunctionalize::defunctionalize;
+
+#[defunctionalize(fn(x: u32) -> u32, markers)]
+#[derive(PartialEq, Debug)]
+mod operation {
+    pub fn double(x: u32) -> u32 {
+        x * 2
+    }
+
+    pub fn clamp(min: u32, max: u32, x: u32) -> u32 {
+        x.max(min).min(max)
+    }
+}
+
+#[defunctionalize(fn(x: u32) -> u32, markers = ops)]
+mod renamed {
+    pub fn negate(x: u32) -> u32 {
+        !x
+    }
+}
+
+use operation_markers::{Clamp, Double, Variant};
+
+/// Describes a command by its type, without a value of it.
+fn describe<V: Variant>() -> String {
+    format!("{} #{}", V::NAME, V::INDEX)
+}
+
+#[test]
+fn markers_describe_their_commands() {
+    assert_eq!(describe::<Double>(), "double #0");
+    assert_eq!(describe::<Clamp>(), "clamp #1");
+    assert_eq!(std::mem::size_of::<Clamp>(), 0);
+}
+
+#[test]
+fn markers_construct_their_commands() {
+    assert_eq!(Double::command(()), Operation::Double);
+    assert_eq!(Clamp::command((1, 5)), Operation::Clamp(1, 5));
+    assert_eq!(<ops::Negate as ops::Variant>::NAME, "negate");
+}
@@ -0,0 +1,24 @@
+use defunctionalize::defunctionalize;
+
+#[defunctionalize(fn(x: u64) -> u64, max_size = 16)]
+mod operation {
+    pub fn add(amount: u64, x: u64) -> u64 {
+        x + amount
+    }
+
+    pub fn clamp(min: u64, max: u64, x: u64) -> u64 {
+        x.max(min).min(max)
+    }
+
+    pub fn double(x: u64) -> u64 {
+        x * 2
+    }
+}
+
+#[test]
+fn commands_within_the_limit_compile() {
+    assert_eq!(Operation::Add(1).call(1), 2);
+    assert_eq!(Operation::Clamp(2, 4).call(7), 4);
+    assert_eq!(Operation::Double.call(3), 6);
+    assert!(std::mem::size_of::<Operation>() <= 24);
+}
@@ -0,0 +1,33 @@
+use defunctionalize::defunctionalize;
+
+#[defunctionalize(fn(x: u32) -> u32, merge(scaling, offsets))]
+#[derive(PartialEq, Debug)]
+mod arithmetic {
+    pub fn identity(x: u32) -> u32 {
+        x
+    }
+
+    pub mod scaling {
+        pub fn double(x: u32) -> u32 {
+            x * 2
+        }
+    }
+
+    pub mod offsets {
+        pub fn add(amount: u32, x: u32) -> u32 {
+            x + amount
+        }
+
+        #[allow(dead_code)]
+        fn private(x: u32) -> u32 {
+            x
+        }
+    }
+}
+
+#[test]
+fn functions_of_merged_modules_become_cases() {
+    assert_eq!(Arithmetic::Identity.call(2), 2);
+    assert_eq!(Arithmetic::Double.call(2), 4);
+    assert_eq!(Arithmetic::Add(3).call(2), 5);
+}
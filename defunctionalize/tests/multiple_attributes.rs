@@ -0,0 +1,35 @@
+use defunctionalize::defunctionalize;
+
+pub struct Counter {
+    count: u32,
+}
+
+#[defunctionalize(fn Read(counter: &Counter) -> u32)]
+#[defunctionalize(fn Write(counter: &mut Counter) -> u32)]
+#[derive(Clone, Copy, PartialEq, Debug)]
+mod counting {
+    use super::Counter;
+
+    pub fn current(counter: &Counter) -> u32 {
+        counter.count
+    }
+
+    pub fn offset(by: u32, counter: &Counter) -> u32 {
+        counter.count + by
+    }
+}
+
+#[test]
+fn each_attribute_generates_an_enum() {
+    let mut counter = Counter { count: 2 };
+    assert_eq!(Read::Offset(1).call(&counter), 3);
+    // Each function is passed a `&Counter` from the `&mut Counter`.
+    assert_eq!(Write::Offset(1).call(&mut counter), 3);
+    assert_eq!(Write::Current.call(&mut counter), 2);
+}
+
+#[test]
+fn module_attributes_apply_to_every_enum() {
+    assert_eq!(Read::Current, Read::Current);
+    assert_eq!(Write::Offset(1), Write::Offset(1));
+}
@@ -0,0 +1,30 @@
+use arithmetic::Offsets;
+use defunctionalize::defunctionalize;
+
+#[defunctionalize(fn(x: u32) -> u32)]
+#[derive(PartialEq, Debug)]
+mod arithmetic {
+    pub fn double(x: u32) -> u32 {
+        x * 2
+    }
+
+    #[defunctionalize::defunctionalize(fn(x: u32) -> u32)]
+    #[derive(PartialEq, Debug)]
+    pub mod offsets {
+        pub fn add(amount: u32, x: u32) -> u32 {
+            x + amount
+        }
+
+        pub fn increment(x: u32) -> u32 {
+            x + 1
+        }
+    }
+}
+
+#[test]
+fn nested_enums_are_wrapped_by_a_case() {
+    let command = Arithmetic::Offsets(Offsets::Add(3));
+    assert_eq!(command.call(2), 5);
+    assert_eq!(Arithmetic::Offsets(Offsets::Increment).call(2), 3);
+    assert_eq!(Arithmetic::Double.call(2), 4);
+}
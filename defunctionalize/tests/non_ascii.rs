@@ -0,0 +1,37 @@
+#![deny(warnings)]
+
+use defunctionalize::defunctionalize;
+
+#[defunctionalize(fn(x: u32) -> u32, markers)]
+#[derive(PartialEq, Debug)]
+mod opérations {
+    pub fn réduire_à_moitié(x: u32) -> u32 {
+        x / 2
+    }
+
+    pub fn ßeite(x: u32) -> u32 {
+        x
+    }
+}
+
+#[defunctionalize(fn 計算命令(x: u32) -> u32)]
+mod 計算 {
+    pub fn 倍(x: u32) -> u32 {
+        x * 2
+    }
+}
+
+#[test]
+fn non_ascii_names_keep_their_letters() {
+    assert_eq!(Opérations::RéduireÀMoitié.call(4), 2);
+    assert_eq!(Opérations::ßeite.call(3), 3);
+    assert_eq!(
+        <opérations_markers::RéduireÀMoitié as opérations_markers::Variant>::NAME,
+        "réduire_à_moitié"
+    );
+}
+
+#[test]
+fn names_without_case_are_kept() {
+    assert_eq!(計算命令::倍.call(2), 4);
+}
@@ -0,0 +1,32 @@
+use defunctionalize::{defunctionalize, DeFn, Once};
+
+#[defunctionalize(fn(log: &mut Vec<String>))]
+#[derive(Debug, PartialEq)]
+mod task {
+    #[allow(clippy::ptr_arg)]
+    pub fn greet(name: String, log: &mut Vec<String>) {
+        log.push(format!("hello {}", name));
+    }
+}
+
+#[test]
+fn commands_are_called_at_most_once() {
+    let mut log = vec![];
+    let mut once = Once::new(Task::Greet("world".to_owned()));
+    assert!(!once.is_called());
+    assert_eq!(once.get(), Some(&Task::Greet("world".to_owned())));
+    assert_eq!(once.call_mut(&mut log), Some(()));
+    assert!(once.is_called());
+    assert_eq!(DeFn::call(&mut once, &mut log), None);
+    assert_eq!(log, ["hello world"]);
+    assert_eq!(once.into_inner(), None);
+}
+
+#[test]
+#[should_panic(expected = "called more than once")]
+fn panicking_commands_panic_when_called_again() {
+    let mut log = vec![];
+    let mut once = Once::panicking(Task::Greet("world".to_owned()));
+    once.call_mut(&mut log);
+    once.call_mut(&mut log);
+}
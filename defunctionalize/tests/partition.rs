@@ -0,0 +1,41 @@
+use defunctionalize::defunctionalize;
+
+#[derive(Default)]
+pub struct Ctx {
+    log: Vec<String>,
+}
+
+#[defunctionalize(fn(ctx: _) -> _, partition)]
+mod handlers {
+    use super::Ctx;
+
+    pub fn greet(name: String, ctx: &mut Ctx) {
+        ctx.log.push(format!("hello {}", name));
+    }
+
+    pub fn clear(ctx: &mut Ctx) {
+        ctx.log.clear();
+    }
+
+    pub fn count(ctx: &Ctx) -> usize {
+        ctx.log.len()
+    }
+
+    pub fn last(prefix: &'static str, ctx: &Ctx) -> usize {
+        ctx.log
+            .iter()
+            .filter(|line| line.starts_with(prefix))
+            .count()
+    }
+}
+
+#[test]
+fn functions_are_grouped_by_signature() {
+    let mut ctx = Ctx::default();
+    HandlersMutCtx::Greet("alice".to_owned()).call(&mut ctx);
+    HandlersMutCtx::Greet("bob".to_owned()).call(&mut ctx);
+    assert_eq!(HandlersCtx::Count.call(&ctx), 2);
+    assert_eq!(HandlersCtx::Last("hello a").call(&ctx), 1);
+    HandlersMutCtx::Clear.call(&mut ctx);
+    assert_eq!(HandlersCtx::Count.call(&ctx), 0);
+}
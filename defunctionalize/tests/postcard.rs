@@ -0,0 +1,45 @@
+#![cfg(feature = "postcard")]
+
+use defunctionalize::{defunctionalize, PostcardError};
+
+#[defunctionalize(fn(x: u32) -> u32, postcard)]
+#[derive(PartialEq, Debug)]
+mod arithmetic {
+    #[defunctionalize(tag = 1)]
+    pub fn add(amount: u32, x: u32) -> u32 {
+        x + amount
+    }
+
+    #[defunctionalize(tag = 200)]
+    pub fn scale(factor: u16, offset: i8, x: u32) -> u32 {
+        (x * factor as u32).wrapping_add(offset as u32)
+    }
+}
+
+#[test]
+fn encodes_into_a_buffer_without_allocating() {
+    let mut buffer = [0; 8];
+    let used = Arithmetic::Add(5).encode(&mut buffer).unwrap();
+    assert_eq!(&buffer[..used], [1, 5]);
+    assert_eq!(
+        Arithmetic::decode(&buffer[..used]).unwrap(),
+        Arithmetic::Add(5)
+    );
+
+    let used = Arithmetic::Scale(300, -1).encode(&mut buffer).unwrap();
+    assert_eq!(
+        Arithmetic::decode(&buffer[..used]).unwrap(),
+        Arithmetic::Scale(300, -1)
+    );
+}
+
+#[test]
+fn reports_small_buffers_and_bad_input() {
+    let mut buffer = [0; 1];
+    assert!(matches!(
+        Arithmetic::Add(5).encode(&mut buffer),
+        Err(PostcardError::SerializeBufferFull)
+    ));
+    assert!(Arithmetic::decode(&[9, 1]).is_err());
+    assert!(Arithmetic::decode(&[]).is_err());
+}
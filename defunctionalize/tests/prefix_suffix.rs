@@ -0,0 +1,53 @@
+use defunctionalize::defunctionalize;
+
+mod chess {
+    use defunctionalize::defunctionalize;
+
+    #[defunctionalize(fn(position: u32) -> u32, prefix = Chess, args)]
+    pub mod moves {
+        pub fn advance(squares: u32, position: u32) -> u32 {
+            position + squares
+        }
+    }
+}
+
+mod checkers {
+    use defunctionalize::defunctionalize;
+
+    #[defunctionalize(fn(position: u32) -> u32, prefix = Checkers, args)]
+    pub mod moves {
+        pub fn jump(position: u32) -> u32 {
+            position + 2
+        }
+    }
+}
+
+#[defunctionalize(fn(position: u32) -> u32, suffix = Command)]
+mod moves {
+    pub fn stay(position: u32) -> u32 {
+        position
+    }
+}
+
+#[defunctionalize(fn Turn(position: u32) -> u32, prefix = Chess, suffix = Command)]
+mod turns {
+    pub fn pass(position: u32) -> u32 {
+        position
+    }
+}
+
+#[test]
+fn enum_names_are_prefixed_and_suffixed() {
+    assert_eq!(chess::ChessMoves::Advance(2).call(1), 3);
+    assert_eq!(checkers::CheckersMoves::Jump.call(1), 3);
+    assert_eq!(MovesCommand::Stay.call(1), 1);
+    assert_eq!(ChessTurnCommand::Pass.call(1), 1);
+}
+
+#[test]
+fn types_named_after_the_enum_are_prefixed() {
+    let args = chess::ChessMovesArgs { position: 4 };
+    assert_eq!(chess::ChessMoves::Advance(1).call_with(args), 5);
+    let args = checkers::CheckersMovesArgs { position: 4 };
+    assert_eq!(checkers::CheckersMoves::Jump.call_with(args), 6);
+}
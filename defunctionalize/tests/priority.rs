@@ -0,0 +1,79 @@
+use defunctionalize::{defunctionalize, ByPriority, Prioritized, Scheduler};
+use std::collections::BinaryHeap;
+
+#[defunctionalize(fn(log: &mut Vec<String>))]
+#[derive(Clone, PartialEq, Debug)]
+mod task {
+    pub fn cleanup(path: &'static str, log: &mut Vec<String>) {
+        log.push(format!("cleanup {}", path));
+    }
+
+    #[defunctionalize(priority = 10)]
+    pub fn alert(level: u32, log: &mut Vec<String>) {
+        log.push(format!("alert {}", level));
+    }
+
+    #[defunctionalize(priority = -1)]
+    pub fn report(log: &mut Vec<String>) {
+        log.push("report".to_owned());
+    }
+
+    pub fn flush(log: &mut Vec<String>) {
+        log.push("flush".to_owned());
+    }
+}
+
+#[test]
+fn priority_defaults_to_zero() {
+    assert_eq!(Task::Alert(1).priority(), 10);
+    assert_eq!(Task::Report.priority(), -1);
+    assert_eq!(Task::Cleanup("tmp").priority(), 0);
+}
+
+#[test]
+fn equality_is_left_to_the_enum() {
+    assert_ne!(Task::Alert(1), Task::Alert(2));
+    assert_eq!(Task::Alert(1), Task::Alert(1));
+    assert_eq!(Task::Alert(1).priority_key(), Task::Alert(2).priority_key());
+}
+
+#[test]
+fn scheduler_calls_commands_by_priority() {
+    let mut scheduler: Scheduler<Task> = vec![
+        Task::Flush,
+        Task::Report,
+        Task::Alert(3),
+        Task::Cleanup("tmp"),
+    ]
+    .into_iter()
+    .collect();
+    assert_eq!(scheduler.len(), 4);
+    assert_eq!(scheduler.peek(), Some(&Task::Alert(3)));
+
+    let mut log = vec![];
+    while scheduler.run_next(&mut log).is_some() {}
+    // Equal priorities are called in the order of their functions.
+    assert_eq!(log, ["alert 3", "cleanup tmp", "flush", "report"]);
+    assert!(scheduler.is_empty());
+}
+
+#[test]
+fn commands_by_priority_drop_into_a_binary_heap() {
+    let mut heap = BinaryHeap::new();
+    heap.push(ByPriority(Task::Report));
+    heap.push(ByPriority(Task::Flush));
+    heap.push(ByPriority(Task::Alert(1)));
+    heap.push(ByPriority(Task::Cleanup("tmp")));
+    let order = std::iter::from_fn(|| heap.pop().map(|ByPriority(task)| task)).collect::<Vec<_>>();
+    assert_eq!(
+        order,
+        [
+            Task::Alert(1),
+            Task::Cleanup("tmp"),
+            Task::Flush,
+            Task::Report
+        ]
+    );
+    assert!(ByPriority(Task::Alert(1)) > ByPriority(Task::Cleanup("tmp")));
+    assert!(Task::Flush.priority_key() < Task::Cleanup("tmp").priority_key());
+}
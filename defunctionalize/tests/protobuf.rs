@@ -0,0 +1,68 @@
+#![cfg(feature = "prost")]
+
+use defunctionalize::{defunctionalize, ProtoError};
+use std::convert::TryFrom;
+
+#[defunctionalize(fn(x: i64) -> i64, protobuf)]
+#[derive(Clone, PartialEq, Debug)]
+mod operation {
+    #[defunctionalize(tag = 1)]
+    pub fn add(amount: i64, x: i64) -> i64 {
+        x + amount
+    }
+
+    #[defunctionalize(tag = 4)]
+    pub fn pick(choices: Vec<i64>, fallback: Option<i64>, x: i64) -> i64 {
+        choices.get(x as usize).copied().or(fallback).unwrap_or(x)
+    }
+
+    #[defunctionalize(tag = 2)]
+    pub fn negate(x: i64) -> i64 {
+        -x
+    }
+}
+
+#[test]
+fn commands_round_trip_through_protobuf() {
+    for command in [
+        Operation::Add(-3),
+        Operation::Pick(vec![4, 5], Some(6)),
+        Operation::Pick(vec![], None),
+        Operation::Negate,
+    ] {
+        let bytes = command.clone().encode_proto();
+        assert_eq!(Operation::decode_proto(&bytes), Ok(command));
+    }
+}
+
+#[test]
+fn commands_convert_into_messages() {
+    let message = operation_proto::Operation::from(Operation::Add(2));
+    assert_eq!(
+        message.command,
+        Some(operation_proto::Command::Add(operation_proto::AddArgs {
+            amount: 2
+        })),
+    );
+    // The field number of the command is its tag.
+    assert_eq!(Operation::Add(2).encode_proto(), [0x0a, 0x02, 0x08, 0x02]);
+    assert_eq!(Operation::try_from(message), Ok(Operation::Add(2)));
+}
+
+#[test]
+fn messages_without_commands_are_errors() {
+    let message = operation_proto::Operation { command: None };
+    assert_eq!(
+        Operation::try_from(message),
+        Err(ProtoError::MissingCommand)
+    );
+    // A command with a tag which this version does not have.
+    assert_eq!(
+        Operation::decode_proto(&[0x1a, 0x00]),
+        Err(ProtoError::MissingCommand)
+    );
+    assert!(matches!(
+        Operation::decode_proto(&[0x0a, 0x05]),
+        Err(ProtoError::Decode(..))
+    ));
+}
@@ -0,0 +1,46 @@
+use defunctionalize::defunctionalize;
+
+pub struct Ctx {
+    balance: u32,
+}
+
+pub mod billing {
+    use super::Ctx;
+
+    pub fn refund(amount: u32, ctx: &Ctx) -> u32 {
+        ctx.balance + amount
+    }
+
+    pub fn balance(ctx: &Ctx) -> u32 {
+        ctx.balance
+    }
+
+    pub struct Receipt;
+}
+
+#[defunctionalize(fn(ctx: &Ctx) -> u32)]
+mod handlers {
+    use super::Ctx;
+
+    #[defunctionalize(captures(amount: u32))]
+    pub use crate::billing::refund;
+
+    #[defunctionalize]
+    pub use crate::billing::balance;
+
+    // Not marked, so not a case, as it might not be a function.
+    pub use crate::billing::Receipt;
+
+    pub fn charge(amount: u32, ctx: &Ctx) -> u32 {
+        ctx.balance - amount
+    }
+}
+
+#[test]
+fn marked_reexports_become_cases() {
+    let ctx = Ctx { balance: 10 };
+    assert_eq!(Handlers::Refund(5).call(&ctx), 15);
+    assert_eq!(Handlers::Balance.call(&ctx), 10);
+    assert_eq!(Handlers::Charge(3).call(&ctx), 7);
+    let _ = handlers::Receipt;
+}
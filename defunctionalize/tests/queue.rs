@@ -0,0 +1,51 @@
+use defunctionalize::{defunctionalize, CommandQueue};
+
+#[derive(Default)]
+pub struct World {
+    entities: Vec<u32>,
+}
+
+#[defunctionalize(fn(world: &mut World))]
+#[derive(PartialEq, Debug)]
+mod command {
+    use super::World;
+
+    pub fn spawn(id: u32, world: &mut World) {
+        world.entities.push(id);
+    }
+
+    pub fn despawn(id: u32, world: &mut World) {
+        world.entities.retain(|entity| *entity != id);
+    }
+}
+
+#[test]
+fn applies_queued_commands_in_order() {
+    let mut world = World::default();
+    let mut queue = CommandQueue::new();
+    queue.push(Command::Spawn(1));
+    queue.extend(vec![Command::Spawn(2), Command::Despawn(1)]);
+    assert_eq!(queue.len(), 3);
+
+    queue.apply_all(&mut world);
+    assert!(queue.is_empty());
+    assert_eq!(world.entities, [2]);
+}
+
+#[test]
+fn commands_can_be_taken_or_discarded() {
+    let mut queue = CommandQueue::default();
+    queue.push(Command::Spawn(1));
+    queue.push(Command::Spawn(2));
+    assert_eq!(
+        queue.into_iter().collect::<Vec<_>>(),
+        [Command::Spawn(1), Command::Spawn(2)]
+    );
+
+    let mut queue = CommandQueue::new();
+    queue.push(Command::Spawn(1));
+    queue.clear();
+    let mut world = World::default();
+    queue.apply_all(&mut world);
+    assert!(world.entities.is_empty());
+}
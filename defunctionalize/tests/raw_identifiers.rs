@@ -0,0 +1,33 @@
+use defunctionalize::defunctionalize;
+use serde::{Deserialize, Serialize};
+
+#[defunctionalize(fn(board: &mut Vec<u32>), reflect)]
+#[derive(Serialize, Deserialize, PartialEq, Debug)]
+mod pieces {
+    pub fn r#move(r#type: u32, board: &mut Vec<u32>) {
+        board.push(r#type);
+    }
+}
+
+#[test]
+fn raw_functions_and_arguments_are_called() {
+    let mut board = vec![];
+    Pieces::Move(3).call(&mut board);
+    assert_eq!(board, [3]);
+}
+
+#[test]
+fn raw_identifiers_are_named_without_prefix() {
+    assert_eq!(Pieces::Move(3).name(), "move");
+    assert_eq!(Pieces::Move(3).arg_names(), &["type"]);
+}
+
+#[test]
+fn raw_functions_are_serialized_by_case_name() {
+    let json = serde_json::to_string(&Pieces::Move(3)).unwrap();
+    assert_eq!(json, r#"{"Move":3}"#);
+    assert_eq!(
+        serde_json::from_str::<Pieces>(&json).unwrap(),
+        Pieces::Move(3)
+    );
+}
@@ -0,0 +1,46 @@
+use defunctionalize::defunctionalize;
+
+#[defunctionalize(fn(attempts: &mut u32) -> bool, audit)]
+#[derive(Debug)]
+mod login {
+    pub fn password(
+        user: String,
+        #[defunctionalize(redact)] password: String,
+        attempts: &mut u32,
+    ) -> bool {
+        *attempts += 1;
+        user == "alice" && password == "hunter2"
+    }
+}
+
+#[test]
+fn redacted_arguments_are_hidden_from_debug() {
+    let command = Login::Password("alice".to_owned(), "hunter2".to_owned());
+    assert_eq!(format!("{:?}", command), r#"Password("alice", <redacted>)"#);
+    assert_eq!(
+        command.audit_record().to_string(),
+        r#"password user="alice" password=<redacted>"#
+    );
+    let mut attempts = 0;
+    assert!(command.call(&mut attempts));
+}
+
+#[cfg(feature = "serde")]
+#[defunctionalize(fn(attempts: &mut u32) -> bool)]
+#[derive(serde::Serialize)]
+mod token_login {
+    pub fn token(#[defunctionalize(redact(serde))] token: String, attempts: &mut u32) -> bool {
+        *attempts += 1;
+        token == "secret"
+    }
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn redacted_arguments_are_hidden_from_serde() {
+    let command = TokenLogin::Token("secret".to_owned());
+    assert_eq!(
+        serde_json::to_string(&command).unwrap(),
+        r#"{"Token":"<redacted>"}"#
+    );
+}
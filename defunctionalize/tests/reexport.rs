@@ -0,0 +1,28 @@
+use defunctionalize::defunctionalize;
+
+#[defunctionalize(fn(x: u32) -> u32, reexport)]
+pub mod operations {
+    pub fn double(x: u32) -> u32 {
+        x * 2
+    }
+}
+
+mod outer {
+    use defunctionalize::defunctionalize;
+
+    #[defunctionalize(fn(x: u32) -> u32, reexport)]
+    pub(crate) mod scaling {
+        pub fn triple(x: u32) -> u32 {
+            x * 3
+        }
+    }
+}
+
+#[test]
+fn enums_are_named_through_their_modules() {
+    assert_eq!(operations::Operations::Double.call(2), 4);
+    let command: Operations = operations::Operations::Double;
+    assert_eq!(command.call(3), 6);
+    assert_eq!(outer::scaling::Scaling::Triple.call(2), 6);
+    assert_eq!(outer::Scaling::Triple.call(1), 3);
+}
@@ -0,0 +1,80 @@
+#![deny(missing_docs)]
+//! Reflection is generated only when requested, and the generated items pass `missing_docs`.
+
+use defunctionalize::defunctionalize;
+
+/// Commands which can be inspected.
+#[defunctionalize(fn(x: u32) -> u32, reflect)]
+pub mod reflected {
+    /// Adds an amount.
+    ///
+    /// More detail.
+    pub fn add(amount: u32, x: u32) -> u32 {
+        x + amount
+    }
+
+    /// Does nothing.
+    pub fn r#move(x: u32) -> u32 {
+        x
+    }
+}
+
+/// Commands which cannot be inspected.
+#[defunctionalize(fn(x: u32) -> u32)]
+pub mod plain {
+    /// Adds an amount.
+    pub fn add(amount: u32, x: u32) -> u32 {
+        x + amount
+    }
+
+    /// Does nothing.
+    pub fn identity(x: u32) -> u32 {
+        x
+    }
+}
+
+/// Commands which exist only in tests.
+#[cfg(test)]
+#[defunctionalize(fn(x: u32) -> u32, reflect)]
+pub mod configured {
+    /// Does nothing.
+    pub fn identity(x: u32) -> u32 {
+        x
+    }
+}
+
+trait Name {
+    fn name(&self) -> &'static str {
+        "unreflected"
+    }
+}
+
+impl Name for Plain {}
+
+#[test]
+fn reflect_describes_commands() {
+    let add = Reflected::Add(1);
+    assert_eq!(add.name(), "add");
+    assert_eq!(add.arity(), 1);
+    assert_eq!(add.arg_names(), &["amount"]);
+    assert_eq!(add.description(), "Adds an amount.");
+    assert_eq!(Reflected::Move.name(), "move");
+    assert_eq!(Reflected::Move.arity(), 0);
+
+    assert_eq!(Reflected::SCHEMA.len(), 2);
+    assert_eq!(Reflected::SCHEMA[0].name, "add");
+    assert_eq!(Reflected::SCHEMA[0].arg_types, &["u32"]);
+    assert_eq!(Reflected::SCHEMA[1].index, 1);
+    assert_ne!(Reflected::FINGERPRINT, 0);
+}
+
+#[test]
+fn reflection_is_not_generated_without_the_option() {
+    // The trait's method would be shadowed by an inherent `name()`.
+    assert_eq!(Plain::Identity.name(), "unreflected");
+}
+
+#[test]
+fn module_cfg_applies_to_generated_items() {
+    assert_eq!(Configured::Identity.name(), "identity");
+}
@@ -0,0 +1,39 @@
+#![cfg(feature = "inventory")]
+
+use defunctionalize::{defunctionalize, register};
+
+#[defunctionalize(fn(x: u32) -> u32, registry)]
+pub mod arithmetic {
+    pub fn double(x: u32) -> u32 {
+        x * 2
+    }
+}
+
+// Such as a function in another crate, which the module cannot know about.
+mod plugin {
+    #[defunctionalize::register(crate::Arithmetic)]
+    fn triple(x: u32) -> u32 {
+        x * 3
+    }
+}
+
+#[register(Arithmetic)]
+fn square(x: u32) -> u32 {
+    x * x
+}
+
+#[test]
+fn registered_functions_are_called_by_name() {
+    assert_eq!(Arithmetic::call_registered("triple", 2), Some(6));
+    assert_eq!(Arithmetic::call_registered("square", 3), Some(9));
+    assert_eq!(Arithmetic::call_registered("double", 3), None);
+}
+
+#[test]
+fn registrations_are_listed() {
+    let mut names = Arithmetic::registrations()
+        .map(|registration| registration.name)
+        .collect::<Vec<_>>();
+    names.sort_unstable();
+    assert_eq!(names, ["square", "triple"]);
+}
@@ -0,0 +1,84 @@
+#![cfg(feature = "remote")]
+
+use defunctionalize::defunctionalize;
+use defunctionalize::remote::{serve, Client, ClientError, RemoteError, Response};
+use serde::{Deserialize, Serialize};
+use std::io::{BufReader, Cursor};
+use std::net::{TcpListener, TcpStream};
+use std::thread;
+
+#[defunctionalize(fn(base: u32) -> u32)]
+#[derive(Serialize, Deserialize)]
+mod arithmetic {
+    pub fn add(amount: u32, base: u32) -> u32 {
+        base + amount
+    }
+
+    pub fn double(base: u32) -> u32 {
+        base * 2
+    }
+}
+
+#[test]
+fn client_calls_commands_on_the_server() {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let address = listener.local_addr().unwrap();
+    let server = thread::spawn(move || {
+        let (stream, _) = listener.accept().unwrap();
+        let input = BufReader::new(stream.try_clone().unwrap());
+        serve::<Arithmetic, _, _, _>(input, stream, 10).unwrap();
+    });
+
+    let stream = TcpStream::connect(address).unwrap();
+    let mut client = Client::new(BufReader::new(stream.try_clone().unwrap()), stream);
+    assert_eq!(client.call::<_, u32>(&Arithmetic::Add(5)).unwrap(), 15);
+    assert_eq!(client.call::<_, u32>(&Arithmetic::Double).unwrap(), 20);
+    drop(client);
+    server.join().unwrap();
+}
+
+#[test]
+fn undecodable_commands_are_bad_requests() {
+    let input = Cursor::new(concat!(
+        r#"{"id":3,"command":"Halve"}"#,
+        "\n",
+        "not json\n",
+        "\n",
+        r#"{"id":4,"command":"Double"}"#,
+        "\n",
+    ));
+    let mut output = vec![];
+    serve::<Arithmetic, _, _, _>(input, &mut output, 1).unwrap();
+
+    let responses = String::from_utf8(output)
+        .unwrap()
+        .lines()
+        .map(|line| serde_json::from_str::<Response<u32>>(line).unwrap())
+        .collect::<Vec<_>>();
+    assert_eq!(responses.len(), 2);
+    assert_eq!(responses[0].id, 3);
+    assert!(matches!(
+        responses[0].result,
+        Err(RemoteError::BadRequest(..))
+    ));
+    assert_eq!(responses[1].id, 4);
+    assert_eq!(responses[1].result, Ok(2));
+}
+
+#[test]
+fn responses_to_other_requests_are_errors() {
+    let response = Response::<u32> {
+        id: 7,
+        result: Ok(1),
+    };
+    let mut input = serde_json::to_vec(&response).unwrap();
+    input.push(b'\n');
+    let mut client = Client::new(Cursor::new(input), vec![]);
+    assert!(matches!(
+        client.call::<_, u32>(&Arithmetic::Double),
+        Err(ClientError::Mismatched {
+            expected: 0,
+            found: 7
+        })
+    ));
+}
@@ -0,0 +1,67 @@
+use defunctionalize::defunctionalize;
+
+#[defunctionalize(fn(x: u64) -> u64, repr_c)]
+mod operation {
+    pub fn add(amount: u64, x: u64) -> u64 {
+        x + amount
+    }
+
+    pub fn clamp(min: u8, max: u32, x: u64) -> u64 {
+        x.max(min as u64).min(max as u64)
+    }
+
+    pub fn double(x: u64) -> u64 {
+        x * 2
+    }
+}
+
+/// Reads a value of the given type at `offset` bytes from the start of `command`, as C would.
+fn read_at<T: Copy>(command: &Operation, offset: usize) -> T {
+    unsafe { *((command as *const Operation as *const u8).add(offset) as *const T) }
+}
+
+#[test]
+fn payload_follows_the_tag() {
+    assert_eq!(Operation::PAYLOAD_OFFSET, 8);
+    assert_eq!(Operation::Add(1).tag(), 0);
+    assert_eq!(Operation::Clamp(1, 2).tag(), 1);
+    assert_eq!(Operation::Double.tag(), 2);
+    assert_eq!(Operation::Double.call(3), 6);
+}
+
+#[test]
+fn arguments_are_read_at_their_offsets() {
+    let add = Operation::Add(5);
+    assert_eq!(add.arg_offsets(), [8]);
+    assert_eq!(read_at::<u64>(&add, add.arg_offsets()[0]), 5);
+
+    let clamp = Operation::Clamp(3, 70_000);
+    assert_eq!(clamp.arg_offsets(), [8, 12]);
+    assert_eq!(read_at::<u8>(&clamp, clamp.arg_offsets()[0]), 3);
+    assert_eq!(read_at::<u32>(&clamp, clamp.arg_offsets()[1]), 70_000);
+
+    assert!(Operation::Double.arg_offsets().is_empty());
+}
+
+#[cfg(feature = "serde")]
+#[defunctionalize(fn(x: u32) -> u32, repr_c)]
+mod tagged {
+    #[defunctionalize(tag = 7)]
+    pub fn add(amount: u16, x: u32) -> u32 {
+        x + amount as u32
+    }
+
+    #[defunctionalize(tag = 3)]
+    pub fn double(x: u32) -> u32 {
+        x * 2
+    }
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn stable_tags_are_the_discriminants() {
+    assert_eq!(Tagged::PAYLOAD_OFFSET, 4);
+    assert_eq!(Tagged::Add(1).tag(), 7);
+    assert_eq!(Tagged::Double.tag(), 3);
+    assert_eq!(Tagged::Add(1).arg_offsets(), [4]);
+}
@@ -0,0 +1,33 @@
+use defunctionalize::{defunctionalize, DeFn};
+
+#[defunctionalize(fn(x: u32) -> u32)]
+#[derive(Debug)]
+mod operation {
+    pub fn add(amount: u32, x: u32) -> u32 {
+        x + amount
+    }
+}
+
+fn parse(input: &str) -> Result<Operation, String> {
+    match input.strip_prefix("add ") {
+        Some(amount) => amount
+            .parse()
+            .map(Operation::Add)
+            .map_err(|_| format!("bad amount `{}`", amount)),
+        None => Err(format!("unknown command `{}`", input)),
+    }
+}
+
+#[test]
+fn parsed_commands_are_called() {
+    assert_eq!(parse("add 2").call(3), Ok(5));
+}
+
+#[test]
+fn errors_are_passed_on() {
+    assert_eq!(
+        parse("sub 2").call(3),
+        Err("unknown command `sub 2`".to_owned())
+    );
+    assert_eq!(parse("add x").call(3), Err("bad amount `x`".to_owned()));
+}
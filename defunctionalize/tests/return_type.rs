@@ -0,0 +1,28 @@
+use defunctionalize::defunctionalize;
+
+pub type Res = Result<u32, String>;
+
+#[defunctionalize(fn(input: &str) -> Result<u32, String>)]
+mod parsers {
+    use super::Res;
+
+    pub fn decimal(input: &str) -> Res {
+        input
+            .parse()
+            .map_err(|_| format!("not a number: {}", input))
+    }
+
+    pub fn length(input: &str) -> Result<u32, String> {
+        Ok(input.len() as u32)
+    }
+}
+
+#[test]
+fn return_types_may_be_named_by_aliases() {
+    assert_eq!(Parsers::Decimal.call("12"), Ok(12));
+    assert_eq!(
+        Parsers::Decimal.call("x"),
+        Err("not a number: x".to_owned())
+    );
+    assert_eq!(Parsers::Length.call("four"), Ok(4));
+}
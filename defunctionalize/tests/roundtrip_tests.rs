@@ -0,0 +1,71 @@
+#![cfg(all(feature = "serde_json", feature = "postcard"))]
+
+//! The tests of this file are generated by the `roundtrip_tests` option.
+
+use defunctionalize::defunctionalize;
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, PartialEq, Debug, Default)]
+pub struct Point {
+    x: i32,
+    y: i32,
+}
+
+#[defunctionalize(fn(scale: i32) -> i32, roundtrip_tests)]
+#[derive(Serialize, Deserialize, PartialEq, Debug)]
+mod operation {
+    use super::Point;
+
+    pub fn length(point: Point, scale: i32) -> i32 {
+        (point.x.abs() + point.y.abs()) * scale
+    }
+
+    pub fn offset(amount: i32, #[defunctionalize(default = 3)] extra: i32, scale: i32) -> i32 {
+        (amount + extra) * scale
+    }
+
+    pub fn unit(scale: i32) -> i32 {
+        scale
+    }
+}
+
+#[defunctionalize(fn(scale: i32) -> i32, roundtrip_tests = tagged_tests)]
+#[derive(PartialEq, Debug)]
+mod tagged {
+    #[defunctionalize(tag = 1)]
+    pub fn label(name: String, scale: i32) -> i32 {
+        name.len() as i32 * scale
+    }
+}
+
+#[defunctionalize(fn(scale: i32) -> i32, roundtrip_tests, serde(tag = "type"))]
+#[derive(Serialize, Deserialize, PartialEq, Debug)]
+mod internal {
+    use super::Point;
+
+    pub fn moved(point: Point, scale: i32) -> i32 {
+        (point.x + point.y) * scale
+    }
+}
+
+mod shadowed {
+    use defunctionalize::defunctionalize;
+    use serde::{Deserialize, Serialize};
+
+    // The generated tests do not use this `assert_eq`, nor anything else of this module but the
+    // enum.
+    #[allow(unused_macros)]
+    macro_rules! assert_eq {
+        ($($tokens:tt)*) => {
+            panic!("the generated tests used a shadowed `assert_eq`")
+        };
+    }
+
+    #[defunctionalize(fn(scale: i32) -> i32, roundtrip_tests)]
+    #[derive(Serialize, Deserialize, PartialEq, Debug)]
+    mod operation {
+        pub fn scaled(amount: i32, scale: i32) -> i32 {
+            amount * scale
+        }
+    }
+}
@@ -0,0 +1,84 @@
+#![cfg(feature = "http")]
+
+use defunctionalize::defunctionalize;
+use defunctionalize::http::RouteError;
+
+#[defunctionalize(fn(items: &mut Vec<String>) -> usize)]
+#[derive(PartialEq, Debug)]
+mod items {
+    #[defunctionalize(route = "POST /items")]
+    pub fn create(name: String, items: &mut Vec<String>) -> usize {
+        items.push(name);
+        items.len()
+    }
+
+    #[defunctionalize(route = "PUT /items")]
+    #[allow(clippy::ptr_arg)]
+    pub fn rename(index: usize, name: String, items: &mut Vec<String>) -> usize {
+        items[index] = name;
+        items.len()
+    }
+
+    #[defunctionalize(route = "DELETE /items")]
+    pub fn clear(items: &mut Vec<String>) -> usize {
+        items.clear();
+        0
+    }
+
+    #[allow(clippy::ptr_arg)]
+    pub fn count(items: &mut Vec<String>) -> usize {
+        items.len()
+    }
+}
+
+#[test]
+fn lists_the_routes() {
+    let routes = Items::routes()
+        .iter()
+        .map(|route| (route.method, route.path))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        routes,
+        [("POST", "/items"), ("PUT", "/items"), ("DELETE", "/items")]
+    );
+}
+
+#[test]
+fn reads_commands_from_requests() {
+    assert_eq!(
+        Items::from_request("POST", "/items", br#""apple""#).unwrap(),
+        Items::Create("apple".to_owned())
+    );
+    assert_eq!(
+        Items::from_request("PUT", "/items", br#"[0, "pear"]"#).unwrap(),
+        Items::Rename(0, "pear".to_owned())
+    );
+    assert_eq!(
+        Items::from_request("DELETE", "/items", b"").unwrap(),
+        Items::Clear
+    );
+}
+
+#[test]
+fn dispatches_requests() {
+    let mut items = vec![];
+    let count = Items::dispatch_request("POST", "/items", br#""apple""#, &mut items).unwrap();
+    assert_eq!(count, 1);
+    assert_eq!(items, ["apple"]);
+}
+
+#[test]
+fn reports_unknown_routes_and_bad_bodies() {
+    assert!(matches!(
+        Items::from_request("GET", "/items", b""),
+        Err(RouteError::NotFound)
+    ));
+    assert!(matches!(
+        Items::from_request("POST", "/items/1", br#""apple""#),
+        Err(RouteError::NotFound)
+    ));
+    assert!(matches!(
+        Items::from_request("POST", "/items", b"7"),
+        Err(RouteError::BadRequest(..))
+    ));
+}
@@ -0,0 +1,64 @@
+use defunctionalize::defunctionalize;
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, PartialEq, Debug)]
+pub struct Amount {
+    value: u32,
+}
+
+#[defunctionalize(fn(x: u32) -> u32, serde(tag = "type"))]
+#[derive(Serialize, Deserialize, PartialEq, Debug)]
+mod internal {
+    use super::Amount;
+
+    pub fn add(amount: Amount, x: u32) -> u32 {
+        x + amount.value
+    }
+
+    pub fn double(x: u32) -> u32 {
+        x * 2
+    }
+}
+
+#[defunctionalize(fn(x: u32) -> u32, serde(tag = "type", content = "args"))]
+#[derive(Serialize, Deserialize, PartialEq, Debug)]
+mod adjacent {
+    pub fn add_both(a: u32, b: u32, x: u32) -> u32 {
+        x + a + b
+    }
+}
+
+#[defunctionalize(fn(x: u32) -> u32, serde(untagged))]
+#[derive(Serialize, PartialEq, Debug)]
+mod untagged {
+    pub fn add(amount: u32, x: u32) -> u32 {
+        x + amount
+    }
+}
+
+#[test]
+fn internally_tagged() {
+    let command = Internal::Add(Amount { value: 2 });
+    let json = serde_json::to_string(&command).unwrap();
+    assert_eq!(json, r#"{"type":"Add","value":2}"#);
+    assert_eq!(serde_json::from_str::<Internal>(&json).unwrap(), command);
+    assert_eq!(
+        serde_json::to_string(&Internal::Double).unwrap(),
+        r#"{"type":"Double"}"#
+    );
+}
+
+#[test]
+fn adjacently_tagged() {
+    let json = serde_json::to_string(&Adjacent::AddBoth(1, 2)).unwrap();
+    assert_eq!(json, r#"{"type":"AddBoth","args":[1,2]}"#);
+    assert_eq!(
+        serde_json::from_str::<Adjacent>(&json).unwrap(),
+        Adjacent::AddBoth(1, 2)
+    );
+}
+
+#[test]
+fn untagged() {
+    assert_eq!(serde_json::to_string(&Untagged::Add(3)).unwrap(), "3");
+}
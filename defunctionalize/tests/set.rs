@@ -0,0 +1,61 @@
+use defunctionalize::defunctionalize;
+
+#[defunctionalize(fn(x: u32) -> u32, set)]
+#[derive(Debug)]
+mod arithmetic {
+    pub fn double(x: u32) -> u32 {
+        x * 2
+    }
+
+    pub fn square(x: u32) -> u32 {
+        x * x
+    }
+
+    pub fn negate(x: u32) -> u32 {
+        !x
+    }
+}
+
+#[defunctionalize(fn(x: u32) -> u32, set = Toggles)]
+mod toggle {
+    pub fn on(x: u32) -> u32 {
+        x
+    }
+}
+
+#[test]
+fn inserts_and_removes_variants() {
+    let mut set = ArithmeticSet::new();
+    assert!(set.is_empty());
+    assert!(set.insert(Arithmetic::Square));
+    assert!(!set.insert(Arithmetic::Square));
+    assert!(set.insert(Arithmetic::Double));
+    assert_eq!(set.len(), 2);
+    assert!(set.contains(&Arithmetic::Double));
+    assert!(!set.contains(&Arithmetic::Negate));
+    assert!(set.remove(&Arithmetic::Double));
+    assert!(!set.remove(&Arithmetic::Double));
+    assert_eq!(set.len(), 1);
+    set.clear();
+    assert!(set.is_empty());
+}
+
+#[test]
+fn iterates_in_order_of_the_functions() {
+    let set = vec![Arithmetic::Negate, Arithmetic::Double]
+        .into_iter()
+        .collect::<ArithmeticSet>();
+    assert_eq!(
+        set.iter().collect::<Vec<_>>(),
+        [Arithmetic::Double, Arithmetic::Negate]
+    );
+    assert_eq!(set, set.clone());
+    assert_eq!(ArithmeticSet::default(), ArithmeticSet::new());
+}
+
+#[test]
+fn set_can_be_named() {
+    let mut set = Toggles::new();
+    set.insert(Toggle::On);
+    assert!(set.contains(&Toggle::On));
+}
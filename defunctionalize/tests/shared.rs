@@ -0,0 +1,38 @@
+use defunctionalize::{defunctionalize, DeFn, Shared};
+use std::sync::Arc;
+use std::thread;
+
+#[defunctionalize(fn(x: u32) -> u32)]
+#[derive(Clone, Debug, PartialEq)]
+mod operation {
+    pub fn lookup(table: Vec<u32>, x: u32) -> u32 {
+        table[x as usize]
+    }
+}
+
+#[test]
+fn shared_commands_are_called_on_many_threads() {
+    let command = Shared::new(Operation::Lookup(vec![10, 20, 30]));
+    let handles: Vec<_> = (0..3)
+        .map(|x| {
+            let command = command.clone();
+            thread::spawn(move || command.call(x))
+        })
+        .collect();
+    let outputs: Vec<_> = handles
+        .into_iter()
+        .map(|handle| handle.join().unwrap())
+        .collect();
+    assert_eq!(outputs, [10, 20, 30]);
+    assert_eq!(Arc::strong_count(&command.0), 1);
+    assert_eq!(*command, Operation::Lookup(vec![10, 20, 30]));
+    assert_eq!(command.call(1), 20);
+}
+
+#[test]
+fn commands_are_converted_into_shared_commands() {
+    let command: Shared<Operation> = Operation::Lookup(vec![1]).into();
+    let other = command.clone();
+    assert_eq!(command.call(0), 1);
+    assert_eq!(other.call(0), 1);
+}
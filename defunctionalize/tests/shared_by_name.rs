@@ -0,0 +1,40 @@
+use defunctionalize::{defunctionalize, DeFn};
+
+pub struct Ctx {
+    base: u32,
+}
+
+pub struct Log {
+    prefix: &'static str,
+}
+
+#[defunctionalize(fn(ctx: &Ctx, log: &Log) -> String, shared_by_name)]
+mod command {
+    use super::{Ctx, Log};
+
+    pub fn full(amount: u32, ctx: &Ctx, log: &Log) -> String {
+        format!("{}{}", log.prefix, ctx.base + amount)
+    }
+
+    pub fn context_only(amount: u32, ctx: &Ctx) -> String {
+        (ctx.base + amount).to_string()
+    }
+
+    pub fn unused_context(_ctx: &Ctx) -> String {
+        "ignored".to_owned()
+    }
+
+    pub fn constant(value: u32) -> String {
+        value.to_string()
+    }
+}
+
+#[test]
+fn functions_take_the_arguments_they_name() {
+    let ctx = Ctx { base: 10 };
+    let log = Log { prefix: "> " };
+    assert_eq!(Command::Full(1).call(&ctx, &log), "> 11");
+    assert_eq!(Command::ContextOnly(2).call(&ctx, &log), "12");
+    assert_eq!(Command::UnusedContext.call(&ctx, &log), "ignored");
+    assert_eq!(DeFn::call(Command::Constant(7), (&ctx, &log)), "7");
+}
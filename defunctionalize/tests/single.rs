@@ -0,0 +1,24 @@
+use defunctionalize::{defunctionalize_fn, DeFn};
+
+#[defunctionalize_fn(fn(x: u32, y: u32) -> u32)]
+pub fn add_plus_n(n: u32, x: u32, y: u32) -> u32 {
+    x + y + n
+}
+
+#[defunctionalize_fn(fn Doubler(x: u32) -> u32)]
+pub fn double(x: u32) -> u32 {
+    x * 2
+}
+
+#[test]
+fn struct_holds_the_extra_arguments() {
+    assert_eq!(AddPlusN(1).call(2, 3), 6);
+    assert_eq!(DeFn::call(AddPlusN(1), (2, 3)), 6);
+}
+
+#[test]
+fn struct_can_be_named() {
+    assert_eq!(Doubler.call(4), 8);
+    // The function itself is left as it was.
+    assert_eq!(double(4), 8);
+}
@@ -0,0 +1,40 @@
+use defunctionalize::defunctionalize;
+use std::collections::HashSet;
+
+#[defunctionalize(fn(count: &mut u32))]
+#[derive(PartialEq, Eq, Hash, Debug)]
+mod task {
+    pub fn refresh(
+        key: &'static str,
+        #[defunctionalize(skip_eq)] requested_at: u64,
+        count: &mut u32,
+    ) {
+        let _ = (key, requested_at);
+        *count += 1;
+    }
+
+    pub fn flush(count: &mut u32) {
+        *count = 0;
+    }
+}
+
+#[test]
+fn skipped_arguments_are_not_compared() {
+    assert_eq!(Task::Refresh("users", 1), Task::Refresh("users", 2));
+    assert_ne!(Task::Refresh("users", 1), Task::Refresh("posts", 1));
+    assert_ne!(Task::Refresh("users", 1), Task::Flush);
+}
+
+#[test]
+fn skipped_arguments_are_not_hashed() {
+    let queued: HashSet<_> = vec![
+        Task::Refresh("users", 1),
+        Task::Refresh("users", 2),
+        Task::Refresh("posts", 3),
+        Task::Flush,
+        Task::Flush,
+    ]
+    .into_iter()
+    .collect();
+    assert_eq!(queued.len(), 3);
+}
@@ -0,0 +1,70 @@
+#![cfg(feature = "serde")]
+
+use defunctionalize::{defunctionalize, StableHasher};
+use std::hash::{Hash, Hasher};
+
+#[defunctionalize(fn(x: u64) -> u64, stable_hash)]
+#[derive(PartialEq, Eq)]
+mod before {
+    #[defunctionalize(tag = 1)]
+    pub fn add(amount: u64, label: String, x: u64) -> u64 {
+        let _ = label;
+        x + amount
+    }
+
+    #[defunctionalize(tag = 2)]
+    pub fn double(x: u64) -> u64 {
+        x * 2
+    }
+}
+
+// The same functions, reordered, with another added before them.
+#[defunctionalize(fn(x: u64) -> u64, stable_hash)]
+mod after {
+    #[defunctionalize(tag = 3)]
+    pub fn negate(x: u64) -> u64 {
+        !x
+    }
+
+    #[defunctionalize(tag = 2)]
+    pub fn double(x: u64) -> u64 {
+        x * 2
+    }
+
+    #[defunctionalize(tag = 1)]
+    pub fn add(
+        amount: u64,
+        label: String,
+        #[defunctionalize(skip_eq)] requested_at: u64,
+        x: u64,
+    ) -> u64 {
+        let _ = (label, requested_at);
+        x + amount
+    }
+}
+
+#[test]
+fn hashes_do_not_depend_on_the_order_of_functions() {
+    assert_eq!(Before::Double.stable_hash(), After::Double.stable_hash());
+    assert_eq!(
+        Before::Add(1, "one".to_owned()).stable_hash(),
+        After::Add(1, "one".to_owned(), 12345).stable_hash(),
+    );
+    assert_ne!(
+        Before::Add(1, "one".to_owned()).stable_hash(),
+        Before::Add(2, "one".to_owned()).stable_hash()
+    );
+}
+
+#[test]
+fn hashes_are_stable_across_builds() {
+    // Hashes may be stored, so must not change between platforms or compilers.
+    assert_eq!(Before::Double.stable_hash(), 10_167_666_227_858_214_167);
+    let mut hasher = StableHasher::new();
+    Before::Double.hash(&mut hasher);
+    assert_eq!(hasher.finish(), Before::Double.stable_hash());
+    assert_eq!(
+        defunctionalize::stable_hash(&Before::Double),
+        Before::Double.stable_hash()
+    );
+}
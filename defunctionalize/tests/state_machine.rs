@@ -0,0 +1,52 @@
+use defunctionalize::defunctionalize;
+
+#[defunctionalize(fn Light(elapsed: u32) -> Light)]
+#[derive(PartialEq, Debug)]
+mod light {
+    // The enum is imported into the module, so its functions can return the next state.
+    pub fn red(elapsed: u32) -> Light {
+        if elapsed >= 30 {
+            Light::Green
+        } else {
+            Light::Red
+        }
+    }
+
+    pub fn green(elapsed: u32) -> Light {
+        if elapsed >= 25 {
+            Light::Yellow(0)
+        } else {
+            Light::Green
+        }
+    }
+
+    pub fn yellow(ticks: u32, elapsed: u32) -> self::Light {
+        if ticks + elapsed >= 5 {
+            Light::Red
+        } else {
+            Light::Yellow(ticks + elapsed)
+        }
+    }
+}
+
+#[test]
+fn functions_return_the_next_state() {
+    let mut state = Light::Red;
+    for elapsed in [10, 30, 25, 3, 3] {
+        state = state.call(elapsed);
+    }
+    assert_eq!(state, Light::Red);
+    assert_eq!(Light::Yellow(1).call(1), Light::Yellow(2));
+}
+
+#[test]
+fn modules_in_function_bodies_do_not_import_the_enum() {
+    #[defunctionalize(fn Local(x: u32) -> u32)]
+    mod local {
+        pub fn double(x: u32) -> u32 {
+            x * 2
+        }
+    }
+
+    assert_eq!(Local::Double.call(2), 4);
+}
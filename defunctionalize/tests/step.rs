@@ -0,0 +1,38 @@
+use defunctionalize::{defunctionalize, run_to_completion, DeFn, Step};
+
+pub struct Ctx {
+    limit: u32,
+}
+
+#[defunctionalize(fn Count(ctx: &Ctx) -> Step<Count, u32>)]
+#[derive(PartialEq, Debug)]
+pub mod count {
+    use super::Ctx;
+    use defunctionalize::Step;
+
+    pub fn up(n: u32, ctx: &Ctx) -> Step<Count, u32> {
+        if n < ctx.limit {
+            Step::Continue(Count::Up(n + 1))
+        } else {
+            Step::Done(n)
+        }
+    }
+
+    pub fn stop(ctx: &Ctx) -> Step<Count, u32> {
+        Step::Done(ctx.limit)
+    }
+}
+
+#[test]
+fn runs_until_a_command_is_done() {
+    let ctx = Ctx { limit: 5 };
+    assert_eq!(run_to_completion(Count::Up(0), &ctx), 5);
+    assert_eq!(run_to_completion(Count::Stop, &ctx), 5);
+}
+
+#[test]
+fn each_call_takes_one_step() {
+    let ctx = Ctx { limit: 5 };
+    assert_eq!(DeFn::call(Count::Up(4), &ctx), Step::Continue(Count::Up(5)));
+    assert_eq!(DeFn::call(Count::Up(5), &ctx), Step::Done(5));
+}
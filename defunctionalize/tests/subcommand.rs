@@ -0,0 +1,50 @@
+#![cfg(feature = "clap")]
+
+use clap::{Command, FromArgMatches, Subcommand};
+use defunctionalize::defunctionalize;
+
+#[defunctionalize(fn(x: u32) -> u32, subcommand)]
+#[derive(PartialEq, Debug)]
+mod arithmetic {
+    /// Adds two amounts.
+    pub fn add_both(first: u32, second: u32, x: u32) -> u32 {
+        x + first + second
+    }
+
+    /// Doubles the input.
+    pub fn double(x: u32) -> u32 {
+        x * 2
+    }
+}
+
+fn command() -> Command {
+    Arithmetic::augment_subcommands(Command::new("calc").subcommand_required(true))
+}
+
+fn parse(args: &[&str]) -> Result<Arithmetic, clap::Error> {
+    let matches = command().try_get_matches_from(args)?;
+    Arithmetic::from_arg_matches(&matches)
+}
+
+#[test]
+fn parses_subcommands_with_flags() {
+    assert_eq!(
+        parse(&["calc", "add-both", "--first", "1", "--second", "2"]).unwrap(),
+        Arithmetic::AddBoth(1, 2)
+    );
+    assert_eq!(parse(&["calc", "double"]).unwrap(), Arithmetic::Double);
+}
+
+#[test]
+fn flags_are_required() {
+    assert!(parse(&["calc", "add-both", "--first", "1"]).is_err());
+    assert!(parse(&["calc", "halve"]).is_err());
+}
+
+#[test]
+fn doc_comments_are_help() {
+    let help = command().render_long_help().to_string();
+    assert!(help.contains("add-both"));
+    assert!(help.contains("Adds two amounts."));
+    assert!(Arithmetic::has_subcommand("double"));
+}
@@ -0,0 +1,60 @@
+#![cfg(feature = "serde")]
+
+use defunctionalize::defunctionalize;
+
+#[defunctionalize(fn(x: u32) -> u32)]
+#[derive(PartialEq, Debug)]
+mod before {
+    #[defunctionalize(tag = 7)]
+    pub fn add(amount: u32, x: u32) -> u32 {
+        x + amount
+    }
+
+    #[defunctionalize(tag = 3)]
+    pub fn double(x: u32) -> u32 {
+        x * 2
+    }
+}
+
+// The same functions, reordered, with one removed and another added.
+#[defunctionalize(fn(x: u32) -> u32)]
+#[derive(PartialEq, Debug)]
+mod after {
+    #[defunctionalize(tag = 9)]
+    pub fn negate(x: u32) -> u32 {
+        !x
+    }
+
+    #[defunctionalize(tag = 7)]
+    pub fn add(amount: u32, x: u32) -> u32 {
+        x + amount
+    }
+}
+
+#[test]
+fn binary_formats_identify_cases_by_tag() {
+    let bytes = postcard::to_allocvec(&Before::Add(5)).unwrap();
+    assert_eq!(bytes, [7, 5]);
+    assert_eq!(postcard::to_allocvec(&Before::Double).unwrap(), [3]);
+    assert_eq!(
+        postcard::from_bytes::<After>(&bytes).unwrap(),
+        After::Add(5)
+    );
+}
+
+#[test]
+fn unknown_tags_are_errors() {
+    let bytes = postcard::to_allocvec(&Before::Double).unwrap();
+    assert!(postcard::from_bytes::<After>(&bytes).is_err());
+}
+
+#[test]
+fn self_describing_formats_identify_cases_by_name() {
+    let json = serde_json::to_string(&Before::Add(5)).unwrap();
+    assert_eq!(json, r#"{"Add":5}"#);
+    assert_eq!(serde_json::from_str::<After>(&json).unwrap(), After::Add(5));
+    assert_eq!(
+        serde_json::to_string(&Before::Double).unwrap(),
+        r#""Double""#
+    );
+}
@@ -0,0 +1,48 @@
+#![cfg(feature = "tokio")]
+
+use defunctionalize::defunctionalize;
+use std::sync::{Arc, Mutex};
+
+pub type Log = Arc<Mutex<Vec<String>>>;
+
+#[defunctionalize(async fn(x: u32) -> u32, send)]
+mod operation {
+    pub async fn add(amount: u32, x: u32) -> u32 {
+        tokio::task::yield_now().await;
+        x + amount
+    }
+}
+
+#[defunctionalize(async fn(log: Log), send)]
+mod task {
+    use super::Log;
+
+    pub async fn record(message: &'static str, log: Log) {
+        tokio::task::yield_now().await;
+        log.lock().unwrap().push(message.to_owned());
+    }
+}
+
+#[tokio::test]
+async fn commands_are_spawned_as_tasks() {
+    let handles: Vec<_> = (0..3)
+        .map(|amount| defunctionalize::tokio::spawn(Operation::Add(amount), 10))
+        .collect();
+    let mut outputs = vec![];
+    for handle in handles {
+        outputs.push(handle.await.unwrap());
+    }
+    assert_eq!(outputs, [10, 11, 12]);
+}
+
+#[tokio::test]
+async fn looping_tasks_call_commands_in_order() {
+    let log = Log::default();
+    let (sender, handle) = defunctionalize::tokio::spawn_loop(2, log.clone());
+    for message in ["first", "second", "third"] {
+        sender.send(Task::Record(message)).await.unwrap();
+    }
+    drop(sender);
+    handle.await.unwrap();
+    assert_eq!(*log.lock().unwrap(), ["first", "second", "third"]);
+}
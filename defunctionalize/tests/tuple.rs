@@ -0,0 +1,53 @@
+use defunctionalize::{defunctionalize, DeFn, Tupled, Untupled};
+
+#[defunctionalize(fn(x: u32) -> u32)]
+mod operation {
+    pub fn add(amount: u32, x: u32) -> u32 {
+        x + amount
+    }
+}
+
+#[defunctionalize(fn<T>(x: T) -> T)]
+mod identity {
+    pub fn same<T>(x: T) -> T {
+        x
+    }
+}
+
+fn call_tupled<D: DeFn<(u32,), Output = u32>>(command: D, x: u32) -> u32 {
+    command.call((x,))
+}
+
+fn call_untupled<D: DeFn<u32, Output = u32>>(command: D, x: u32) -> u32 {
+    command.call(x)
+}
+
+/// A command which only takes its input as a 1-tuple.
+struct Double;
+
+impl DeFn<(u32,)> for Double {
+    type Output = u32;
+
+    fn call(self, (x,): (u32,)) -> u32 {
+        x * 2
+    }
+}
+
+#[test]
+fn single_inputs_are_taken_as_tuples() {
+    assert_eq!(call_tupled(Operation::Add(1), 2), 3);
+    assert_eq!(call_untupled(Operation::Add(1), 2), 3);
+}
+
+#[test]
+fn generic_single_inputs_are_taken_directly() {
+    assert_eq!(DeFn::call(Identity::Same, (1u32,)), (1,));
+    assert_eq!(call_tupled(Tupled(Identity::Same), 4), 4);
+}
+
+#[test]
+fn adapters_convert_between_forms() {
+    assert_eq!(call_tupled(Tupled(Operation::Add(1)), 2), 3);
+    assert_eq!(call_untupled(Untupled(Double), 2), 4);
+    assert_eq!(call_tupled(Tupled(Untupled(Double)), 3), 6);
+}
@@ -0,0 +1,15 @@
+use defunctionalize::defunctionalize;
+
+pub struct Token;
+
+#[defunctionalize(fn(x: u32) -> u32, auto_clone)]
+mod commands {
+    use super::Token;
+
+    pub fn spend(token: Token, x: u32) -> u32 {
+        let _ = token;
+        x
+    }
+}
+
+fn main() {}
@@ -0,0 +1,19 @@
+use defunctionalize::defunctionalize;
+
+#[defunctionalize(fn(x: u32) -> u32, reflect)]
+mod operation {
+    pub fn call(amount: u32, #[defunctionalize(default = 1)] times: u32, x: u32) -> u32 {
+        x + amount * times
+    }
+
+    pub fn name(scale: u32, #[defunctionalize(default = 2)] offset: u32, x: u32) -> u32 {
+        x * scale + offset
+    }
+
+    // Without `priority`, nothing else is named `priority`.
+    pub fn priority(level: u32, #[defunctionalize(default = 0)] offset: u32, x: u32) -> u32 {
+        x + level + offset
+    }
+}
+
+fn main() {}
@@ -0,0 +1,16 @@
+use defunctionalize::defunctionalize;
+
+#[defunctionalize(fn(x: u32) -> u32)]
+mod operation {
+    #[defunctionalize(default)]
+    pub fn double(x: u32) -> u32 {
+        x * 2
+    }
+
+    #[defunctionalize(default)]
+    pub fn triple(x: u32) -> u32 {
+        x * 3
+    }
+}
+
+fn main() {}
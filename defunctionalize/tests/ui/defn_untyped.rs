@@ -0,0 +1,5 @@
+use defunctionalize::defn;
+
+fn main() {
+    let _command = defn!(|x, y: u32| -> u32 { x + y });
+}
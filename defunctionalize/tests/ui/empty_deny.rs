@@ -0,0 +1,10 @@
+use defunctionalize::defunctionalize;
+
+#[defunctionalize(fn(x: u32) -> u32, empty = deny)]
+mod operation {
+    fn double(x: u32) -> u32 {
+        x * 2
+    }
+}
+
+fn main() {}
@@ -0,0 +1,12 @@
+#![deny(warnings)]
+
+use defunctionalize::defunctionalize;
+
+#[defunctionalize(fn(x: u32) -> u32)]
+mod operation {
+    fn double(x: u32) -> u32 {
+        x * 2
+    }
+}
+
+fn main() {}
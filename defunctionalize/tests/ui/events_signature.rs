@@ -0,0 +1,11 @@
+use defunctionalize::defunctionalize;
+
+#[defunctionalize(fn(total: &mut u32) -> u32, events)]
+mod counter {
+    pub fn increment(total: &mut u32) -> u32 {
+        *total += 1;
+        *total
+    }
+}
+
+fn main() {}
@@ -0,0 +1,12 @@
+use defunctionalize::defunctionalize;
+
+#[defunctionalize(fn(x: u32) -> u32)]
+mod operation {
+    #[defunctionalize(priority = 1)]
+    #[defunctionalize(priority = 2)]
+    pub fn double(x: u32) -> u32 {
+        x * 2
+    }
+}
+
+fn main() {}
@@ -0,0 +1,15 @@
+use defunctionalize::defunctionalize;
+
+#[defunctionalize(fn(x: u32) -> u32)]
+mod operation {
+    pub fn double(x: u32) -> u32 {
+        halve(x) * 4
+    }
+
+    #[defunctionalize(priority = 1)]
+    fn halve(x: u32) -> u32 {
+        x / 2
+    }
+}
+
+fn main() {}
@@ -0,0 +1,10 @@
+use defunctionalize::defunctionalize;
+
+#[defunctionalize(fn(x: u32) -> u32, max_size = 8)]
+mod operation {
+    pub fn lookup(table: [u32; 4], x: u32) -> u32 {
+        table[x as usize % 4]
+    }
+}
+
+fn main() {}
@@ -0,0 +1,10 @@
+use defunctionalize::defunctionalize;
+
+#[defunctionalize(fn(x: u32) -> u32, max_size = 8)]
+mod operation {
+    pub fn between(min: u64, max: u64, x: u32) -> u32 {
+        (x as u64).max(min).min(max) as u32
+    }
+}
+
+fn main() {}
@@ -0,0 +1,16 @@
+use defunctionalize::defunctionalize;
+
+#[defunctionalize(fn(x: u32) -> u32, merge(inner))]
+mod arithmetic {
+    pub fn double(x: u32) -> u32 {
+        x * 2
+    }
+
+    pub mod inner {
+        pub fn double(x: u32) -> u32 {
+            x + x
+        }
+    }
+}
+
+fn main() {}
@@ -0,0 +1,15 @@
+use defunctionalize::defunctionalize;
+
+pub struct Context;
+pub struct Config;
+
+#[defunctionalize(fn(ctx: &Context) -> u32)]
+mod commands {
+    use super::*;
+
+    pub fn configure(level: u32, _ctx: &Config) -> u32 {
+        level
+    }
+}
+
+fn main() {}
@@ -0,0 +1,10 @@
+use defunctionalize::defunctionalize;
+
+#[defunctionalize(async fn(x: u32) -> u32)]
+mod commands {
+    pub async fn widen(scale: u32, x: u32) -> i64 {
+        (scale * x) as i64
+    }
+}
+
+fn main() {}
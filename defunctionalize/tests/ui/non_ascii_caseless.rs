@@ -0,0 +1,10 @@
+use defunctionalize::defunctionalize;
+
+#[defunctionalize(fn(x: u32) -> u32)]
+mod 計算 {
+    pub fn 倍(x: u32) -> u32 {
+        x * 2
+    }
+}
+
+fn main() {}
@@ -0,0 +1,20 @@
+use defunctionalize::defunctionalize;
+
+pub struct Ctx;
+
+#[defunctionalize(fn(ctx: _) -> _, partition)]
+mod handlers {
+    use super::Ctx;
+
+    pub fn count(ctx: &Ctx) -> usize {
+        let _ = ctx;
+        0
+    }
+
+    pub fn name(ctx: &Ctx) -> String {
+        let _ = ctx;
+        String::new()
+    }
+}
+
+fn main() {}
@@ -0,0 +1,10 @@
+use defunctionalize::defunctionalize;
+
+#[defunctionalize(fn(x: u32) -> u32)]
+mod operation {
+    pub fn add(self, x: u32) -> u32 {
+        x
+    }
+}
+
+fn main() {}
@@ -0,0 +1,16 @@
+use defunctionalize::defunctionalize;
+
+#[defunctionalize(fn(x: u32) -> u32)]
+mod commands {
+    #[defunctionalize(route = "POST /items")]
+    pub fn create(x: u32) -> u32 {
+        x
+    }
+
+    #[defunctionalize(route = "POST /items")]
+    pub fn replace(x: u32) -> u32 {
+        x
+    }
+}
+
+fn main() {}
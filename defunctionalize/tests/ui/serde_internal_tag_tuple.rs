@@ -0,0 +1,10 @@
+use defunctionalize::defunctionalize;
+
+#[defunctionalize(fn(x: u32) -> u32, serde(tag = "type"))]
+mod commands {
+    pub fn add_both(a: u32, b: u32, x: u32) -> u32 {
+        x + a + b
+    }
+}
+
+fn main() {}
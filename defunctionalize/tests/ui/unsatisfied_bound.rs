@@ -0,0 +1,14 @@
+use defunctionalize::defunctionalize;
+
+#[defunctionalize(fn<T>(x: T) -> T)]
+mod operation {
+    pub fn same<T>(x: T) -> T {
+        x
+    }
+
+    pub fn duplicate<T: Clone>(x: T) -> T {
+        x.clone()
+    }
+}
+
+fn main() {}
@@ -0,0 +1,10 @@
+use defunctionalize::defunctionalize;
+
+#[defunctionalize(fn(x: u32) -> u32)]
+mod operation {
+    pub unsafe extern "C" fn add(x: u32, _: ...) -> u32 {
+        x
+    }
+}
+
+fn main() {}
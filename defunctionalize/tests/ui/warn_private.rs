@@ -0,0 +1,16 @@
+#![deny(warnings)]
+
+use defunctionalize::defunctionalize;
+
+#[defunctionalize(fn(x: u32) -> u32, warn_private)]
+mod operation {
+    pub fn double(x: u32) -> u32 {
+        halve(x) * 4
+    }
+
+    fn halve(x: u32) -> u32 {
+        x / 2
+    }
+}
+
+fn main() {}
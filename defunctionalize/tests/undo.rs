@@ -0,0 +1,66 @@
+use defunctionalize::{defunctionalize, History, Invert};
+
+#[defunctionalize(fn(items: &mut Vec<u32>))]
+#[derive(Clone, PartialEq, Debug)]
+mod edit {
+    #[defunctionalize(undo = remove_item)]
+    pub fn add_item(item: u32, items: &mut Vec<u32>) {
+        items.push(item);
+    }
+
+    #[defunctionalize(undo = add_item)]
+    pub fn remove_item(item: u32, items: &mut Vec<u32>) {
+        items.retain(|other| *other != item);
+    }
+
+    pub fn clear(items: &mut Vec<u32>) {
+        items.clear();
+    }
+}
+
+#[test]
+fn commands_are_inverted_with_the_same_arguments() {
+    assert_eq!(Edit::AddItem(1).invert(), Some(Edit::RemoveItem(1)));
+    assert_eq!(Invert::invert(Edit::RemoveItem(2)), Some(Edit::AddItem(2)));
+    assert_eq!(Edit::Clear.invert(), None);
+}
+
+#[test]
+fn history_undoes_and_redoes_commands() {
+    let mut items = vec![];
+    let mut history = History::new();
+    history.apply(Edit::AddItem(1), &mut items);
+    history.apply(Edit::AddItem(2), &mut items);
+    assert_eq!(items, [1, 2]);
+
+    assert!(history.can_undo());
+    assert_eq!(history.undo(&mut items), Some(()));
+    assert_eq!(items, [1]);
+    assert!(history.can_redo());
+    assert_eq!(history.redo(&mut items), Some(()));
+    assert_eq!(items, [1, 2]);
+    assert_eq!(history.redo(&mut items), None);
+}
+
+#[test]
+fn applying_a_command_forgets_undone_commands() {
+    let mut items = vec![];
+    let mut history = History::new();
+    history.apply(Edit::AddItem(1), &mut items);
+    history.undo(&mut items);
+    history.apply(Edit::AddItem(3), &mut items);
+    assert!(!history.can_redo());
+    assert_eq!(items, [3]);
+}
+
+#[test]
+fn commands_without_an_inverse_are_not_undone() {
+    let mut items = vec![1];
+    let mut history = History::default();
+    history.apply(Edit::Clear, &mut items);
+    assert!(!history.can_undo());
+    assert_eq!(history.undo(&mut items), None);
+    assert!(items.is_empty());
+    history.clear();
+    assert!(!history.can_redo());
+}
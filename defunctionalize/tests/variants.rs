@@ -0,0 +1,31 @@
+use defunctionalize::defunctionalize;
+
+#[defunctionalize(fn(x: u32) -> u32)]
+#[derive(Debug)]
+mod arithmetic {
+    pub fn double(x: u32) -> u32 {
+        x * 2
+    }
+
+    #[cfg(any())]
+    pub fn never(x: u32) -> u32 {
+        x
+    }
+
+    pub fn square(x: u32) -> u32 {
+        x * x
+    }
+}
+
+#[test]
+fn variants_lists_every_case_in_order() {
+    assert_eq!(
+        Arithmetic::variants(),
+        &[Arithmetic::Double, Arithmetic::Square]
+    );
+    let outputs = Arithmetic::variants()
+        .iter()
+        .map(|command| command.call(3))
+        .collect::<Vec<_>>();
+    assert_eq!(outputs, [6, 9]);
+}
@@ -0,0 +1,62 @@
+#![cfg(feature = "serde")]
+
+use defunctionalize::{decode_versioned, defunctionalize, DecodeError, SchemaVersion, Versioned};
+use serde::{Deserialize, Serialize};
+
+#[defunctionalize(fn(x: u32) -> u32, version = 3)]
+#[derive(Serialize, Deserialize, PartialEq, Debug)]
+mod operation {
+    pub fn add(amount: u32, x: u32) -> u32 {
+        x + amount
+    }
+}
+
+fn decode(json: &str) -> Result<Operation, DecodeError<serde_json::Error>> {
+    decode_versioned(&mut serde_json::Deserializer::from_str(json))
+}
+
+#[test]
+fn commands_are_serialized_with_their_version() {
+    assert_eq!(Operation::schema_version(), 3);
+    assert_eq!(<Operation as SchemaVersion>::SCHEMA_VERSION, 3);
+    let json = serde_json::to_string(&Versioned(&Operation::Add(1))).unwrap();
+    assert_eq!(json, r#"{"version":3,"command":{"Add":1}}"#);
+    assert_eq!(decode(&json).unwrap(), Operation::Add(1));
+    let versioned: Versioned<Operation> = serde_json::from_str(&json).unwrap();
+    assert_eq!(versioned, Versioned(Operation::Add(1)));
+}
+
+#[test]
+fn other_versions_are_told_apart_from_corrupt_payloads() {
+    assert!(matches!(
+        decode(r#"{"version":2,"command":{"Add":1}}"#),
+        Err(DecodeError::UnknownVersion {
+            found: 2,
+            expected: 3
+        }),
+    ));
+    assert!(matches!(
+        decode(r#"{"version":3,"command":{"Sub":1}}"#),
+        Err(DecodeError::Corrupt(..)),
+    ));
+    assert!(
+        serde_json::from_str::<Versioned<Operation>>(r#"{"version":2,"command":{"Add":1}}"#)
+            .is_err()
+    );
+}
+
+#[test]
+fn versioned_commands_are_read_from_sequences() {
+    let bytes = postcard::to_allocvec(&Versioned(Operation::Add(5))).unwrap();
+    assert_eq!(bytes, [3, 0, 5]);
+    let mut deserializer = postcard::Deserializer::from_bytes(&bytes);
+    assert_eq!(
+        decode_versioned::<Operation, _>(&mut deserializer).unwrap(),
+        Operation::Add(5)
+    );
+    let mut deserializer = postcard::Deserializer::from_bytes(&[4, 0, 5]);
+    assert!(matches!(
+        decode_versioned::<Operation, _>(&mut deserializer),
+        Err(DecodeError::UnknownVersion { found: 4, .. }),
+    ));
+}
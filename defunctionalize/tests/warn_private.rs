@@ -0,0 +1,20 @@
+#![deny(warnings)]
+
+use defunctionalize::defunctionalize;
+
+#[defunctionalize(fn(x: u32) -> u32, warn_private)]
+mod operation {
+    pub fn double(x: u32) -> u32 {
+        x * 2
+    }
+
+    pub fn triple(x: u32) -> u32 {
+        x * 3
+    }
+}
+
+#[test]
+fn modules_of_public_functions_are_not_warned_about() {
+    assert_eq!(Operation::Double.call(2), 4);
+    assert_eq!(Operation::Triple.call(2), 6);
+}